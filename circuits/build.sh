@@ -7,6 +7,7 @@ COMMON_DIR="common"
 
 CIRCUIT_FILES=(
  "shuffle_encrypt/shuffle_encrypt"
+  "shuffle_encrypt/shuffle_encrypt_short_deck"
   "decrypt/decrypt"
   "test/test_elgamal_encrypt"
   "test/test_elgamal_decrypt"
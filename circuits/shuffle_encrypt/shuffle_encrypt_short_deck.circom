@@ -0,0 +1,6 @@
+pragma circom 2.1.6;
+
+include "shuffle_encrypt_template.circom";
+
+// Shuffle of the 36-card short deck, six up to the ace
+component main {public [pk, nonce, original, permuted]} = ShuffleEncrypt(36);
@@ -171,6 +171,7 @@ describe("ShuffleEncrypt", function () {
         const shuffled = permuteMatrix(encrypted, permutation);
         const input = {
             pk: [pk.X.toString(), pk.Y.toString(), pk.Z.toString()],
+            nonce: "1",
             R: rScalars.map(r => r.toString()),
             original: deck.map((row) => row.map((v) => v.toString())),
             permuted: shuffled.map((row) => row.map((v) => v.toString())),
@@ -219,6 +219,7 @@ function toAffine(F: any, P: { X: bigint, Y: bigint, Z: bigint }) {
         
         const input = {
             pk: [pk.X.toString(), pk.Y.toString(), pk.Z.toString()],
+            nonce: "1",
             R:  rScalars.map(r => r.toString()),
             original: deck.map((row) => row.map((v) => v.toString())),
             permuted: shuffled.map((row) => row.map((v) => v.toString())),
@@ -0,0 +1,512 @@
+#![no_std]
+
+//! Poker hand evaluation without allocations.
+//!
+//! A hand is ranked into a [`HandStrength`], a `u32` which compares the way the hands do
+//! under the same [`Rules`]: the stronger hand has the greater value and equal hands
+//! split the pot.
+
+pub const ACE: u8 = 14;
+
+/// A playing card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card {
+    /// 2–14 (where 11-J, 12-Q, 13-K, 14-A)
+    pub rank: u8,
+    /// 0–3
+    pub suit: u8,
+}
+
+impl Card {
+    pub const fn new(rank: u8, suit: u8) -> Self {
+        Self { rank, suit }
+    }
+}
+
+/// How five cards are ranked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rules {
+    /// Standard high hands, A-2-3-4-5 is the lowest straight
+    High,
+    /// The 36-card deck: A-6-7-8-9 is the lowest straight and a flush beats a full house
+    ShortDeck,
+    /// Ace-to-five low: the ace plays low, straights and flushes don't count,
+    /// and 5-4-3-2-A is the best hand
+    AceToFiveLow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl Category {
+    const ALL: [Category; 9] = [
+        Category::HighCard,
+        Category::Pair,
+        Category::TwoPair,
+        Category::ThreeOfAKind,
+        Category::Straight,
+        Category::Flush,
+        Category::FullHouse,
+        Category::FourOfAKind,
+        Category::StraightFlush,
+    ];
+
+    /// Place of the category among the others, the best one has the highest
+    fn order(self, rules: Rules) -> u32 {
+        match (rules, self) {
+            (Rules::ShortDeck, Category::Flush) => Category::FullHouse as u32,
+            (Rules::ShortDeck, Category::FullHouse) => Category::Flush as u32,
+            (Rules::AceToFiveLow, category) => Category::StraightFlush as u32 - category as u32,
+            (_, category) => category as u32,
+        }
+    }
+}
+
+/// Strength of a five-card hand.
+///
+/// Bits 28–31 hold the place of the category under the rules, bit 24 marks a low hand,
+/// bits 20–23 hold the category and bits 0–19 the ranks deciding between hands of the
+/// same category, four bits each from the most significant one. The ranks of a low hand
+/// are stored as `15 - rank`, so that lower cards give a greater value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandStrength(u32);
+
+impl HandStrength {
+    const ORDER_SHIFT: u32 = 28;
+    const LOW_FLAG: u32 = 1 << 24;
+    const CATEGORY_SHIFT: u32 = 20;
+
+    fn new(rules: Rules, category: Category, ranks: [u8; 5]) -> Self {
+        let low = rules == Rules::AceToFiveLow;
+        let mut value = (category.order(rules) << Self::ORDER_SHIFT)
+            | ((category as u32) << Self::CATEGORY_SHIFT);
+        if low {
+            value |= Self::LOW_FLAG;
+        }
+        for (i, rank) in ranks.into_iter().enumerate() {
+            let nibble = match rank {
+                0 => 0,
+                rank if low => 15 - rank,
+                rank => rank,
+            };
+            value |= (nibble as u32) << (16 - 4 * i);
+        }
+        Self(value)
+    }
+
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    pub fn category(self) -> Category {
+        Category::ALL[((self.0 >> Self::CATEGORY_SHIFT) & 0xf) as usize]
+    }
+
+    pub const fn is_low(self) -> bool {
+        self.0 & Self::LOW_FLAG != 0
+    }
+
+    /// Ranks deciding between hands of the same category, from the most significant one,
+    /// padded with zeros.
+    ///
+    /// A straight is given by its top card, a full house by the ranks of its three and
+    /// its pair. The ace of a low hand is 1.
+    pub fn ranks(self) -> [u8; 5] {
+        let mut ranks = [0; 5];
+        for (i, rank) in ranks.iter_mut().enumerate() {
+            let nibble = ((self.0 >> (16 - 4 * i)) & 0xf) as u8;
+            *rank = match nibble {
+                0 => 0,
+                nibble if self.is_low() => 15 - nibble,
+                nibble => nibble,
+            };
+        }
+        ranks
+    }
+
+    /// Whether a low hand has five different ranks none of which is above `max_rank`,
+    /// like the eight-or-better qualifier of the split pot games.
+    pub fn qualifies_for_low(self, max_rank: u8) -> bool {
+        self.is_low() && self.category() == Category::HighCard && self.ranks()[0] <= max_rank
+    }
+}
+
+/// Ranks exactly five cards.
+pub fn evaluate(cards: &[Card; 5], rules: Rules) -> HandStrength {
+    let low = rules == Rules::AceToFiveLow;
+
+    let mut counts = [0u8; 15];
+    for card in cards {
+        let rank = if low && card.rank == ACE {
+            1
+        } else {
+            card.rank
+        };
+        counts[rank as usize] += 1;
+    }
+
+    // distinct ranks, the bigger groups first and the higher ranks first within a group
+    let mut ranks = [0u8; 5];
+    let mut distinct = 0;
+    for count in (1..=4).rev() {
+        for rank in (1..=ACE).rev() {
+            if counts[rank as usize] == count {
+                ranks[distinct] = rank;
+                distinct += 1;
+            }
+        }
+    }
+
+    let category = match (counts[ranks[0] as usize], counts[ranks[1] as usize]) {
+        (4, _) => Category::FourOfAKind,
+        (3, 2) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::Pair,
+        _ if low => Category::HighCard,
+        _ => {
+            let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+            let lowest_straight_top = match rules {
+                Rules::ShortDeck => 9,
+                _ => 5,
+            };
+            let straight_top = if ranks[0] - ranks[4] == 4 {
+                Some(ranks[0])
+            } else if ranks[0] == ACE && ranks[1] == lowest_straight_top && ranks[1] - ranks[4] == 3
+            {
+                Some(lowest_straight_top)
+            } else {
+                None
+            };
+
+            match (flush, straight_top) {
+                (true, Some(top)) => {
+                    ranks = [top, 0, 0, 0, 0];
+                    Category::StraightFlush
+                }
+                (false, Some(top)) => {
+                    ranks = [top, 0, 0, 0, 0];
+                    Category::Straight
+                }
+                (true, None) => Category::Flush,
+                (false, None) => Category::HighCard,
+            }
+        }
+    };
+
+    HandStrength::new(rules, category, ranks)
+}
+
+/// Ranks the best hand made of any five of the cards, like in Hold'em.
+///
+/// Returns `None` if there are less than five cards.
+pub fn best_of(cards: &[Card], rules: Rules) -> Option<HandStrength> {
+    let mut best = None;
+    for_each_combination(cards.len(), |[a, b, c, d, e]: [usize; 5]| {
+        let hand = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+        best = best.max(Some(evaluate(&hand, rules)));
+    });
+    best
+}
+
+/// Ranks the best hand made of exactly two hole cards and three board cards, like in Omaha.
+///
+/// Returns `None` if there are less than two hole cards or three board cards.
+pub fn best_omaha(hole_cards: &[Card], board: &[Card], rules: Rules) -> Option<HandStrength> {
+    let mut best = None;
+    for_each_combination(hole_cards.len(), |[a, b]: [usize; 2]| {
+        for_each_combination(board.len(), |[c, d, e]: [usize; 3]| {
+            let hand = [hole_cards[a], hole_cards[b], board[c], board[d], board[e]];
+            best = best.max(Some(evaluate(&hand, rules)));
+        });
+    });
+    best
+}
+
+/// Calls `f` with every set of `K` indices below `n`, in lexicographic order.
+fn for_each_combination<const K: usize>(n: usize, mut f: impl FnMut([usize; K])) {
+    if n < K {
+        return;
+    }
+    let mut indices = [0; K];
+    for (i, index) in indices.iter_mut().enumerate() {
+        *index = i;
+    }
+    loop {
+        f(indices);
+        // the last index that can still move right
+        let Some(i) = (0..K).rev().find(|&i| indices[i] < n - K + i) else {
+            return;
+        };
+        indices[i] += 1;
+        for j in i + 1..K {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::collections::HashSet;
+    use std::vec::Vec;
+
+    fn deck(lowest_rank: u8) -> Vec<Card> {
+        (lowest_rank..=ACE)
+            .flat_map(|rank| (0..4).map(move |suit| Card::new(rank, suit)))
+            .collect()
+    }
+
+    /// Ranks every five cards of the deck and returns the number of hands in each
+    /// category and the number of distinct strengths.
+    fn rank_all_hands(lowest_rank: u8, rules: Rules) -> ([u32; 9], usize) {
+        let deck = deck(lowest_rank);
+        let mut counts = [0; 9];
+        let mut strengths = HashSet::new();
+        for_each_combination(deck.len(), |[a, b, c, d, e]: [usize; 5]| {
+            let strength = evaluate(&[deck[a], deck[b], deck[c], deck[d], deck[e]], rules);
+            counts[strength.category() as usize] += 1;
+            strengths.insert(strength);
+        });
+        (counts, strengths.len())
+    }
+
+    /// Parses cards like `"As Td 2c"`
+    fn cards(s: &str) -> Vec<Card> {
+        s.split_whitespace()
+            .map(|card| {
+                let [rank, suit] = card.as_bytes() else {
+                    panic!("Bad card {card}");
+                };
+                let rank = match rank {
+                    b'2'..=b'9' => rank - b'0',
+                    b'T' => 10,
+                    b'J' => 11,
+                    b'Q' => 12,
+                    b'K' => 13,
+                    b'A' => ACE,
+                    _ => panic!("Bad rank {card}"),
+                };
+                let suit = b"shdc".iter().position(|s| s == suit).expect("Bad suit") as u8;
+                Card::new(rank, suit)
+            })
+            .collect()
+    }
+
+    fn best(s: &str, rules: Rules) -> HandStrength {
+        best_of(&cards(s), rules).expect("Not enough cards")
+    }
+
+    #[test]
+    fn test_all_five_card_hands() {
+        let (counts, distinct) = rank_all_hands(2, Rules::High);
+        assert_eq!(
+            counts,
+            [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 40]
+        );
+        assert_eq!(counts.iter().sum::<u32>(), 2_598_960);
+        assert_eq!(distinct, 7_462);
+    }
+
+    #[test]
+    fn test_all_short_deck_hands() {
+        let (counts, distinct) = rank_all_hands(6, Rules::ShortDeck);
+        assert_eq!(
+            counts,
+            [122_400, 193_536, 36_288, 16_128, 6_120, 480, 1_728, 288, 24]
+        );
+        assert_eq!(counts.iter().sum::<u32>(), 376_992);
+        // 120 high cards, 504 pairs, 252 two pairs, 252 threes, 6 straights,
+        // 120 flushes, 72 full houses, 72 fours and 6 straight flushes
+        assert_eq!(distinct, 1_404);
+    }
+
+    #[test]
+    fn test_all_ace_to_five_lows() {
+        let deck = deck(2);
+        let mut counts = [0; 9];
+        let mut strengths = HashSet::new();
+        let mut eight_or_better = HashSet::new();
+        for_each_combination(deck.len(), |[a, b, c, d, e]: [usize; 5]| {
+            let hand = [deck[a], deck[b], deck[c], deck[d], deck[e]];
+            let strength = evaluate(&hand, Rules::AceToFiveLow);
+            counts[strength.category() as usize] += 1;
+            strengths.insert(strength);
+            if strength.qualifies_for_low(8) {
+                eight_or_better.insert(strength);
+            }
+        });
+
+        assert_eq!(
+            counts,
+            [1_317_888, 1_098_240, 123_552, 54_912, 0, 0, 3_744, 624, 0]
+        );
+        assert_eq!(strengths.len(), 6_175);
+        // five of the eight ranks from the ace to the eight
+        assert_eq!(eight_or_better.len(), 56);
+    }
+
+    #[test]
+    fn test_categories_are_ordered() {
+        let hands = [
+            "Kd Js 8h 5c 3s",
+            "Kd Ks 8h 5c 3s",
+            "Kd Ks 8h 8c 3s",
+            "Kd Ks Kh 8c 3s",
+            "Ad 2s 3h 4c 5s",
+            "Kd Jd 8d 5d 3d",
+            "Kd Ks Kh 8c 8s",
+            "Kd Ks Kh Kc 3s",
+            "5d 4d 3d 2d Ad",
+        ];
+        for pair in hands.windows(2) {
+            assert!(best(pair[0], Rules::High) < best(pair[1], Rules::High));
+        }
+        for (hand, category) in hands.iter().zip(Category::ALL) {
+            assert_eq!(best(hand, Rules::High).category(), category);
+        }
+
+        // ace-to-five: no pair beats a pair, a flush and a straight are just high cards
+        let lows = [
+            "Kd Ks Kh Kc 3s",
+            "Kd Ks Kh 8c 8s",
+            "Kd Ks Kh 8c 3s",
+            "Kd Ks 8h 8c 3s",
+            "Kd Ks 8h 5c 3s",
+            "Kd Jd 8d 5d 3d",
+            "9d 8s 7h 6c 5s",
+            "5d 4d 3d 2d Ad",
+        ];
+        for pair in lows.windows(2) {
+            assert!(best(pair[0], Rules::AceToFiveLow) < best(pair[1], Rules::AceToFiveLow));
+        }
+    }
+
+    #[test]
+    fn test_best_of_seven() {
+        let table = [
+            // a flush on the board with a pair in hand
+            ("2h 2s Ah 9h 7h 4h 2d", Category::Flush, [14, 9, 7, 4, 2]),
+            // two threes make a full house of the higher one
+            ("9s 9d 9c 5h 5s 5d Kc", Category::FullHouse, [9, 5, 0, 0, 0]),
+            // three pairs play the two highest and the best kicker
+            ("Qs Qd 8c 8h 4s 4d 2c", Category::TwoPair, [12, 8, 4, 0, 0]),
+            // four of a kind plays the highest of the other cards
+            (
+                "7s 7h 7d 7c Ks Kd 2c",
+                Category::FourOfAKind,
+                [7, 13, 0, 0, 0],
+            ),
+            // a wheel straight flush beats the six-high straight
+            (
+                "As 2s 3s 4s 5s 6d Kc",
+                Category::StraightFlush,
+                [5, 0, 0, 0, 0],
+            ),
+            ("As 2s 3s 4s 5d 6d Kc", Category::Straight, [6, 0, 0, 0, 0]),
+            // a straight beats three of a kind made with the same cards
+            ("8s 8d 8c 9h Ts Jd Qc", Category::Straight, [12, 0, 0, 0, 0]),
+            // high card keeps only the five highest
+            (
+                "As Qd 9c 7h 5s 3d 2c",
+                Category::HighCard,
+                [14, 12, 9, 7, 5],
+            ),
+        ];
+        for (hand, category, ranks) in table {
+            let strength = best(hand, Rules::High);
+            assert_eq!(strength.category(), category, "{hand}");
+            assert_eq!(strength.ranks(), ranks, "{hand}");
+        }
+
+        assert_eq!(best_of(&cards("As Ks Qs Js"), Rules::High), None);
+    }
+
+    #[test]
+    fn test_short_deck() {
+        let table = [
+            ("As 6d 7c 8h 9s Kd Kc", Category::Straight, [9, 0, 0, 0, 0]),
+            (
+                "As 6s 7s 8s 9s Kd Kc",
+                Category::StraightFlush,
+                [9, 0, 0, 0, 0],
+            ),
+            ("Kh Qh 6h 7h Ks Kd 8h", Category::Flush, [13, 12, 8, 7, 6]),
+        ];
+        for (hand, category, ranks) in table {
+            let strength = best(hand, Rules::ShortDeck);
+            assert_eq!(strength.category(), category, "{hand}");
+            assert_eq!(strength.ranks(), ranks, "{hand}");
+        }
+
+        let flush = best("Kh Qh 6h 7h 8h", Rules::ShortDeck);
+        let full_house = best("Kh Ks Kd 8h 8s", Rules::ShortDeck);
+        assert!(flush > full_house);
+        assert!(best("Kh Qh 6h 7h 8h", Rules::High) < best("Kh Ks Kd 8h 8s", Rules::High));
+        // A-2-3-4-5 is not a straight with the short deck rules
+        assert_eq!(
+            best("As 2d 3c 4h 5s", Rules::ShortDeck).category(),
+            Category::HighCard
+        );
+    }
+
+    #[test]
+    fn test_omaha() {
+        let board = cards("2h 5h 9h Kh 3c");
+
+        // one heart in hand doesn't make a flush
+        let hole = cards("Ah 7s 8d Jc");
+        let strength = best_omaha(&hole, &board, Rules::High).unwrap();
+        assert_eq!(strength.category(), Category::HighCard);
+        assert_eq!(strength.ranks(), [14, 13, 11, 9, 5]);
+        assert_eq!(
+            best_of(&[hole, board.clone()].concat(), Rules::High)
+                .unwrap()
+                .category(),
+            Category::Flush
+        );
+
+        // two pairs in hand play as one pair
+        let hole = cards("Ks Kd 4s 6d");
+        let strength = best_omaha(&hole, &board, Rules::High).unwrap();
+        assert_eq!(strength.category(), Category::Straight);
+        assert_eq!(strength.ranks(), [6, 0, 0, 0, 0]);
+
+        // four of a kind on the board plays only one of the four
+        let board = cards("7s 7h 7d 7c 2s");
+        let strength = best_omaha(&cards("As Ad 3c 4h"), &board, Rules::High).unwrap();
+        assert_eq!(strength.category(), Category::FullHouse);
+        assert_eq!(strength.ranks(), [7, 14, 0, 0, 0]);
+
+        let low = best_omaha(
+            &cards("Ad 2c Ks Kh"),
+            &cards("3s 4s 8h Qd Jc"),
+            Rules::AceToFiveLow,
+        )
+        .unwrap();
+        assert!(low.qualifies_for_low(8));
+        assert_eq!(low.ranks(), [8, 4, 3, 2, 1]);
+        assert_eq!(best_omaha(&cards("Ad"), &board, Rules::High), None);
+    }
+
+    #[test]
+    fn test_low_qualifier() {
+        assert!(best("5d 4d 3d 2d Ad", Rules::AceToFiveLow).qualifies_for_low(8));
+        assert!(best("8d 7s 6h 5c 4s Kd Kc", Rules::AceToFiveLow).qualifies_for_low(8));
+        assert!(!best("9d 7s 6h 5c 4s", Rules::AceToFiveLow).qualifies_for_low(8));
+        assert!(!best("8d 8s 6h 5c 4s", Rules::AceToFiveLow).qualifies_for_low(8));
+        // only low hands qualify
+        assert!(!best("5d 4d 3d 2d Ad", Rules::High).qualifies_for_low(8));
+    }
+}
@@ -1,12 +1,14 @@
 use gstd::prog::ProgramGenerator;
-use poker_client::{SessionConfig, SignatureInfo, ZkPublicKey};
+use poker_app::services::game::GameVariant as LobbyGameVariant;
+use poker_client::{
+    BettingStructure, GameMode, GameVariant, LevelDuration, SchnorrProofBytes, SessionConfig,
+    SignatureInfo, TournamentConfig, ZkPublicKey,
+};
+use pts_client::pts::io as pts_io;
+use sails_rs::calls::ActionIo;
 use sails_rs::collections::{HashMap, HashSet};
 use sails_rs::gstd::msg;
 use sails_rs::prelude::*;
-mod utils;
-use crate::services::utils::panic;
-use pts_client::pts::io as pts_io;
-use sails_rs::calls::ActionIo;
 
 #[derive(Debug, Clone)]
 struct Storage {
@@ -26,7 +28,7 @@ pub struct Config {
     pub gas_for_reply_deposit: u64,
 }
 
-#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
 #[codec(crate = sails_rs::scale_codec)]
 #[scale_info(crate = sails_rs::scale_info)]
 pub struct LobbyConfig {
@@ -37,6 +39,14 @@ pub struct LobbyConfig {
     big_blind: u128,
     starting_bank: u128,
     time_per_move_ms: u64,
+    betting_structure: Option<BettingStructure>,
+    game_mode: GameMode,
+    ante: u128,
+    allow_straddle: bool,
+    max_players: u8,
+    min_players_to_start: u8,
+    game_variant: GameVariant,
+    hi_lo: bool,
 }
 
 static mut STORAGE: Option<Storage> = None;
@@ -66,6 +76,26 @@ pub enum Event {
     },
 }
 
+// the lobby decodes `DeleteLobby` replies with a copy of this enum, keep them in sync
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum PokerFactoryError {
+    AccessDenied,
+    NotLobbyCreator,
+    WrongValue,
+    TimerTooShort,
+    TimerTooLong,
+    InvalidBettingStructure,
+    InvalidTournament,
+    InvalidTableSize,
+    HiLoNotSupported,
+    LowPtsBalance,
+    LobbyCreationFailed,
+    PtsCallFailed,
+    LobbyNotFound,
+}
+
 pub struct PokerFactoryService(());
 
 impl PokerFactoryService {
@@ -87,6 +117,57 @@ impl PokerFactoryService {
     fn get(&self) -> &'static Storage {
         unsafe { STORAGE.as_ref().expect("Storage is not initialized") }
     }
+    fn check_admin(&self) -> Result<(), PokerFactoryError> {
+        if !self.get().admins.contains(&msg::source()) {
+            return Err(PokerFactoryError::AccessDenied);
+        }
+        Ok(())
+    }
+}
+
+/// Players that can be dealt their cards in the variant, as the lobby counts them
+fn max_players(game_variant: &GameVariant) -> usize {
+    let game_variant = match game_variant {
+        GameVariant::Holdem => LobbyGameVariant::Holdem,
+        GameVariant::Omaha => LobbyGameVariant::Omaha,
+        GameVariant::ShortDeck => LobbyGameVariant::ShortDeck,
+        GameVariant::FiveCardDraw { max_discards } => LobbyGameVariant::FiveCardDraw {
+            max_discards: *max_discards,
+        },
+    };
+    game_variant.max_players()
+}
+
+/// Blinds of every level must be set, levels must not be empty, and the payout table
+/// must pay at least one and at most `max_players` places and at most 100 percent
+/// in total, the lobby gives the rest of the prize pool to the winner.
+fn is_valid_tournament(tournament: &TournamentConfig, max_players: u8) -> bool {
+    let levels_valid = !tournament.blind_levels.is_empty()
+        && tournament
+            .blind_levels
+            .iter()
+            .all(|level| level.small_blind > 0 && level.big_blind >= level.small_blind);
+    let duration_valid = !matches!(
+        tournament.level_duration,
+        LevelDuration::Hands(0) | LevelDuration::Millis(0)
+    );
+    let payouts_valid = !tournament.payouts.is_empty()
+        && tournament.payouts.len() <= max_players as usize
+        && tournament
+            .payouts
+            .iter()
+            .map(|share| *share as u32)
+            .sum::<u32>()
+            <= 100;
+
+    levels_valid && duration_valid && payouts_valid
+}
+
+async fn pts_call(pts_actor_id: ActorId, request: Vec<u8>) -> Result<Vec<u8>, PokerFactoryError> {
+    msg::send_bytes_for_reply(pts_actor_id, request, 0, 0)
+        .map_err(|_| PokerFactoryError::PtsCallFailed)?
+        .await
+        .map_err(|_| PokerFactoryError::PtsCallFailed)
 }
 
 #[sails_rs::service(events = Event)]
@@ -98,9 +179,18 @@ impl PokerFactoryService {
 
     /// Creates new poker lobby with provided config.
     ///
-    /// Panics if:
+    /// Fails if:
+    /// - Caller is not the lobby admin from `init_lobby`
+    /// - Invalid betting structure or sit-and-go settings
+    /// - Table size not dealable from the deck, or fewer than 2 players needed to start
     /// - Insufficient PTS balance
-    /// - Program creation fails
+    /// - Program creation fails (e.g. `pk_proof` is rejected by the lobby)
+    ///
+    /// The attached value is sent back when the lobby program isn't deployed. Once it is,
+    /// the value pays for the deployment even if setting up the lobby fails afterwards.
+    ///
+    /// `pk_proof` is a proof of knowledge of the secret key for `pk`, bound to
+    /// the admin and this factory, and is verified by the lobby on init.
     ///
     /// Performs:
     /// 1. Checks player's PTS balance
@@ -112,43 +202,133 @@ impl PokerFactoryService {
         &mut self,
         init_lobby: LobbyConfig,
         pk: ZkPublicKey,
+        pk_proof: SchnorrProofBytes,
         session: Option<SignatureInfo>,
-    ) {
+    ) -> Result<(), PokerFactoryError> {
+        let msg_src = msg::source();
+        let value = msg::value();
+        let lobby_address = match self.deploy_lobby(&init_lobby, &pk, pk_proof, session).await {
+            Ok(lobby_address) => lobby_address,
+            Err(error) => {
+                // an error reply doesn't return the value the way a panic does
+                if value != 0 {
+                    msg::send_bytes(msg_src, [], value).expect("Error in returning value");
+                }
+                return Err(error);
+            }
+        };
+
+        let storage = self.get_mut();
+        let request = pts_io::AddAdmin::encode_call(lobby_address);
+        let reply = pts_call(storage.pts_actor_id, request).await?;
+        pts_io::AddAdmin::decode_reply(reply)
+            .map_err(|_| PokerFactoryError::PtsCallFailed)?
+            .map_err(|_| PokerFactoryError::PtsCallFailed)?;
+
+        let request =
+            pts_io::Transfer::encode_call(msg_src, lobby_address, init_lobby.starting_bank);
+        let reply = pts_call(storage.pts_actor_id, request).await?;
+        pts_io::Transfer::decode_reply(reply)
+            .map_err(|_| PokerFactoryError::PtsCallFailed)?
+            .map_err(|_| PokerFactoryError::PtsCallFailed)?;
+
+        storage.lobbies.insert(lobby_address, init_lobby.clone());
+
+        self.emit_event(Event::LobbyCreated {
+            lobby_address,
+            admin: msg_src,
+            pk,
+            lobby_config: init_lobby,
+        })
+        .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Checks the lobby config and the admin's PTS balance, then deploys the lobby program.
+    async fn deploy_lobby(
+        &mut self,
+        init_lobby: &LobbyConfig,
+        pk: &ZkPublicKey,
+        pk_proof: SchnorrProofBytes,
+        session: Option<SignatureInfo>,
+    ) -> Result<ActorId, PokerFactoryError> {
         let storage = self.get_mut();
         let msg_src = msg::source();
 
+        if init_lobby.admin_id != msg_src {
+            return Err(PokerFactoryError::NotLobbyCreator);
+        }
+
         if msg::value() != 1_000_000_000_000 {
-            panic!("Wrong value to create a lobby");
+            return Err(PokerFactoryError::WrongValue);
         }
 
+        let session_config = SessionConfig {
+            gas_to_delete_session: 10_000_000_000,
+            minimum_session_duration_ms: 180_000,
+            ms_per_block: 3_000,
+        };
+
         if init_lobby.time_per_move_ms < 15_000 {
-            panic!("Timer less than 15s");
+            return Err(PokerFactoryError::TimerTooShort);
+        }
+        // the lobby schedules the turn timer in blocks, the delay has to fit in u32
+        if init_lobby
+            .time_per_move_ms
+            .div_ceil(session_config.ms_per_block)
+            >= u32::MAX.into()
+        {
+            return Err(PokerFactoryError::TimerTooLong);
         }
 
-        let request = pts_io::GetBalance::encode_call(msg_src);
+        if matches!(
+            init_lobby.betting_structure,
+            Some(BettingStructure::FixedLimit { small_bet, big_bet, .. }) if small_bet == 0 || big_bet < small_bet
+        ) {
+            return Err(PokerFactoryError::InvalidBettingStructure);
+        }
 
-        let bytes_reply_balance = msg::send_bytes_for_reply(storage.pts_actor_id, request, 0, 0)
-            .expect("Error in async message to PTS contract")
-            .await
-            .expect("PTS: Error getting balance from the contract");
+        if matches!(
+            &init_lobby.game_mode,
+            GameMode::SitAndGo(tournament) if !is_valid_tournament(tournament, init_lobby.max_players)
+        ) {
+            return Err(PokerFactoryError::InvalidTournament);
+        }
+
+        if init_lobby.max_players as usize > max_players(&init_lobby.game_variant)
+            || init_lobby.min_players_to_start < 2
+            || init_lobby.min_players_to_start > init_lobby.max_players
+        {
+            return Err(PokerFactoryError::InvalidTableSize);
+        }
+
+        // low hands are made with the board of the standard deck
+        if init_lobby.hi_lo
+            && !matches!(
+                init_lobby.game_variant,
+                GameVariant::Holdem | GameVariant::Omaha
+            )
+        {
+            return Err(PokerFactoryError::HiLoNotSupported);
+        }
 
-        let balance: u128 = pts_io::GetBalance::decode_reply(bytes_reply_balance).unwrap();
+        let request = pts_io::GetBalance::encode_call(msg_src);
+        let reply = pts_call(storage.pts_actor_id, request).await?;
+        let balance = pts_io::GetBalance::decode_reply(reply)
+            .map_err(|_| PokerFactoryError::PtsCallFailed)?
+            .map_err(|_| PokerFactoryError::LowPtsBalance)?;
 
         if balance < init_lobby.starting_bank {
-            panic!("Low pts balance");
+            return Err(PokerFactoryError::LowPtsBalance);
         }
 
-        let session_config = SessionConfig {
-            gas_to_delete_session: 10_000_000_000,
-            minimum_session_duration_ms: 180_000,
-            ms_per_block: 3_000,
-        };
         let payload = [
             "New".encode(),
             init_lobby.encode(),
             session_config.encode(),
             storage.pts_actor_id.encode(),
             pk.encode(),
+            pk_proof.encode(),
             session.encode(),
             storage.zk_verification_id.encode(),
         ]
@@ -160,95 +340,74 @@ impl PokerFactoryService {
             0,
             storage.config.gas_for_reply_deposit,
         )
-        .unwrap_or_else(|e| panic(e));
-
-        let (lobby_address, _) = create_program_future.await.unwrap_or_else(|e| panic(e));
-
-        let request = pts_io::AddAdmin::encode_call(lobby_address);
-
-        msg::send_bytes_for_reply(storage.pts_actor_id, request, 0, 0)
-            .expect("Error in async message to PTS contract")
-            .await
-            .expect("PTS: Error adding new admin");
-
-        let request =
-            pts_io::Transfer::encode_call(msg_src, lobby_address, init_lobby.starting_bank);
+        .map_err(|_| PokerFactoryError::LobbyCreationFailed)?;
 
-        msg::send_bytes_for_reply(storage.pts_actor_id, request, 0, 0)
-            .expect("Error in async message to PTS contract")
+        let (lobby_address, _) = create_program_future
             .await
-            .expect("PTS: Error transfer points to contract");
-
-        storage.lobbies.insert(lobby_address, init_lobby.clone());
-
-        self.emit_event(Event::LobbyCreated {
-            lobby_address,
-            admin: msg_src,
-            pk,
-            lobby_config: init_lobby,
-        })
-        .expect("Notification Error");
+            .map_err(|_| PokerFactoryError::LobbyCreationFailed)?;
+        Ok(lobby_address)
     }
 
     /// Deletes lobby from registry. Admin or lobby itself only.
-    /// Panics if:
+    /// Fails if:
     /// - Lobby doesn't exist
     /// - Caller lacks permissions
     ///
     /// Emits LobbyDeleted event on success.
-    pub async fn delete_lobby(&mut self, lobby_address: ActorId) {
+    pub async fn delete_lobby(&mut self, lobby_address: ActorId) -> Result<(), PokerFactoryError> {
         let storage = self.get_mut();
         let msg_src = msg::source();
         let lobby = storage
             .lobbies
             .get(&lobby_address)
-            .expect("Lobby must be exist");
+            .ok_or(PokerFactoryError::LobbyNotFound)?;
         if msg_src != lobby.admin_id
             && msg_src != lobby_address
             && !storage.admins.contains(&msg_src)
         {
-            panic!("Access denied");
+            return Err(PokerFactoryError::AccessDenied);
         }
         storage.lobbies.remove(&lobby_address);
 
         self.emit_event(Event::LobbyDeleted { lobby_address })
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub async fn change_config(&mut self, config: Config) {
+    pub async fn change_config(&mut self, config: Config) -> Result<(), PokerFactoryError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        let msg_src = msg::source();
-        if !storage.admins.contains(&msg_src) {
-            panic!("Access denied");
-        }
         storage.config = config.clone();
 
         self.emit_event(Event::ConfigChanged { config })
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub async fn change_zk_verification_id(&mut self, zk_verification_id: ActorId) {
+    pub async fn change_zk_verification_id(
+        &mut self,
+        zk_verification_id: ActorId,
+    ) -> Result<(), PokerFactoryError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        let msg_src = msg::source();
-        if !storage.admins.contains(&msg_src) {
-            panic!("Access denied");
-        }
         storage.zk_verification_id = zk_verification_id;
 
         self.emit_event(Event::ZkVerificationIdChanged { zk_verification_id })
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub async fn change_pts_actor_id(&mut self, pts_actor_id: ActorId) {
+    pub async fn change_pts_actor_id(
+        &mut self,
+        pts_actor_id: ActorId,
+    ) -> Result<(), PokerFactoryError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        let msg_src = msg::source();
-        if !storage.admins.contains(&msg_src) {
-            panic!("Access denied");
-        }
         storage.pts_actor_id = pts_actor_id;
 
         self.emit_event(Event::PtsActorIdChanged { pts_actor_id })
             .expect("Notification Error");
+        Ok(())
     }
 
     pub fn add_admin(&mut self, new_admin_id: ActorId) {
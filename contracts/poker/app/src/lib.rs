@@ -1,25 +1,42 @@
 #![no_std]
 
 pub mod services;
+#[cfg(feature = "program")]
 use sails_rs::prelude::*;
-use services::game::{Config as LobbyConfig, PokerService, ZkPublicKey};
+#[cfg(feature = "program")]
+use services::game::{
+    Config as LobbyConfig, PokerError, PokerService, SchnorrProofBytes, ZkPublicKey,
+};
+#[cfg(feature = "program")]
 use services::session::{Config as SessionConfig, SessionService, SignatureInfo};
+#[cfg(feature = "program")]
 use session_service::*;
 
+#[cfg(feature = "program")]
 pub struct PokerProgram(());
 
+#[cfg(feature = "program")]
 #[sails_rs::program]
 impl PokerProgram {
+    #[export(unwrap_result)]
     pub async fn new(
         config: LobbyConfig,
         session_config: SessionConfig,
         pts_actor_id: ActorId,
         pk: ZkPublicKey,
+        pk_proof: SchnorrProofBytes,
         session_for_admin: Option<SignatureInfo>,
         zk_verification_id: ActorId,
-    ) -> Self {
+    ) -> Result<Self, PokerError> {
         let admin_id = config.admin_id;
-        PokerService::init(config, pts_actor_id, pk, zk_verification_id);
+        PokerService::init(
+            config,
+            pts_actor_id,
+            pk,
+            pk_proof,
+            zk_verification_id,
+            session_config.ms_per_block,
+        )?;
         SessionService::init(session_config);
         if let Some(SignatureInfo {
             signature_data,
@@ -28,7 +45,7 @@ impl PokerProgram {
         {
             SessionService::create_session_for_admin(signature_data, signature, admin_id);
         }
-        Self(())
+        Ok(Self(()))
     }
 
     pub fn poker(&self) -> PokerService {
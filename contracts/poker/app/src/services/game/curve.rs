@@ -1,7 +1,7 @@
-use crate::services::game::{Card, Suit, ZkPublicKey};
+use crate::services::game::{Card, Suit, ZkPublicKey, verify::DeckConfig};
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsProjective, Fq, Fr};
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
 use sails_rs::{collections::HashMap, prelude::*};
 
 pub fn deserialize_bandersnatch_coords(coords: &[Vec<u8>; 3]) -> EdwardsProjective {
@@ -15,6 +15,22 @@ pub fn deserialize_bandersnatch_coords(coords: &[Vec<u8>; 3]) -> EdwardsProjecti
         .into()
 }
 
+/// Deserializes a projective point supplied by a player, rejecting points that are
+/// not on the curve or lie outside the prime-order subgroup.
+pub fn deserialize_checked_coords(x: &[u8], y: &[u8], z: &[u8]) -> Option<EdwardsProjective> {
+    let x = Fq::from_le_bytes_mod_order(x);
+    let y = Fq::from_le_bytes_mod_order(y);
+    let z_inv = Fq::from_le_bytes_mod_order(z).inverse()?;
+    let point = EdwardsAffine::new_unchecked(x * z_inv, y * z_inv);
+
+    (point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()).then(|| point.into())
+}
+
+/// Checked counterpart of [`deserialize_public_key`]; the identity is rejected as well.
+pub fn deserialize_checked_public_key(pk: &ZkPublicKey) -> Option<EdwardsProjective> {
+    deserialize_checked_coords(&pk.x, &pk.y, &pk.z).filter(|point| !point.is_zero())
+}
+
 pub fn serialize_bandersnatch_coords(point: &EdwardsProjective) -> [Vec<u8>; 3] {
     [
         point.x.into_bigint().to_bytes_le(),
@@ -85,10 +101,12 @@ pub fn compare_public_keys(pk1: &ZkPublicKey, pk2: &ZkPublicKey) -> bool {
     (x1 * z2 == x2 * z1) && (y1 * z2 == y2 * z1)
 }
 
-pub fn init_deck_and_card_map() -> (Vec<EdwardsProjective>, HashMap<EdwardsProjective, Card>) {
-    let mut encrypted_deck: Vec<EdwardsProjective> = Vec::with_capacity(52);
+pub fn init_deck_and_card_map(
+    config: DeckConfig,
+) -> (Vec<EdwardsProjective>, HashMap<EdwardsProjective, Card>) {
+    let num_cards = config.num_cards;
+    let mut encrypted_deck: Vec<EdwardsProjective> = Vec::with_capacity(num_cards);
 
-    let num_cards = 52;
     let base_affine = EdwardsAffine::generator();
     let base_point: EdwardsProjective = base_affine.into();
 
@@ -99,16 +117,19 @@ pub fn init_deck_and_card_map() -> (Vec<EdwardsProjective>, HashMap<EdwardsProje
         encrypted_deck.push(point);
     }
 
-    let card_map = build_card_map(encrypted_deck.clone());
+    let card_map = build_card_map(encrypted_deck.clone(), config.lowest_value());
 
     (encrypted_deck, card_map)
 }
 
-pub fn build_card_map(deck: Vec<EdwardsProjective>) -> HashMap<EdwardsProjective, Card> {
+pub fn build_card_map(
+    deck: Vec<EdwardsProjective>,
+    lowest_value: u8,
+) -> HashMap<EdwardsProjective, Card> {
     let mut card_map = HashMap::new();
 
     let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
-    let values = 2..=14;
+    let values = lowest_value..=14;
 
     let mut index = 0;
     for suit in &suits {
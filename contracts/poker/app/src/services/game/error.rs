@@ -0,0 +1,53 @@
+use sails_rs::prelude::*;
+
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum PokerError {
+    // sessions
+    NoValidSession,
+    SessionExpired,
+    SessionNotApproved,
+    // lobby and players
+    AccessDenied,
+    WrongStatus,
+    WrongStage,
+    AlreadyRegistered,
+    NotRegistered,
+    LobbyIsFull,
+    InvalidSeat,
+    SeatTaken,
+    NotEnoughParticipants,
+    NoSuchPlayer,
+    InvalidSecretKeyProof,
+    DeadlineNotReached,
+    InvalidConfig,
+    InvalidTournament,
+    // betting
+    NotYourTurn,
+    WrongAction,
+    CannotCheck,
+    RaiseTooLow,
+    RaiseNotAllowed,
+    RaiseTooHigh,
+    RaiseCapReached,
+    StraddleNotAllowed,
+    InvalidDiscard,
+    // shuffle
+    InvalidPublicInput,
+    PublicKeyMismatch,
+    ShuffleNonceMismatch,
+    InitialDeckMismatch,
+    ShuffleChainDiscontinuity,
+    // decryption
+    AlreadySubmitted,
+    WrongNumberOfDecryptions,
+    CardNotFound,
+    DecryptionVerificationFailed,
+    CardDecryptionFailed,
+    NotEnoughCards,
+    // other programs
+    PtsTransferFailed,
+    ZkVerificationFailed,
+    FactoryCallFailed,
+}
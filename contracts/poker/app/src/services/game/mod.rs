@@ -4,6 +4,8 @@ use sails_rs::gstd::{exec, msg};
 use sails_rs::prelude::*;
 use utils::*;
 mod curve;
+mod error;
+mod tournament;
 mod utils;
 pub mod verify;
 use crate::services::game::curve::{
@@ -12,12 +14,20 @@ use crate::services::game::curve::{
 use crate::services::session::Storage as SessionStorage;
 use ark_ec::PrimeGroup;
 use ark_ed_on_bls12_381_bandersnatch::EdwardsProjective;
+use ark_ff::{BigInteger, PrimeField};
+pub use error::PokerError;
 use pts_client::pts::io as pts_io;
-pub use verify::{ChaumPedersenProofBytes, ShuffleChainValidator};
+use sails_rs::calls::ActionIo;
+pub use tournament::{BlindLevel, GameMode, LevelDuration, TournamentConfig, TournamentState};
+use verify::{CardSlot, DeckConfig, DecryptionContext, TABLE_CARDS};
+pub use verify::{ChaumPedersenProofBytes, SchnorrProofBytes, ShuffleChainValidator};
 use zk_verification_client::zk_verification::io as zk_io;
 
 use zk_verification_client::VerificationVariables;
 
+// gas reserved for the delayed message that ends a turn the player didn't make in time
+const GAS_FOR_TURN_TIMEOUT: u64 = 10_000_000_000;
+
 #[derive(Debug, Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Hash)]
 #[codec(crate = sails_rs::scale_codec)]
 #[scale_info(crate = sails_rs::scale_info)]
@@ -31,9 +41,9 @@ struct Storage {
     // for zk
     zk_verification_id: ActorId,
     encrypted_deck: Option<Vec<EncryptedCard>>,
-    encrypted_cards: HashMap<ActorId, [EncryptedCard; 2]>,
+    encrypted_cards: HashMap<ActorId, Vec<EncryptedCard>>,
     submitted_decrypters: HashSet<ActorId>,
-    partially_decrypted_cards: HashMap<ActorId, [EncryptedCard; 2]>,
+    partially_decrypted_cards: HashMap<ActorId, Vec<EncryptedCard>>,
     revealed_table_cards: Vec<Card>,
     original_card_map: HashMap<EdwardsProjective, Card>,
     original_deck: Vec<EdwardsProjective>,
@@ -45,7 +55,7 @@ struct Storage {
     // active_participants - players who can place bets
     // not to be confused with those who are in the game, as there are also all in players.
     active_participants: TurnManager<ActorId>,
-    revealed_players: HashMap<ActorId, (Card, Card)>,
+    revealed_players: HashMap<ActorId, Vec<Card>>,
     status: Status,
     config: Config,
     round: u64,
@@ -56,6 +66,27 @@ struct Storage {
     // which can change after each player's turn
     pts_actor_id: ActorId,
     factory_actor_id: ActorId,
+    // deadline of the current shuffle, decryption or disclosure phase,
+    // after which the stalled players can be penalized with `claim_timeout`
+    phase_deadline: Option<u64>,
+    // incremented on every new betting turn, so that timers of the previous turns are ignored
+    turn_nonce: u64,
+    ms_per_block: u64,
+    // progress of a sit-and-go, set when its first hand starts
+    tournament: Option<TournamentState>,
+    // button and blinds of the current (or the last) hand
+    positions: Option<Positions>,
+    // players who still have to show or muck their cards, the first one is next
+    showdown_order: Vec<ActorId>,
+    // players who mucked at the showdown, in the order they did
+    mucked: Vec<ActorId>,
+    // players of an all-in hand who agreed to run the rest of the board twice
+    run_it_twice: Vec<ActorId>,
+    // players of a draw game who still have to discard, the first one is next
+    drawing: Vec<ActorId>,
+    // hand positions discarded by each player, replaced by the cards dealt after the draw;
+    // they are public, `draw` takes them in the clear
+    discards: Vec<(ActorId, Vec<u8>)>,
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
@@ -63,13 +94,13 @@ struct Storage {
 #[scale_info(crate = sails_rs::scale_info)]
 pub enum Status {
     Registration,
-    WaitingShuffleVerification,
+    WaitingShuffle { turn: ActorId },
     WaitingStart,
     WaitingPartialDecryptionsForPlayersCards,
     Play { stage: Stage },
     WaitingForCardsToBeDisclosed,
     WaitingForAllTableCardsToBeDisclosed,
-    Finished { pots: Vec<(u128, Vec<ActorId>)> },
+    Finished { pots: Pots },
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
@@ -94,6 +125,132 @@ pub struct Config {
     big_blind: u128,
     starting_bank: u128,
     time_per_move_ms: u64,
+    /// `None` for the default of the game variant
+    betting_structure: Option<BettingStructure>,
+    game_mode: GameMode,
+    /// Paid by every player before the blinds, 0 for no ante
+    ante: u128,
+    /// Whether the player after the big blind may straddle
+    allow_straddle: bool,
+    /// Number of seats at the table, limited by the cards in the deck
+    max_players: u8,
+    /// Players needed to start a hand, at least 2
+    min_players_to_start: u8,
+    game_variant: GameVariant,
+    /// Whether every pot is split between the best high hand and the best
+    /// eight-or-better low hand, as in Omaha Hi-Lo
+    hi_lo: bool,
+}
+
+impl Config {
+    /// The betting structure of the lobby, or the default one of its game variant
+    fn betting_structure(&self) -> BettingStructure {
+        self.betting_structure
+            .clone()
+            .unwrap_or_else(|| self.game_variant.default_betting_structure())
+    }
+}
+
+/// Limits on the size of bets and raises in a lobby
+#[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum BettingStructure {
+    NoLimit,
+    /// A raise is capped by the pot after the raiser's call
+    PotLimit,
+    /// Bets and raises are `small_bet` preflop and on the flop, `big_bet` on the turn and the river,
+    /// with at most `max_raises` raises per round
+    FixedLimit {
+        small_bet: u128,
+        big_bet: u128,
+        max_raises: u32,
+    },
+}
+
+/// Poker game played in a lobby
+#[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum GameVariant {
+    /// Texas Hold'em: two hole cards, the best five of the seven cards play
+    Holdem,
+    /// Omaha: four hole cards, exactly two of them play with three from the board
+    Omaha,
+    /// Short-deck (6+) Hold'em: the deck has no cards below six, A-6-7-8-9 is
+    /// the lowest straight and a flush beats a full house
+    ShortDeck,
+    /// Five-card draw: five hole cards and no board, after the first betting round
+    /// every player may replace up to `max_discards` of their cards. The discarded
+    /// values stay hidden, their positions and count are public
+    FiveCardDraw { max_discards: u8 },
+}
+
+impl GameVariant {
+    pub const fn hole_cards(&self) -> usize {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck => 2,
+            GameVariant::Omaha => 4,
+            GameVariant::FiveCardDraw { .. } => 5,
+        }
+    }
+
+    /// Cards dealt to the board, none in a draw game
+    pub const fn table_cards(&self) -> usize {
+        match self {
+            GameVariant::FiveCardDraw { .. } => 0,
+            _ => TABLE_CARDS,
+        }
+    }
+
+    /// Most cards a player may replace at the draw
+    pub const fn max_discards(&self) -> usize {
+        match self {
+            GameVariant::FiveCardDraw { max_discards } => *max_discards as usize,
+            _ => 0,
+        }
+    }
+
+    /// Most players that can be dealt their cards, with the board or the replacements still to come
+    pub const fn max_players(&self) -> usize {
+        match self {
+            GameVariant::FiveCardDraw { max_discards } => {
+                self.deck().num_cards / (self.hole_cards() + *max_discards as usize)
+            }
+            _ => self.deck().max_players(self.hole_cards()),
+        }
+    }
+
+    /// The first betting round of a hand
+    pub fn first_stage(&self) -> Stage {
+        match self {
+            GameVariant::FiveCardDraw { .. } => Stage::PreDraw,
+            _ => Stage::PreFlop,
+        }
+    }
+
+    pub const fn deck(&self) -> DeckConfig {
+        match self {
+            GameVariant::Holdem | GameVariant::Omaha | GameVariant::FiveCardDraw { .. } => {
+                DeckConfig::STANDARD
+            }
+            GameVariant::ShortDeck => DeckConfig::SHORT,
+        }
+    }
+
+    /// Whether the pots can be split with the low hands, which are made with the board
+    pub const fn supports_hi_lo(&self) -> bool {
+        matches!(self, GameVariant::Holdem | GameVariant::Omaha)
+    }
+
+    pub fn default_betting_structure(&self) -> BettingStructure {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck | GameVariant::FiveCardDraw { .. } => {
+                BettingStructure::NoLimit
+            }
+            GameVariant::Omaha => BettingStructure::PotLimit,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
@@ -103,6 +260,20 @@ pub struct Participant {
     name: String,
     balance: u128,
     pk: ZkPublicKey,
+    seat: u8,
+}
+
+/// Seats of the dealer and the blinds of a hand.
+///
+/// When players leave, the button and the small blind can fall on an empty seat
+/// (dead button, dead small blind), so that nobody skips the big blind.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Positions {
+    pub button: u8,
+    pub small_blind: u8,
+    pub big_blind: u8,
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
@@ -131,6 +302,7 @@ pub enum Event {
     Registered {
         participant_id: ActorId,
         pk: ZkPublicKey,
+        seat: u8,
     },
     PlayerDeleted {
         player_id: ActorId,
@@ -138,9 +310,12 @@ pub enum Event {
     RegistrationCanceled {
         player_id: ActorId,
     },
+    DeckShuffled {
+        player_id: ActorId,
+    },
     DeckShuffleComplete,
     GameStarted,
-    CardsDealtToPlayers(Vec<(ActorId, [EncryptedCard; 2])>),
+    CardsDealtToPlayers(Vec<(ActorId, Vec<EncryptedCard>)>),
     CardsDealtToTable(Vec<EncryptedCard>),
     GameRestarted {
         status: Status,
@@ -150,44 +325,114 @@ pub enum Event {
     TurnIsMade {
         action: Action,
     },
+    TurnTimedOut {
+        player_id: ActorId,
+        action: Action,
+    },
     NextStage(Stage),
     Finished {
-        pots: Vec<(u128, Vec<ActorId>)>,
+        pots: Pots,
     },
     Killed,
     AllPartialDecryptionsSubmited,
     TablePartialDecryptionsSubmited,
     CardsDisclosed,
+    CardsMucked {
+        player_id: ActorId,
+    },
+    CardsShown {
+        player_id: ActorId,
+        cards: Vec<Card>,
+    },
+    RunItTwiceAgreed {
+        player_id: ActorId,
+    },
+    RabbitHunted {
+        cards: Vec<Card>,
+    },
+    CardsDiscarded {
+        player_id: ActorId,
+        count: u8,
+    },
     GameCanceled,
     WaitingForCardsToBeDisclosed,
     WaitingForAllTableCardsToBeDisclosed,
     RegisteredToTheNextRound {
         participant_id: ActorId,
         pk: ZkPublicKey,
+        seat: u8,
+    },
+    PlayerTimedOut {
+        player_id: ActorId,
+        forfeited: u128,
+    },
+    BlindsRaised {
+        level: u32,
+        small_blind: u128,
+        big_blind: u128,
+    },
+    PlayerEliminated {
+        player_id: ActorId,
+        place: u32,
+    },
+    TournamentFinished {
+        standings: Vec<(ActorId, u128)>,
+    },
+    PrizesPaid,
+    StraddleIsSet {
+        player_id: ActorId,
+        amount: u128,
     },
 }
 
 pub struct PokerService(());
 
 impl PokerService {
+    /// The admin's key proof is bound to the deployer (the factory), since the
+    /// lobby address is not known before the program is created.
     pub fn init(
         config: Config,
         pts_actor_id: ActorId,
         pk: ZkPublicKey,
+        pk_proof: SchnorrProofBytes,
         zk_verification_id: ActorId,
-    ) -> Self {
+        ms_per_block: u64,
+    ) -> Result<Self, PokerError> {
+        if !verify::verify_secret_key_knowledge(&pk, config.admin_id, msg::source(), pk_proof) {
+            return Err(PokerError::InvalidSecretKeyProof);
+        }
+        if config.min_players_to_start < 2
+            || config.min_players_to_start > config.max_players
+            || config.max_players as usize > config.game_variant.max_players()
+        {
+            return Err(PokerError::InvalidConfig);
+        }
+        if config.hi_lo && !config.game_variant.supports_hi_lo() {
+            return Err(PokerError::InvalidConfig);
+        }
+        if matches!(
+            &config.game_mode,
+            GameMode::SitAndGo(tournament) if !tournament.is_valid(config.max_players)
+        ) {
+            return Err(PokerError::InvalidTournament);
+        }
+        // the turn timer is a delayed message, its delay in blocks has to fit in u32
+        if ms_per_block == 0 || config.time_per_move_ms.div_ceil(ms_per_block) >= u32::MAX.into() {
+            return Err(PokerError::InvalidConfig);
+        }
         let participants = vec![(
             config.admin_id,
             Participant {
                 name: config.admin_name.clone(),
                 balance: config.starting_bank,
                 pk: pk.clone(),
+                seat: 0,
             },
         )];
         let mut active_participants = TurnManager::new();
         active_participants.add(config.admin_id);
 
-        let (original_deck, original_card_map) = init_deck_and_card_map();
+        let (original_deck, original_card_map) = init_deck_and_card_map(config.game_variant.deck());
         unsafe {
             STORAGE = Some(Storage {
                 zk_verification_id,
@@ -214,9 +459,19 @@ impl PokerService {
                 agg_pub_key: pk,
                 revealed_players: HashMap::new(),
                 submitted_decrypters: HashSet::new(),
+                phase_deadline: None,
+                turn_nonce: 0,
+                ms_per_block,
+                tournament: None,
+                positions: None,
+                showdown_order: Vec::new(),
+                mucked: Vec::new(),
+                run_it_twice: Vec::new(),
+                drawing: Vec::new(),
+                discards: Vec::new(),
             });
         }
-        Self(())
+        Ok(Self(()))
     }
     fn get_mut(&mut self) -> &'static mut Storage {
         unsafe { STORAGE.as_mut().expect("Storage is not initialized") }
@@ -226,21 +481,73 @@ impl PokerService {
     }
 }
 
-async fn pts_transfer(pts_actor_id: ActorId, from: ActorId, to: ActorId, amount: u128) {
+/// Errors of the factory's `DeleteLobby` reply, in the order of the factory's `PokerFactoryError`.
+///
+/// The factory client can't be a dependency here, it's built from the lobby's own client.
+#[derive(Debug, Decode)]
+#[codec(crate = sails_rs::scale_codec)]
+#[allow(dead_code)]
+enum PokerFactoryError {
+    AccessDenied,
+    NotLobbyCreator,
+    WrongValue,
+    TimerTooShort,
+    TimerTooLong,
+    InvalidBettingStructure,
+    InvalidTournament,
+    InvalidTableSize,
+    HiLoNotSupported,
+    LowPtsBalance,
+    LobbyCreationFailed,
+    PtsCallFailed,
+    LobbyNotFound,
+}
+
+async fn pts_transfer(
+    pts_actor_id: ActorId,
+    from: ActorId,
+    to: ActorId,
+    amount: u128,
+) -> Result<(), PokerError> {
     let request = pts_io::Transfer::encode_call(from, to, amount);
 
-    msg::send_bytes_for_reply(pts_actor_id, request, 0, 0)
-        .expect("Error in async message to PTS contract")
+    let reply = msg::send_bytes_for_reply(pts_actor_id, request, 0, 0)
+        .map_err(|_| PokerError::PtsTransferFailed)?
+        .await
+        .map_err(|_| PokerError::PtsTransferFailed)?;
+
+    pts_io::Transfer::decode_reply(reply)
+        .map_err(|_| PokerError::PtsTransferFailed)?
+        .map_err(|_| PokerError::PtsTransferFailed)
+}
+
+async fn pts_batch_transfer(
+    pts_actor_id: ActorId,
+    ids: Vec<ActorId>,
+    points: Vec<u128>,
+) -> Result<(), PokerError> {
+    let request = pts_io::BatchTransfer::encode_call(exec::program_id(), ids, points);
+
+    let reply = msg::send_bytes_for_reply(pts_actor_id, request, 0, 0)
+        .map_err(|_| PokerError::PtsTransferFailed)?
         .await
-        .expect("PTS: Error transfer points to player");
+        .map_err(|_| PokerError::PtsTransferFailed)?;
+
+    pts_io::BatchTransfer::decode_reply(reply)
+        .map_err(|_| PokerError::PtsTransferFailed)?
+        .map_err(|_| PokerError::PtsTransferFailed)
 }
 
-fn process_blind(storage: &mut Storage, player_id: ActorId, blind_amount: u128) {
+fn process_blind(
+    storage: &mut Storage,
+    player_id: ActorId,
+    blind_amount: u128,
+) -> Result<(), PokerError> {
     let (_, participant) = storage
         .participants
         .iter_mut()
         .find(|(id, _)| *id == player_id)
-        .expect("Participant not found");
+        .ok_or(PokerError::NoSuchPlayer)?;
 
     let amount = participant.balance.min(blind_amount);
 
@@ -257,47 +564,129 @@ fn process_blind(storage: &mut Storage, player_id: ActorId, blind_amount: u128)
     *storage.betting_bank.entry(player_id).or_default() += amount;
 
     participant.balance -= amount;
+    Ok(())
+}
+
+/// The first player after `seat` who is still in `rotation`, with their seat.
+///
+/// `participants` must be sorted by seat.
+fn next_in_rotation(
+    participants: &[(ActorId, Participant)],
+    rotation: &TurnManager<ActorId>,
+    seat: u8,
+) -> Option<(u8, ActorId)> {
+    let mut in_rotation = participants
+        .iter()
+        .filter(|(id, _)| rotation.all().contains(id))
+        .map(|(id, participant)| (participant.seat, *id));
+    let first = in_rotation.clone().next();
+    in_rotation.find(|(next, _)| *next > seat).or(first)
+}
+
+/// Antes are dead money: they go to the pot, but don't count towards the bets of the round.
+fn process_ante(storage: &mut Storage, player_id: ActorId, ante: u128) -> Result<(), PokerError> {
+    let (_, participant) = storage
+        .participants
+        .iter_mut()
+        .find(|(id, _)| *id == player_id)
+        .ok_or(PokerError::NoSuchPlayer)?;
+
+    let amount = participant.balance.min(ante);
+
+    // a player who can't cover more than the ante has nothing left to bet
+    if participant.balance <= ante {
+        storage.active_participants.remove(&player_id);
+        storage.all_in_players.push(player_id);
+    }
+
+    *storage.betting_bank.entry(player_id).or_default() += amount;
+
+    participant.balance -= amount;
+    Ok(())
+}
+
+/// Returns the smallest total bet of the round that makes a full raise
+/// and the largest one allowed by the betting structure (`None` if unlimited).
+fn raise_bounds(
+    config: &Config,
+    stage: &Stage,
+    betting: &BettingStage,
+    pot: u128,
+    already_invested: u128,
+) -> Result<(u128, Option<u128>), PokerError> {
+    // a raise is at least the size of the last bet or raise, the big blind when opening
+    let min_raise = betting.current_bet + betting.last_raise;
+    match config.betting_structure() {
+        BettingStructure::NoLimit => Ok((min_raise, None)),
+        BettingStructure::PotLimit => {
+            let call = betting.current_bet - already_invested;
+            Ok((min_raise, Some(betting.current_bet + pot + call)))
+        }
+        BettingStructure::FixedLimit {
+            small_bet,
+            big_bet,
+            max_raises,
+        } => {
+            if betting.current_bet > 0 && betting.raises >= max_raises {
+                return Err(PokerError::RaiseCapReached);
+            }
+            let bet_size = match stage {
+                Stage::Turn | Stage::River | Stage::PostDraw => big_bet,
+                _ => small_bet,
+            };
+            let fixed_bet = betting.current_bet + bet_size;
+            Ok((fixed_bet, Some(fixed_bet)))
+        }
+    }
 }
 
-async fn remove_participant_if_registered(
+/// Takes the balance of a player who can leave the lobby now, `None` if they are not registered.
+fn take_balance_if_registered(
     storage: &mut Storage,
     player_id: ActorId,
-) -> Option<u128> {
+) -> Result<Option<u128>, PokerError> {
     // The main list of participants
-    if let Some((_, participant)) = storage.participants.iter().find(|(id, _)| *id == player_id) {
+    if let Some((_, participant)) = storage
+        .participants
+        .iter_mut()
+        .find(|(id, _)| *id == player_id)
+    {
         match storage.status {
             Status::Registration
-            | Status::WaitingShuffleVerification
+            | Status::WaitingShuffle { .. }
             | Status::WaitingStart
             | Status::Finished { .. } => (),
-            _ => panic!("Wrong status"),
+            _ => return Err(PokerError::WrongStatus),
         }
-
-        let balance = participant.balance;
-
-        storage.participants.retain(|(id, _)| *id != player_id);
-        storage
-            .active_participants
-            .remove_and_update_first_index(&player_id);
-
-        storage.status = Status::Registration;
-        return Some(balance);
+        return Ok(Some(core::mem::take(&mut participant.balance)));
     }
 
     // Waiting participants
     if let Some((_, participant)) = storage
         .waiting_participants
-        .iter()
+        .iter_mut()
         .find(|(id, _)| *id == player_id)
     {
-        let balance = participant.balance;
+        return Ok(Some(core::mem::take(&mut participant.balance)));
+    }
+
+    Ok(None)
+}
+
+/// Removes a player who has left the lobby from the participants or the waiting list.
+fn remove_participant(storage: &mut Storage, player_id: ActorId) {
+    if let Some((_, participant)) = storage.participants.iter().find(|(id, _)| *id == player_id) {
+        storage.agg_pub_key = substract_agg_pub_key(&storage.agg_pub_key, &participant.pk);
+
+        storage.participants.retain(|(id, _)| *id != player_id);
+        storage.active_participants.remove(&player_id);
+
+        storage.status = Status::Registration;
+    } else {
         storage
             .waiting_participants
             .retain(|(id, _)| *id != player_id);
-        return Some(balance);
     }
-
-    None
 }
 
 impl Storage {
@@ -313,151 +702,576 @@ impl Storage {
         self.all_in_players = Vec::new();
         self.already_invested_in_the_circle = HashMap::new();
         self.betting = None;
+        self.submitted_decrypters = HashSet::new();
+        self.phase_deadline = None;
+        self.showdown_order = Vec::new();
+        self.mucked = Vec::new();
+        self.run_it_twice = Vec::new();
+        self.drawing = Vec::new();
+        self.discards = Vec::new();
     }
 
-    pub fn refund_bets_to_players(&mut self) {
-        for (id, bet) in &self.betting_bank {
-            if *bet != 0 {
-                let (_, participant) = self
-                    .participants
-                    .iter_mut()
-                    .find(|(player_id, _)| player_id == id)
-                    .expect("There is no such participant");
-                participant.balance += *bet;
-            }
+    /// Whether everyone left in the all-in hand has agreed to run it twice.
+    fn runs_it_twice(&self) -> bool {
+        self.active_participants
+            .all()
+            .iter()
+            .chain(&self.all_in_players)
+            .all(|id| self.run_it_twice.contains(id))
+    }
+
+    /// The revealed board, and the second one if the hand was run twice.
+    fn boards(&self) -> ([Card; 5], Option<[Card; 5]>) {
+        let first: [Card; 5] = self.revealed_table_cards[..TABLE_CARDS]
+            .to_vec()
+            .try_into()
+            .expect("The board is not revealed");
+        let second_run = &self.revealed_table_cards[TABLE_CARDS..];
+        if second_run.is_empty() {
+            return (first, None);
         }
+        // the boards share the cards revealed before the all-in
+        let shared = TABLE_CARDS - second_run.len();
+        let second = [&first[..shared], second_run]
+            .concat()
+            .try_into()
+            .expect("The second board is not revealed");
+        (first, Some(second))
     }
-}
 
-fn get_player(session_for_account: &Option<ActorId>) -> ActorId {
-    let msg_src = msg::source();
-    let sessions = SessionStorage::get_session_map();
-    match session_for_account {
-        Some(account) => {
-            let session = sessions
-                .get(account)
-                .expect("This account has no valid session");
-            assert!(
-                session.expires > exec::block_timestamp(),
-                "The session has already expired"
-            );
-            assert_eq!(
-                session.key, msg_src,
-                "The account is not approved for this session"
-            );
-            *account
-        }
-        None => msg_src,
+    /// Moves to the showdown. The last aggressor of the final betting round shows first,
+    /// then the others clockwise; if nobody bet, it starts from the first seat after the button.
+    fn start_showdown(&mut self) {
+        let mut order = self.players_in_hand();
+        let first = self
+            .betting
+            .as_ref()
+            .and_then(|betting| betting.last_aggressor)
+            .and_then(|aggressor| order.iter().position(|id| *id == aggressor))
+            .unwrap_or(0);
+        order.rotate_left(first);
+
+        self.showdown_order = order;
+        self.status = Status::WaitingForCardsToBeDisclosed;
+        self.start_phase_timer();
     }
-}
 
-#[sails_rs::service(events = Event)]
-#[allow(clippy::new_without_default)]
-impl PokerService {
-    pub fn new() -> Self {
-        Self(())
+    /// Moves to the draw, where everyone left in the hand discards in turn
+    /// from the first seat after the button.
+    fn start_draw(&mut self) {
+        self.drawing = self.players_in_hand();
+        self.status = Status::Play { stage: Stage::Draw };
+        self.start_phase_timer();
     }
-    /// Registers a player by sending a transfer request to the PTS contract (starting_bank points).
-    ///
-    /// Panics if:
-    /// - status is not `Registration`;
-    /// - player is already registered.
-    ///
-    /// Sends a message to the PTS contract (pts_actor_id) to transfer points to this contract.
-    /// On success, updates participant data and emits a `Registered` event.
-    pub async fn register(
-        &mut self,
-        player_name: String,
-        pk: ZkPublicKey,
-        session_for_account: Option<ActorId>,
-    ) {
-        let storage = self.get_mut();
-        let player_id = get_player(&session_for_account);
-        if storage.participants.iter().any(|(id, _)| *id == player_id) {
-            panic!("Already registered");
-        }
 
-        if storage.participants.len() == 9 {
-            panic!("Alerady max amount of players");
-        }
+    /// Players who haven't folded, in seat order from the first seat after the button.
+    fn players_in_hand(&self) -> Vec<ActorId> {
+        self.clockwise_from_button()
+            .into_iter()
+            .filter(|id| {
+                self.active_participants.all().contains(id) || self.all_in_players.contains(id)
+            })
+            .collect()
+    }
 
-        pts_transfer(
-            storage.pts_actor_id,
-            player_id,
-            exec::program_id(),
-            storage.config.starting_bank,
-        )
-        .await;
+    /// Blinds of the current hand: the config ones in a cash game,
+    /// the ones of the current level in a tournament.
+    fn blinds(&self) -> (u128, u128) {
+        match (&self.config.game_mode, &self.tournament) {
+            (GameMode::SitAndGo(config), Some(tournament)) => {
+                let level = tournament.blinds(config);
+                (level.small_blind, level.big_blind)
+            }
+            _ => (self.config.small_blind, self.config.big_blind),
+        }
+    }
 
-        let participant = Participant {
-            name: player_name,
-            balance: storage.config.starting_bank,
-            pk: pk.clone(),
+    /// Moves the button and the blinds for a new hand by the dead button rule.
+    ///
+    /// The big blind goes to the next player in rotation, the small blind to the seat of
+    /// the last big blind and the button to the seat of the last small blind, even if
+    /// these players have left. Heads-up the button posts the small blind.
+    fn move_positions(&mut self) -> Positions {
+        let next = |seat| {
+            next_in_rotation(&self.participants, &self.active_participants, seat)
+                .expect("No players in rotation")
+                .0
         };
-        storage.agg_pub_key = calculate_agg_pub_key(&storage.agg_pub_key, &pk);
+        let heads_up = self.active_participants.len() == 2;
+        let positions = match &self.positions {
+            // the first hand is dealt as if the last seat had the button
+            None => {
+                let small_blind = next(self.config.max_players - 1);
+                let big_blind = next(small_blind);
+                let button = if heads_up {
+                    small_blind
+                } else {
+                    self.participants
+                        .iter()
+                        .rev()
+                        .find(|(id, _)| self.active_participants.all().contains(id))
+                        .expect("No players in rotation")
+                        .1
+                        .seat
+                };
+                Positions {
+                    button,
+                    small_blind,
+                    big_blind,
+                }
+            }
+            Some(last) if heads_up => {
+                let big_blind = next(last.big_blind);
+                let button = next(big_blind);
+                Positions {
+                    button,
+                    small_blind: button,
+                    big_blind,
+                }
+            }
+            Some(last) => Positions {
+                button: last.small_blind,
+                small_blind: last.big_blind,
+                big_blind: next(last.big_blind),
+            },
+        };
+        self.positions = Some(positions.clone());
+        positions
+    }
 
-        match storage.status {
-            Status::Registration => {
-                storage.participants.push((player_id, participant));
-                storage.active_participants.add(player_id);
+    /// Players in seat order, starting from the first seat after the button.
+    fn clockwise_from_button(&self) -> Vec<ActorId> {
+        let button = self.positions.as_ref().expect("No button").button;
+        let (before, after): (Vec<_>, Vec<_>) = self
+            .participants
+            .iter()
+            .partition(|(_, participant)| participant.seat <= button);
+        after.into_iter().chain(before).map(|(id, _)| *id).collect()
+    }
 
-                self.emit_event(Event::Registered {
-                    participant_id: player_id,
-                    pk,
-                })
-                .expect("Event Invocation Error");
-            }
-            _ => {
-                storage.waiting_participants.push((player_id, participant));
+    /// The player in `seat` if they still take part in the betting.
+    fn player_in_rotation(&self, seat: u8) -> Option<ActorId> {
+        self.participants
+            .iter()
+            .find(|(id, participant)| {
+                participant.seat == seat && self.active_participants.all().contains(id)
+            })
+            .map(|(id, _)| *id)
+    }
 
-                self.emit_event(Event::RegisteredToTheNextRound {
-                    participant_id: player_id,
-                    pk,
-                })
-                .expect("Event Invocation Error");
+    fn is_seat_taken(&self, seat: u8) -> bool {
+        self.participants
+            .iter()
+            .chain(self.waiting_participants.iter())
+            .any(|(_, participant)| participant.seat == seat)
+    }
+
+    /// Whether the game waits for a betting action.
+    fn is_betting(&self) -> bool {
+        matches!(
+            self.status,
+            Status::Play {
+                stage: Stage::PreFlop
+                    | Stage::Flop
+                    | Stage::Turn
+                    | Stage::River
+                    | Stage::PreDraw
+                    | Stage::PostDraw
             }
-        }
+        )
     }
 
-    /// Cancels player registration and refunds their balance via PTS contract.
-    ///
-    /// Panics if:
-    /// - current status is invalid for cancellation;
-    /// - caller is not a registered player.
-    ///
-    /// Sends a transfer request to PTS contract to return points to the player.
-    /// Removes player data and emits `RegistrationCanceled` event on success.
-    pub async fn cancel_registration(&mut self, session_for_account: Option<ActorId>) {
-        let storage = self.get_mut();
-        let player_id = get_player(&session_for_account);
+    /// Starts the timer of a phase that waits for player submissions.
+    fn start_phase_timer(&mut self) {
+        self.phase_deadline = Some(exec::block_timestamp() + self.config.time_per_move_ms);
+    }
 
-        if player_id == storage.config.admin_id {
-            panic!("Access denied");
-        }
+    /// Starts the timer of the current betting turn.
+    ///
+    /// The lobby sends itself a delayed `TurnTimeout` from a gas reservation, which
+    /// plays for the player if they don't act in `time_per_move_ms`. If there's no gas
+    /// left for the reservation, as in a turn started by the timer itself, nothing is
+    /// scheduled and anyone can call `turn_timeout` once the time is up.
+    fn start_turn_timer(&mut self) {
+        let Some(betting) = self.betting.as_mut() else {
+            return;
+        };
+        betting.last_active_time = Some(exec::block_timestamp());
+        self.turn_nonce += 1;
 
-        if let Some((_, participant)) = storage.participants.iter().find(|(id, _)| *id == player_id)
+        // fits in u32, it's checked at init
+        let delay = self.config.time_per_move_ms.div_ceil(self.ms_per_block) as u32;
+        let Ok(reservation_id) = exec::reserve_gas(GAS_FOR_TURN_TIMEOUT, delay + 1) else {
+            return;
+        };
+        let payload = [
+            "Poker".encode(),
+            "TurnTimeout".encode(),
+            self.turn_nonce.encode(),
+        ]
+        .concat();
+        if msg::send_bytes_delayed_from_reservation(
+            reservation_id,
+            exec::program_id(),
+            payload,
+            0,
+            delay,
+        )
+        .is_err()
         {
-            storage.agg_pub_key = substract_agg_pub_key(&storage.agg_pub_key, &participant.pk);
+            let _ = exec::unreserve_gas(reservation_id);
         }
-        if let Some(balance) = remove_participant_if_registered(storage, player_id).await {
-            pts_transfer(storage.pts_actor_id, exec::program_id(), player_id, balance).await;
+    }
 
-            self.emit_event(Event::RegistrationCanceled { player_id })
-                .expect("Event Error");
-        } else {
-            panic!("You are not registered");
+    /// Players who haven't made their submission in the current phase yet.
+    fn stalled_players(&self) -> Result<Vec<ActorId>, PokerError> {
+        let stalled = match &self.status {
+            Status::WaitingShuffle { turn } => vec![*turn],
+            Status::WaitingPartialDecryptionsForPlayersCards
+            | Status::WaitingForAllTableCardsToBeDisclosed
+            | Status::Play {
+                stage:
+                    Stage::WaitingTableCardsAfterPreFlop
+                    | Stage::WaitingTableCardsAfterFlop
+                    | Stage::WaitingTableCardsAfterTurn
+                    | Stage::WaitingDrawnCards,
+            } => self
+                .participants
+                .iter()
+                .map(|(id, _)| *id)
+                .filter(|id| !self.submitted_decrypters.contains(id))
+                .collect(),
+            Status::WaitingForCardsToBeDisclosed => {
+                self.showdown_order.first().into_iter().copied().collect()
+            }
+            Status::Play { stage: Stage::Draw } => {
+                self.drawing.first().into_iter().copied().collect()
+            }
+            _ => return Err(PokerError::WrongStatus),
+        };
+
+        Ok(stalled)
+    }
+
+    /// Checks the player's decryption shares of their hole cards and returns the cards.
+    fn decrypt_hole_cards(
+        &self,
+        player_id: ActorId,
+        player_decryptions: Vec<PartialDec>,
+    ) -> Result<Vec<Card>, PokerError> {
+        let hole_cards = self.config.game_variant.hole_cards();
+        if player_decryptions.len() != hole_cards {
+            return Err(PokerError::WrongNumberOfDecryptions);
+        }
+        let (_, participant) = self
+            .participants
+            .iter()
+            .find(|(id, _)| *id == player_id)
+            .ok_or(PokerError::NotRegistered)?;
+        let cards_entry = self
+            .partially_decrypted_cards
+            .get(&player_id)
+            .ok_or(PokerError::CardNotFound)?;
+        let pk = curve::deserialize_public_key(&participant.pk);
+        let g = EdwardsProjective::generator();
+        let lobby_id = exec::program_id();
+
+        let mut cards = Vec::new();
+        let mut decrypted = vec![false; hole_cards];
+        for PartialDec {
+            c0,
+            delta_c0,
+            proof,
+        } in player_decryptions
+        {
+            let idx = cards_entry
+                .iter()
+                .position(|card| curve::compare_points(&card.c0, &c0))
+                .ok_or(PokerError::CardNotFound)?;
+            // the same card can't be shown twice
+            if decrypted[idx] {
+                return Err(PokerError::CardNotFound);
+            }
+            decrypted[idx] = true;
+            let c0_point = curve::deserialize_bandersnatch_coords(&c0);
+            let delta_c0_neg = curve::deserialize_bandersnatch_coords(&delta_c0);
+            let delta_c0 = -delta_c0_neg;
+            let proof = proof.into_proof();
+            let context = DecryptionContext {
+                lobby_id,
+                round: self.round,
+                slot: CardSlot::Hole {
+                    owner: player_id,
+                    index: idx as u8,
+                },
+            };
+            if !verify::verify_chaum_pedersen(g, pk, c0_point, delta_c0, &proof, &context) {
+                return Err(PokerError::DecryptionVerificationFailed);
+            }
+            let current_c1_point = curve::deserialize_bandersnatch_coords(&cards_entry[idx].c1);
+            let new_c1_point = current_c1_point + delta_c0_neg;
+            let card = curve::find_card_by_point(&self.original_card_map, &new_c1_point)
+                .ok_or(PokerError::CardDecryptionFailed)?;
+            cards.push(card);
+        }
+
+        Ok(cards)
+    }
+
+    /// Applies the player's decryption shares to a copy of the table cards from `base_index`.
+    ///
+    /// Returns the updated table cards, and the decrypted cards if the other players
+    /// have already submitted their shares.
+    fn decrypt_table_cards(
+        &self,
+        pk: &ZkPublicKey,
+        player_decryptions: Vec<PartialDec>,
+        base_index: usize,
+        expected_count: usize,
+    ) -> Result<(Vec<EncryptedCard>, Option<Vec<Card>>), PokerError> {
+        if player_decryptions.len() != expected_count {
+            return Err(PokerError::WrongNumberOfDecryptions);
+        }
+
+        let pk = curve::deserialize_public_key(pk);
+        let g = EdwardsProjective::generator();
+        let lobby_id = exec::program_id();
+
+        // decryptions are applied to a copy, which replaces the table only if everything checks out
+        let mut table_cards = self.table_cards.clone();
+        for PartialDec {
+            c0,
+            delta_c0,
+            proof,
+        } in player_decryptions
+        {
+            let idx = find_table_idx_in_window(&table_cards, base_index, expected_count, &c0)
+                .ok_or(PokerError::CardNotFound)?;
+            let c0_point = curve::deserialize_bandersnatch_coords(&c0);
+            let delta_c0_neg = curve::deserialize_bandersnatch_coords(&delta_c0);
+            let delta_c0 = -delta_c0_neg;
+            let proof = proof.into_proof();
+            let context = DecryptionContext {
+                lobby_id,
+                round: self.round,
+                slot: CardSlot::Table { index: idx as u8 },
+            };
+            if !verify::verify_chaum_pedersen(g, pk, c0_point, delta_c0, &proof, &context) {
+                return Err(PokerError::DecryptionVerificationFailed);
+            }
+            let current_c1_point = curve::deserialize_bandersnatch_coords(&table_cards[idx].c1);
+            let new_c1_point = current_c1_point + delta_c0_neg;
+            table_cards[idx].c1 = curve::serialize_bandersnatch_coords(&new_c1_point);
+        }
+
+        if self.submitted_decrypters.len() + 1 < self.participants.len() {
+            return Ok((table_cards, None));
+        }
+        let mut revealed_cards = Vec::with_capacity(expected_count);
+        for card in &table_cards[base_index..base_index + expected_count] {
+            let c1_point = curve::deserialize_bandersnatch_coords(&card.c1);
+            let card = curve::find_card_by_point(&self.original_card_map, &c1_point)
+                .ok_or(PokerError::CardDecryptionFailed)?;
+            revealed_cards.push(card);
+        }
+
+        Ok((table_cards, Some(revealed_cards)))
+    }
+
+    pub fn refund_bets_to_players(&mut self) {
+        for (id, bet) in &self.betting_bank {
+            if *bet != 0 {
+                let (_, participant) = self
+                    .participants
+                    .iter_mut()
+                    .find(|(player_id, _)| player_id == id)
+                    .expect("There is no such participant");
+                participant.balance += *bet;
+            }
+        }
+    }
+}
+
+fn get_player(session_for_account: &Option<ActorId>) -> Result<ActorId, PokerError> {
+    let msg_src = msg::source();
+    let sessions = SessionStorage::get_session_map();
+    match session_for_account {
+        Some(account) => {
+            let session = sessions.get(account).ok_or(PokerError::NoValidSession)?;
+            if session.expires <= exec::block_timestamp() {
+                return Err(PokerError::SessionExpired);
+            }
+            if session.key != msg_src {
+                return Err(PokerError::SessionNotApproved);
+            }
+            Ok(*account)
+        }
+        None => Ok(msg_src),
+    }
+}
+
+#[sails_rs::service(events = Event)]
+#[allow(clippy::new_without_default)]
+impl PokerService {
+    pub fn new() -> Self {
+        Self(())
+    }
+    /// Registers a player by sending a transfer request to the PTS contract (starting_bank points).
+    ///
+    /// The player takes `seat`, or the first free one if it's `None`.
+    ///
+    /// Fails if:
+    /// - status is not `Registration`;
+    /// - player is already registered;
+    /// - the sit-and-go has already started;
+    /// - the seat doesn't exist or is taken, or there are no free seats;
+    /// - `pk_proof` does not prove knowledge of the secret key for `pk`
+    ///   (bound to the player and this lobby).
+    ///
+    /// Sends a message to the PTS contract (pts_actor_id) to transfer points to this contract.
+    /// On success, updates participant data and emits a `Registered` event.
+    pub async fn register(
+        &mut self,
+        player_name: String,
+        pk: ZkPublicKey,
+        pk_proof: SchnorrProofBytes,
+        seat: Option<u8>,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+        if storage.participants.iter().any(|(id, _)| *id == player_id) {
+            return Err(PokerError::AlreadyRegistered);
         }
+        if storage.tournament.is_some() {
+            return Err(PokerError::WrongStatus);
+        }
+
+        if !verify::verify_secret_key_knowledge(&pk, player_id, exec::program_id(), pk_proof) {
+            return Err(PokerError::InvalidSecretKeyProof);
+        }
+
+        let seat = match seat {
+            Some(seat) if seat >= storage.config.max_players => {
+                return Err(PokerError::InvalidSeat);
+            }
+            Some(seat) if storage.is_seat_taken(seat) => return Err(PokerError::SeatTaken),
+            Some(seat) => seat,
+            None => (0..storage.config.max_players)
+                .find(|seat| !storage.is_seat_taken(*seat))
+                .ok_or(PokerError::LobbyIsFull)?,
+        };
+
+        pts_transfer(
+            storage.pts_actor_id,
+            player_id,
+            exec::program_id(),
+            storage.config.starting_bank,
+        )
+        .await?;
+
+        let participant = Participant {
+            name: player_name,
+            balance: storage.config.starting_bank,
+            pk: pk.clone(),
+            seat,
+        };
+        storage.agg_pub_key = calculate_agg_pub_key(&storage.agg_pub_key, &pk);
+
+        match storage.status {
+            Status::Registration => {
+                // the order of the participants is the seat order
+                let position = storage
+                    .participants
+                    .partition_point(|(_, participant)| participant.seat < seat);
+                storage
+                    .participants
+                    .insert(position, (player_id, participant));
+                storage.active_participants.clear_all();
+                for (id, _) in storage.participants.iter() {
+                    storage.active_participants.add(*id);
+                }
+
+                self.emit_event(Event::Registered {
+                    participant_id: player_id,
+                    pk,
+                    seat,
+                })
+                .expect("Event Invocation Error");
+            }
+            _ => {
+                storage.waiting_participants.push((player_id, participant));
+
+                self.emit_event(Event::RegisteredToTheNextRound {
+                    participant_id: player_id,
+                    pk,
+                    seat,
+                })
+                .expect("Event Invocation Error");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancels player registration and refunds their balance via PTS contract.
+    ///
+    /// Fails if:
+    /// - current status is invalid for cancellation or the sit-and-go has started;
+    /// - caller is not a registered player.
+    ///
+    /// Sends a transfer request to PTS contract to return points to the player.
+    /// Removes player data and emits `RegistrationCanceled` event on success.
+    pub async fn cancel_registration(
+        &mut self,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+
+        if player_id == storage.config.admin_id {
+            return Err(PokerError::AccessDenied);
+        }
+        if storage.tournament.is_some() {
+            return Err(PokerError::WrongStatus);
+        }
+
+        // the balance is taken before the transfer, so a concurrent cancel can't refund it twice
+        let balance =
+            take_balance_if_registered(storage, player_id)?.ok_or(PokerError::NotRegistered)?;
+        if let Err(error) =
+            pts_transfer(storage.pts_actor_id, exec::program_id(), player_id, balance).await
+        {
+            if let Some((_, participant)) = storage
+                .participants
+                .iter_mut()
+                .chain(storage.waiting_participants.iter_mut())
+                .find(|(id, _)| *id == player_id)
+            {
+                participant.balance += balance;
+            }
+            return Err(error);
+        }
+        // a hand can't get past the shuffle without the player, so they are still free to leave
+        remove_participant(storage, player_id);
+
+        self.emit_event(Event::RegistrationCanceled { player_id })
+            .expect("Event Error");
+        Ok(())
     }
 
     /// Restarts the game, resetting status and refunding bets (if not Finished).
-    /// Panics if caller is not admin.
-    /// Resets game to WaitingShuffleVerification (if full) or Registration status.
+    /// Fails if caller is not admin or the lobby runs a sit-and-go, which restarts on its own.
+    /// Resets game to Registration status.
     /// Emits GameRestarted event with new status.
-    pub fn restart_game(&mut self, session_for_account: Option<ActorId>) {
+    pub fn restart_game(&mut self, session_for_account: Option<ActorId>) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        let player_id = get_player(&session_for_account);
+        let player_id = get_player(&session_for_account)?;
         if player_id != storage.config.admin_id {
-            panic!("Access denied");
+            return Err(PokerError::AccessDenied);
+        }
+        if storage.tournament.is_some() {
+            return Err(PokerError::WrongStatus);
         }
         if !matches!(storage.status, Status::Finished { .. }) {
             storage.refund_bets_to_players();
@@ -479,6 +1293,9 @@ impl PokerService {
         storage
             .participants
             .append(&mut storage.waiting_participants);
+        storage
+            .participants
+            .sort_by_key(|(_, participant)| participant.seat);
 
         for (id, _) in storage.participants.iter() {
             storage.active_participants.add(*id);
@@ -490,13 +1307,15 @@ impl PokerService {
             status: storage.status.clone(),
         })
         .expect("Event Invocation Error");
+        Ok(())
     }
 
     /// Admin-only function to terminate the lobby and refund all players.
     ///
-    /// Panics if:
+    /// Fails if:
     /// - caller is not admin
-    /// - wrong game status (not Registration/WaitingShuffleVerification/Finished/WaitingStart)
+    /// - wrong game status (not Registration/WaitingShuffle/Finished/WaitingStart)
+    /// - the sit-and-go is over, but the prizes aren't paid yet
     ///
     /// Performs:
     /// 1. Batch transfer of all player balances via PTS contract
@@ -504,21 +1323,28 @@ impl PokerService {
     /// 3. Emits Killed event and transfers remaining funds to admin
     ///
     /// WARNING: Irreversible operation
-    pub async fn kill(&mut self, session_for_account: Option<ActorId>) {
-        let storage = self.get();
-        let player_id = get_player(&session_for_account);
+    pub async fn kill(&mut self, session_for_account: Option<ActorId>) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
         if player_id != storage.config.admin_id {
-            panic!("Access denied");
+            return Err(PokerError::AccessDenied);
         }
         match storage.status {
             Status::Registration
-            | Status::WaitingShuffleVerification
+            | Status::WaitingShuffle { .. }
             | Status::Finished { .. }
             | Status::WaitingStart => {}
             _ => {
-                panic!("Wrong status");
+                return Err(PokerError::WrongStatus);
             }
         }
+        if storage
+            .tournament
+            .as_ref()
+            .is_some_and(|tournament| tournament.is_over() && !tournament.prizes_paid)
+        {
+            return Err(PokerError::WrongStatus);
+        }
         let mut ids = Vec::new();
         let mut points = Vec::new();
 
@@ -526,12 +1352,11 @@ impl PokerService {
             ids.push(*id);
             points.push(participant.balance);
         }
-        let request = pts_io::BatchTransfer::encode_call(exec::program_id(), ids, points);
-
-        msg::send_bytes_for_reply(storage.pts_actor_id, request, 0, 0)
-            .expect("Error in async message to PTS contract")
-            .await
-            .expect("PTS: Error batch transfer points to players");
+        pts_batch_transfer(storage.pts_actor_id, ids, points).await?;
+        // the balances are paid out, a retry after a failed factory call mustn't pay them again
+        for (_, participant) in storage.participants.iter_mut() {
+            participant.balance = 0;
+        }
 
         let request = [
             "PokerFactory".encode(),
@@ -540,24 +1365,32 @@ impl PokerService {
         ]
         .concat();
 
-        msg::send_bytes_for_reply(storage.factory_actor_id, request, 0, 0)
-            .expect("Error in sending message to PokerFactory")
+        let reply = msg::send_bytes_for_reply(storage.factory_actor_id, request, 0, 0)
+            .map_err(|_| PokerError::FactoryCallFailed)?
             .await
-            .expect("PokerFactory: Error DeleteLobby");
+            .map_err(|_| PokerError::FactoryCallFailed)?;
+        // the reply is prefixed with the service and method names, only the outcome matters here
+        let (_, _, result) =
+            <(String, String, Result<(), PokerFactoryError>)>::decode(&mut reply.as_slice())
+                .map_err(|_| PokerError::FactoryCallFailed)?;
+        result.map_err(|_| PokerError::FactoryCallFailed)?;
 
         self.emit_event(Event::Killed).expect("Notification Error");
         exec::exit(storage.config.admin_id);
     }
 
-    pub async fn cancel_game(&mut self, session_for_account: Option<ActorId>) {
+    pub async fn cancel_game(
+        &mut self,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        let player_id = get_player(&session_for_account);
+        let player_id = get_player(&session_for_account)?;
         if player_id != storage.config.admin_id {
-            panic!("Access denied");
+            return Err(PokerError::AccessDenied);
         }
         match storage.status {
             Status::Registration | Status::Finished { .. } => {
-                panic!("Wrong status");
+                return Err(PokerError::WrongStatus);
             }
             _ => {
                 storage.refund_bets_to_players();
@@ -568,13 +1401,159 @@ impl PokerService {
 
         self.emit_event(Event::GameCanceled)
             .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Ends a hand stalled in the shuffle, decryption or disclosure phase.
+    ///
+    /// Can be called by anyone once the phase deadline has passed. The players who
+    /// haven't made their submission forfeit their bets in the hand to the remaining
+    /// participants and leave the table, the rest of their balance is transferred back
+    /// to them. Bets of the other players are refunded and the game returns to
+    /// Registration. A sit-and-go knocks the stalled players out and goes on with the
+    /// next hand.
+    ///
+    /// At the showdown the player who hasn't shown their cards in time mucks them instead,
+    /// and the next one gets a new deadline.
+    ///
+    /// Fails if:
+    /// - wrong status (not a shuffle, decryption or disclosure phase),
+    ///   or another claim is waiting for the transfer of the balances
+    /// - the phase deadline hasn't passed yet
+    /// - the balances of the stalled players can't be transferred back
+    ///
+    /// Emits PlayerTimedOut for every stalled player, then GameCanceled
+    /// (CardsMucked at the showdown).
+    pub async fn claim_timeout(&mut self) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let stalled = storage.stalled_players()?;
+        let deadline = storage.phase_deadline.ok_or(PokerError::WrongStatus)?;
+        if exec::block_timestamp() < deadline {
+            return Err(PokerError::DeadlineNotReached);
+        }
+
+        if storage.status == Status::WaitingForCardsToBeDisclosed {
+            self.muck_cards(stalled[0]);
+            return Ok(());
+        }
+        // a player who doesn't draw in time stands pat
+        if storage.status == (Status::Play { stage: Stage::Draw }) {
+            return self.discard_cards(stalled[0], Vec::new());
+        }
+
+        // if everybody stalled, there is nobody to take the bets and they are just refunded
+        let penalize = stalled.len() < storage.participants.len();
+
+        // tournament chips only count at the table, in a cash game the stalled players
+        // take the rest of their balance with them
+        if penalize && storage.tournament.is_none() {
+            let points: Vec<u128> = stalled
+                .iter()
+                .map(|player_id| {
+                    let (_, participant) = storage
+                        .participants
+                        .iter_mut()
+                        .find(|(id, _)| id == player_id)
+                        .expect("There is no such participant");
+                    core::mem::take(&mut participant.balance)
+                })
+                .collect();
+            // the balances are taken and the deadline is cleared before the transfer,
+            // so a concurrent claim can't pay them twice
+            storage.phase_deadline = None;
+            if let Err(error) =
+                pts_batch_transfer(storage.pts_actor_id, stalled.clone(), points.clone()).await
+            {
+                for (player_id, balance) in stalled.iter().zip(points) {
+                    if let Some((_, participant)) = storage
+                        .participants
+                        .iter_mut()
+                        .find(|(id, _)| id == player_id)
+                    {
+                        participant.balance += balance;
+                    }
+                }
+                storage.phase_deadline = Some(deadline);
+                return Err(error);
+            }
+            // the balances are paid out, so the forfeit is finished even if a stalled
+            // player has made their submission while waiting for the reply
+        }
+
+        let mut timed_out = Vec::with_capacity(stalled.len());
+        let mut forfeited_total: u128 = 0;
+        for player_id in &stalled {
+            let forfeited = if penalize {
+                if let Some((_, participant)) =
+                    storage.participants.iter().find(|(id, _)| id == player_id)
+                {
+                    storage.agg_pub_key =
+                        substract_agg_pub_key(&storage.agg_pub_key, &participant.pk);
+                }
+                storage.betting_bank.remove(player_id).unwrap_or(0)
+            } else {
+                0
+            };
+            forfeited_total += forfeited;
+            timed_out.push((*player_id, forfeited));
+        }
+        if penalize {
+            storage.participants.retain(|(id, _)| !stalled.contains(id));
+        }
+
+        storage.refund_bets_to_players();
+        // odd chips go out one by one starting left of the button, as in the pots.
+        // The admin may have deleted the other players while the balances were paid out
+        let remaining = storage.participants.len().max(1) as u128;
+        let share = forfeited_total / remaining;
+        let mut odd_chips = forfeited_total % remaining;
+        for player_id in storage.clockwise_from_button() {
+            let (_, participant) = storage
+                .participants
+                .iter_mut()
+                .find(|(id, _)| *id == player_id)
+                .expect("There is no such participant");
+            participant.balance += share;
+            if odd_chips > 0 {
+                participant.balance += 1;
+                odd_chips -= 1;
+            }
+        }
+
+        storage.reset_for_new_game();
+        storage.active_participants.clear_all();
+        for (id, _) in storage.participants.iter() {
+            storage.active_participants.add(*id);
+        }
+        storage.status = Status::Registration;
+
+        for (player_id, forfeited) in timed_out {
+            self.emit_event(Event::PlayerTimedOut {
+                player_id,
+                forfeited,
+            })
+            .expect("Event Invocation Error");
+        }
+        self.emit_event(Event::GameCanceled)
+            .expect("Notification Error");
+
+        if let (true, Some(tournament)) = (penalize, storage.tournament.as_mut()) {
+            for player_id in stalled {
+                let place = tournament.eliminate(player_id);
+                self.emit_event(Event::PlayerEliminated { player_id, place })
+                    .expect("Event Invocation Error");
+            }
+        }
+        self.continue_tournament();
+        Ok(())
     }
 
     /// Admin-only function to forcibly remove a player and refund their balance.
     ///
-    /// Panics if:
+    /// Fails if:
     /// - caller is not admin or tries to delete themselves
-    /// - wrong game status (not Registration/WaitingShuffleVerification)
+    /// - wrong game status (not Registration/WaitingShuffle/WaitingStart)
+    ///   or the sit-and-go has started
     /// - player doesn't exist
     ///
     /// Performs:
@@ -586,114 +1565,218 @@ impl PokerService {
         &mut self,
         player_id: ActorId,
         session_for_account: Option<ActorId>,
-    ) {
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        if get_player(&session_for_account) != storage.config.admin_id
+        if get_player(&session_for_account)? != storage.config.admin_id
             || player_id == storage.config.admin_id
         {
-            panic!("Access denied");
+            return Err(PokerError::AccessDenied);
         }
-        if storage.status != Status::Registration
-            && storage.status != Status::WaitingShuffleVerification
-            && storage.status != Status::WaitingStart
+        if !matches!(
+            storage.status,
+            Status::Registration | Status::WaitingShuffle { .. } | Status::WaitingStart
+        ) || storage.tournament.is_some()
         {
-            panic!("Wrong status");
+            return Err(PokerError::WrongStatus);
         }
 
-        if let Some((_, participant)) = storage.participants.iter().find(|(id, _)| *id == player_id)
-        {
-            pts_transfer(
-                storage.pts_actor_id,
-                exec::program_id(),
-                player_id,
-                participant.balance,
-            )
-            .await;
+        let (_, participant) = storage
+            .participants
+            .iter()
+            .find(|(id, _)| *id == player_id)
+            .ok_or(PokerError::NoSuchPlayer)?;
+        pts_transfer(
+            storage.pts_actor_id,
+            exec::program_id(),
+            player_id,
+            participant.balance,
+        )
+        .await?;
 
-            storage.agg_pub_key = substract_agg_pub_key(&storage.agg_pub_key, &participant.pk);
-            storage.participants.retain(|(id, _)| *id != player_id);
-            storage
-                .active_participants
-                .remove_and_update_first_index(&player_id);
-            storage.status = Status::Registration;
-        } else {
-            panic!("There is no such player");
-        }
+        storage.agg_pub_key = substract_agg_pub_key(&storage.agg_pub_key, &participant.pk);
+        storage.participants.retain(|(id, _)| *id != player_id);
+        storage.active_participants.remove(&player_id);
+        storage.status = Status::Registration;
 
         self.emit_event(Event::PlayerDeleted { player_id })
             .expect("Event Invocation Error");
+        Ok(())
     }
 
-    pub async fn shuffle_deck(
+    /// Submits the caller's shuffle of the deck.
+    ///
+    /// Every participant shuffles exactly once, in seat order, each starting from
+    /// the deck produced by the previous one, so the final order stays hidden
+    /// unless all players collude.
+    ///
+    /// Fails if:
+    /// - wrong status (not WaitingShuffle) or it's not the caller's turn to shuffle
+    /// - the shuffle doesn't continue the chain (wrong key, nonce or input deck)
+    /// - the proof is rejected by the zk verification program
+    ///
+    /// Emits DeckShuffled, and DeckShuffleComplete once the last player has shuffled
+    /// and the cards are dealt.
+    pub async fn submit_shuffle(
         &mut self,
-        encrypted_deck: Vec<EncryptedCard>,
-        instances: Vec<VerificationVariables>,
-    ) {
+        instance: VerificationVariables,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        if storage.status != Status::WaitingShuffleVerification {
-            panic!("Wrong status");
+        let player_id = get_player(&session_for_account)?;
+        match storage.status {
+            Status::WaitingShuffle { turn } if turn == player_id => {}
+            Status::WaitingShuffle { .. } => return Err(PokerError::NotYourTurn),
+            _ => return Err(PokerError::WrongStatus),
         }
 
-        ShuffleChainValidator::validate_shuffle_chain(
-            &instances,
+        let shuffled_deck = ShuffleChainValidator::validate_shuffle_step(
+            &instance,
+            storage.config.game_variant.deck(),
             &storage.original_deck,
+            storage.encrypted_deck.as_deref(),
             &storage.agg_pub_key,
-            &encrypted_deck,
-        );
+            verify::shuffle_nonce(exec::program_id(), storage.round, player_id),
+        )?;
 
-        let request = zk_io::VerifyShuffle::encode_call(instances);
-        msg::send_bytes_for_reply(storage.zk_verification_id, request, 0, 0)
-            .expect("Error in async message to ZK contract")
+        let request = zk_io::VerifyShuffle::encode_call(vec![instance]);
+        let reply = msg::send_bytes_for_reply(storage.zk_verification_id, request, 0, 0)
+            .map_err(|_| PokerError::ZkVerificationFailed)?
             .await
-            .expect("PTS: Error ZK shuffle verification");
-
-        storage.status = Status::WaitingPartialDecryptionsForPlayersCards;
-        storage.encrypted_deck = Some(encrypted_deck);
+            .map_err(|_| PokerError::ZkVerificationFailed)?;
+        zk_io::VerifyShuffle::decode_reply(reply)
+            .map_err(|_| PokerError::ZkVerificationFailed)?
+            .map_err(|_| PokerError::ZkVerificationFailed)?;
+
+        // another shuffle of the same player may have been accepted while waiting for the reply
+        if storage.status != (Status::WaitingShuffle { turn: player_id }) {
+            return Err(PokerError::WrongStatus);
+        }
 
-        self.deal_player_cards();
-        self.deal_table_cards(5);
+        let position = storage
+            .participants
+            .iter()
+            .position(|(id, _)| *id == player_id)
+            .ok_or(PokerError::NoSuchPlayer)?;
 
-        self.emit_event(Event::DeckShuffleComplete)
+        storage.encrypted_deck = Some(shuffled_deck);
+        self.emit_event(Event::DeckShuffled { player_id })
             .expect("Event Invocation Error");
+
+        if let Some((next_player, _)) = storage.participants.get(position + 1) {
+            storage.status = Status::WaitingShuffle { turn: *next_player };
+            storage.start_phase_timer();
+        } else {
+            // the table size is checked against the deck at init
+            self.deal_player_cards()?;
+            let table_cards = storage.config.game_variant.table_cards();
+            if table_cards > 0 {
+                self.deal_table_cards(table_cards)?;
+            }
+            storage.status = Status::WaitingPartialDecryptionsForPlayersCards;
+            storage.start_phase_timer();
+
+            self.emit_event(Event::DeckShuffleComplete)
+                .expect("Event Invocation Error");
+        }
+
+        Ok(())
     }
 
     /// Admin-only function to start the poker game after setup.
     ///
-    /// Panics if:
+    /// Fails if:
     /// - caller is not admin
     /// - wrong status (not WaitingStart)
+    /// - the sit-and-go is already over, or it pays more places than there are players
     ///
     /// Performs:
-    /// 1. Processes small/big blinds (handles all-in cases)
-    /// 2. Initializes betting stage
-    /// 3. Updates game status and emits GameStarted event
+    /// 1. Starts the sit-and-go with the registered players, if the lobby runs one
+    /// 2. Processes antes and small/big blinds (handles all-in cases)
+    /// 3. Initializes betting stage
+    /// 4. Updates game status and emits GameStarted event
     ///
     /// Note: Handles edge cases where players can't cover blinds
-    pub async fn start_game(&mut self, session_for_account: Option<ActorId>) {
+    pub async fn start_game(
+        &mut self,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        if get_player(&session_for_account) != storage.config.admin_id {
-            panic!("Access denied");
+        if get_player(&session_for_account)? != storage.config.admin_id {
+            return Err(PokerError::AccessDenied);
         }
-        if storage.participants.len() < 2 {
-            panic!("Not enough participants");
+        if storage.participants.len() < storage.config.min_players_to_start as usize {
+            return Err(PokerError::NotEnoughParticipants);
         }
         if storage.status != Status::Registration {
-            panic!("Wrong status");
+            return Err(PokerError::WrongStatus);
+        }
+        if let GameMode::SitAndGo(config) = &storage.config.game_mode {
+            match &storage.tournament {
+                Some(tournament) if tournament.is_over() => return Err(PokerError::WrongStatus),
+                // a hand canceled by the admin or a timeout claim is simply replayed
+                Some(_) => {}
+                // every paid place needs a player
+                None if config.payouts.len() > storage.participants.len() => {
+                    return Err(PokerError::NotEnoughParticipants);
+                }
+                None => {
+                    storage.tournament = Some(TournamentState::new(
+                        storage.participants.len() as u32,
+                        storage.config.starting_bank,
+                        exec::block_timestamp(),
+                    ));
+                }
+            }
         }
 
-        storage.active_participants.set_first_index();
+        self.start_hand()
+    }
 
-        let sb_player = storage
-            .active_participants
-            .next()
-            .expect("No small blind player");
-        process_blind(storage, sb_player, storage.config.small_blind);
+    /// Posts the blinds of a new hand and waits for the deck to be shuffled.
+    ///
+    /// In a tournament the blinds go up first if the current level is over.
+    fn start_hand(&mut self) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let level_changed = match (&storage.config.game_mode, &mut storage.tournament) {
+            (GameMode::SitAndGo(config), Some(tournament)) => {
+                tournament.start_hand(config, exec::block_timestamp())
+            }
+            _ => false,
+        };
+        let (small_blind, big_blind) = storage.blinds();
+        if let (true, Some(tournament)) = (level_changed, &storage.tournament) {
+            self.emit_event(Event::BlindsRaised {
+                level: tournament.level,
+                small_blind,
+                big_blind,
+            })
+            .expect("Event Invocation Error");
+        }
 
-        let bb_player = storage
-            .active_participants
-            .next()
-            .expect("No big blind player");
-        process_blind(storage, bb_player, storage.config.big_blind);
+        let positions = storage.move_positions();
+
+        if storage.config.ante > 0 {
+            for player_id in storage.active_participants.all().clone() {
+                process_ante(storage, player_id, storage.config.ante)?;
+            }
+        }
+
+        // nobody posts a dead small blind, and a player all-in from the ante posts nothing
+        if let Some(sb_player) = storage.player_in_rotation(positions.small_blind) {
+            process_blind(storage, sb_player, small_blind)?;
+        }
+        if let Some(bb_player) = storage.player_in_rotation(positions.big_blind) {
+            process_blind(storage, bb_player, big_blind)?;
+        }
+
+        // preflop the player after the big blind acts first, heads-up it's the button
+        let (_, first_player) = next_in_rotation(
+            &storage.participants,
+            &storage.active_participants,
+            positions.big_blind,
+        )
+        .expect("The player must exist");
+        storage.active_participants.set_turn(&first_player);
 
         storage.betting = Some(BettingStage {
             turn: storage
@@ -701,112 +1784,477 @@ impl PokerService {
                 .next()
                 .expect("The player must exist"),
             last_active_time: None,
-            current_bet: storage.config.big_blind,
+            current_bet: big_blind,
             acted_players: vec![],
+            last_raise: big_blind,
+            raise_closed: vec![],
+            raises: 0,
+            last_aggressor: None,
         });
 
-        storage.status = Status::WaitingShuffleVerification;
-        storage.active_participants.new_round();
+        // players shuffle in seat order, starting from the first seat
+        storage.encrypted_deck = None;
+        storage.status = Status::WaitingShuffle {
+            turn: storage.participants[0].0,
+        };
+        storage.start_phase_timer();
         storage.round += 1;
 
         self.emit_event(Event::GameStarted)
             .expect("Event Invocation Error");
+        Ok(())
+    }
+
+    /// Posts a straddle of two big blinds before the cards are dealt.
+    ///
+    /// Only the player after the big blind can straddle, if the lobby allows it.
+    /// The straddle is a raise: preflop action starts with the next player
+    /// and the straddler acts last.
+    ///
+    /// Fails if:
+    /// - wrong status (the hand hasn't started or the preflop betting is already on)
+    /// - it's not the caller's turn after the blinds
+    /// - straddles are disabled, the caller has posted a blind,
+    ///   already straddled or can't cover the straddle
+    ///
+    /// Emits StraddleIsSet
+    pub fn post_straddle(
+        &mut self,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+        if !matches!(
+            storage.status,
+            Status::WaitingShuffle { .. } | Status::WaitingPartialDecryptionsForPlayersCards
+        ) {
+            return Err(PokerError::WrongStatus);
+        }
+        let (_, big_blind) = storage.blinds();
+        let betting = storage.betting.as_ref().expect("No betting");
+        if betting.turn != player_id {
+            return Err(PokerError::NotYourTurn);
+        }
+
+        let amount = 2 * big_blind;
+        let (_, participant) = storage
+            .participants
+            .iter()
+            .find(|(id, _)| *id == player_id)
+            .ok_or(PokerError::NotRegistered)?;
+        if !storage.config.allow_straddle
+            || storage
+                .already_invested_in_the_circle
+                .contains_key(&player_id)
+            || betting.current_bet != big_blind
+            || participant.balance <= amount
+        {
+            return Err(PokerError::StraddleNotAllowed);
+        }
+
+        process_blind(storage, player_id, amount)?;
+        let betting = storage.betting.as_mut().expect("No betting");
+        betting.current_bet = amount;
+        betting.last_raise = amount - big_blind;
+        betting.raises += 1;
+        betting.turn = storage
+            .active_participants
+            .next()
+            .expect("The player must exist");
+
+        self.emit_event(Event::StraddleIsSet { player_id, amount })
+            .expect("Event Invocation Error");
+        Ok(())
     }
 
-    fn deal_player_cards(&mut self) {
+    /// Moves a tournament on after a hand: knocks out the players left without chips,
+    /// then either starts the next hand or ranks the players and pays out the prizes.
+    ///
+    /// Does nothing in a cash game.
+    fn continue_tournament(&mut self) {
+        let storage = self.get_mut();
+        let (GameMode::SitAndGo(config), Some(tournament)) =
+            (&storage.config.game_mode, &mut storage.tournament)
+        else {
+            return;
+        };
+
+        // of the players busted in the same hand, the one with the smaller stack goes out first
+        let mut busted: Vec<(ActorId, u128)> = storage
+            .participants
+            .iter()
+            .filter(|(_, participant)| participant.balance == 0)
+            .map(|(id, _)| (*id, storage.betting_bank.get(id).copied().unwrap_or(0)))
+            .collect();
+        busted.sort_by_key(|(_, stack)| *stack);
+
+        for (player_id, _) in busted {
+            let (_, participant) = storage
+                .participants
+                .iter()
+                .find(|(id, _)| *id == player_id)
+                .expect("There is no such participant");
+            storage.agg_pub_key = substract_agg_pub_key(&storage.agg_pub_key, &participant.pk);
+            storage.participants.retain(|(id, _)| *id != player_id);
+
+            let place = tournament.eliminate(player_id);
+            self.emit_event(Event::PlayerEliminated { player_id, place })
+                .expect("Event Invocation Error");
+        }
+
+        if let [(winner, _)] = storage.participants.as_slice() {
+            tournament.finish(*winner, &config.payouts);
+            self.emit_event(Event::TournamentFinished {
+                standings: tournament.standings.clone(),
+            })
+            .expect("Event Invocation Error");
+
+            // the prizes are paid in a separate message, which can be retried if it fails
+            let payload = ["Poker".encode(), "PayPrizes".encode()].concat();
+            msg::send_bytes(exec::program_id(), payload, 0).expect("Error in sending PayPrizes");
+            return;
+        }
+
+        storage.reset_for_new_game();
+        storage.active_participants.clear_all();
+        for (id, _) in storage.participants.iter() {
+            storage.active_participants.add(*id);
+        }
+        // only seated players post blinds, so this doesn't fail; if it did,
+        // the bets go back and the admin can start the hand again
+        if self.start_hand().is_err() {
+            storage.refund_bets_to_players();
+            storage.status = Status::Registration;
+        }
+    }
+
+    /// Pays the prize pool of a finished sit-and-go by the payout table.
+    ///
+    /// The lobby sends it to itself when the tournament is over; anyone can
+    /// call it again if the transfer has failed.
+    ///
+    /// Fails if:
+    /// - the lobby doesn't run a sit-and-go or it isn't over yet
+    /// - the prizes are already paid
+    /// - the PTS transfer fails
+    ///
+    /// Emits PrizesPaid
+    pub async fn pay_prizes(&mut self) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let tournament = storage
+            .tournament
+            .as_mut()
+            .filter(|tournament| tournament.is_over() && !tournament.prizes_paid)
+            .ok_or(PokerError::WrongStatus)?;
+
+        let (ids, points) = tournament
+            .standings
+            .iter()
+            .filter(|(_, prize)| *prize > 0)
+            .cloned()
+            .unzip();
+        // marked before the transfer, so that a second call can't pay the prizes twice
+        tournament.prizes_paid = true;
+        if let Err(error) = pts_batch_transfer(storage.pts_actor_id, ids, points).await {
+            tournament.prizes_paid = false;
+            return Err(error);
+        }
+
+        // the chips have been exchanged for the prizes
+        for (_, participant) in storage.participants.iter_mut() {
+            participant.balance = 0;
+        }
+
+        self.emit_event(Event::PrizesPaid)
+            .expect("Event Invocation Error");
+        Ok(())
+    }
+
+    fn deal_player_cards(&mut self) -> Result<(), PokerError> {
         let storage = self.get_mut();
         let deck = storage.encrypted_deck.as_ref().expect("No encrypted deck");
         let mut pos = storage.deck_position;
 
+        let hole_cards = storage.config.game_variant.hole_cards();
+        if pos + hole_cards * storage.participants.len() > deck.len() {
+            return Err(PokerError::NotEnoughCards);
+        }
+
         let mut dealt = Vec::new();
         for id in storage.participants.iter().map(|(id, _)| id) {
-            if pos + 2 > deck.len() {
-                panic!("Not enough cards");
-            }
+            let cards = deck[pos..pos + hole_cards].to_vec();
 
-            let card1 = deck[pos].clone();
-            let card2 = deck[pos + 1].clone();
+            storage.encrypted_cards.insert(*id, cards.clone());
 
-            storage
-                .encrypted_cards
-                .insert(*id, [card1.clone(), card2.clone()]);
-
-            dealt.push((*id, [card1, card2]));
+            dealt.push((*id, cards));
 
-            pos += 2;
+            pos += hole_cards;
         }
 
         storage.deck_position = pos;
         self.emit_event(Event::CardsDealtToPlayers(dealt))
             .expect("Event Invocation Error");
+        Ok(())
     }
 
+    /// Submits the caller's decryption shares of the other players' cards: every dealt card
+    /// before the first betting round, and the replacement cards after the draw.
     pub async fn submit_partial_decryptions(
         &mut self,
         player_decryptions: Vec<PartialDec>,
         session_for_account: Option<ActorId>,
-    ) {
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        let player_id = get_player(&session_for_account);
-        if !storage.submitted_decrypters.insert(player_id) {
-            panic!("Already submitted decryptions")
+        let player_id = get_player(&session_for_account)?;
+        // hand positions of the cards to decrypt
+        let slots: Vec<(ActorId, usize)> = match storage.status {
+            Status::WaitingPartialDecryptionsForPlayersCards => storage
+                .encrypted_cards
+                .iter()
+                .flat_map(|(owner, cards)| (0..cards.len()).map(move |idx| (*owner, idx)))
+                .collect(),
+            Status::Play {
+                stage: Stage::WaitingDrawnCards,
+            } => storage
+                .discards
+                .iter()
+                .flat_map(|(owner, positions)| {
+                    positions.iter().map(move |idx| (*owner, *idx as usize))
+                })
+                .collect(),
+            _ => return Err(PokerError::WrongStatus),
+        };
+        if storage.submitted_decrypters.contains(&player_id) {
+            return Err(PokerError::AlreadySubmitted);
         }
         let amounts_of_players = storage.participants.len();
-        assert_eq!(
-            player_decryptions.len(),
-            amounts_of_players * 2 - 2,
-            "Not enough decryptions"
-        );
+        // every card of the other players
+        let slots: Vec<_> = slots
+            .into_iter()
+            .filter(|(owner, _)| *owner != player_id)
+            .collect();
+        if player_decryptions.len() != slots.len() {
+            return Err(PokerError::WrongNumberOfDecryptions);
+        }
         let (_, participant) = storage
             .participants
             .iter()
             .find(|(id, _)| *id == player_id)
-            .expect("Participant not found");
+            .ok_or(PokerError::NotRegistered)?;
         let pk = curve::deserialize_public_key(&participant.pk);
         let g = EdwardsProjective::generator();
+        let lobby_id = exec::program_id();
+
+        // all proofs are verified before the first card is updated
+        let mut deltas = Vec::with_capacity(player_decryptions.len());
         for PartialDec {
             c0,
             delta_c0,
             proof,
         } in player_decryptions
         {
+            let (owner, idx) = locate_owner_and_index(&storage.encrypted_cards, &c0)
+                .filter(|slot| slots.contains(slot))
+                .ok_or(PokerError::CardNotFound)?;
+            // every card is decrypted once
+            if deltas.iter().any(|(o, i, _)| (*o, *i) == (owner, idx)) {
+                return Err(PokerError::CardNotFound);
+            }
             let c0_point = curve::deserialize_bandersnatch_coords(&c0);
             let delta_c0_neg = curve::deserialize_bandersnatch_coords(&delta_c0);
             let delta_c0 = -delta_c0_neg;
             let proof = proof.into_proof();
-            assert!(
-                verify::verify_chaum_pedersen(g, pk, c0_point, delta_c0, &proof),
-                "Decryption verification failed"
-            );
-            let (owner, idx) = locate_owner_and_index(&storage.encrypted_cards, &c0)
-                .expect("Target card not found for given c0");
+            let context = DecryptionContext {
+                lobby_id,
+                round: storage.round,
+                slot: CardSlot::Hole {
+                    owner,
+                    index: idx as u8,
+                },
+            };
+            if !verify::verify_chaum_pedersen(g, pk, c0_point, delta_c0, &proof, &context) {
+                return Err(PokerError::DecryptionVerificationFailed);
+            }
+            deltas.push((owner, idx, delta_c0_neg));
+        }
+
+        for (owner, idx, delta_c0_neg) in deltas {
+            let entry = storage
+                .partially_decrypted_cards
+                .entry(owner)
+                .or_insert_with(|| {
+                    storage
+                        .encrypted_cards
+                        .get(&owner)
+                        .expect("Missing owner in encrypted_cards")
+                        .clone()
+                });
+
+            let current_c1_point = curve::deserialize_bandersnatch_coords(&entry[idx].c1);
+            let new_c1_point = current_c1_point + delta_c0_neg;
+            entry[idx].c1 = curve::serialize_bandersnatch_coords(&new_c1_point);
+        }
+        storage.submitted_decrypters.insert(player_id);
+
+        if storage.submitted_decrypters.len() == amounts_of_players {
+            storage.submitted_decrypters.clear();
+            self.emit_event(Event::AllPartialDecryptionsSubmited)
+                .expect("Event Invocation Error");
+            if storage.status == Status::WaitingPartialDecryptionsForPlayersCards {
+                storage.status = Status::Play {
+                    stage: storage.config.game_variant.first_stage(),
+                };
+                storage.start_turn_timer();
+            } else {
+                self.finish_draw();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Discards the cards at the given positions of the caller's hand in a draw game.
+    ///
+    /// The cards are discarded without being decrypted, so their values stay hidden,
+    /// and they are replaced from the deck once everyone has drawn. An empty list
+    /// stands pat. The players draw in turn from the first seat after the button.
+    ///
+    /// Only the values are hidden: the positions are sent in the clear and kept in
+    /// the state until the hand ends, and `CardsDiscarded` announces the count. The
+    /// others learn how many cards were drawn and which hole card slots they went
+    /// to, so the cards kept can be told from the drawn ones at the showdown.
+    ///
+    /// Fails if:
+    /// - the hand is not at the draw;
+    /// - it's not the player's turn to draw;
+    /// - a position is repeated or out of the hand, or too many cards are discarded;
+    /// - the deck has not enough cards left to replace the discards.
+    ///
+    /// Emits `CardsDiscarded`, and `CardsDealtToPlayers` with the replacements once
+    /// everyone has drawn.
+    pub fn draw(
+        &mut self,
+        discards: Vec<u8>,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+        match storage.status {
+            Status::Play { stage: Stage::Draw } => {}
+            Status::Play { .. } => return Err(PokerError::WrongStage),
+            _ => return Err(PokerError::WrongStatus),
+        }
+        if storage.drawing.first() != Some(&player_id) {
+            return Err(PokerError::NotYourTurn);
+        }
+        let variant = &storage.config.game_variant;
+        let mut positions = discards.clone();
+        positions.sort();
+        positions.dedup();
+        if positions.len() != discards.len()
+            || discards.len() > variant.max_discards()
+            || discards
+                .iter()
+                .any(|idx| *idx as usize >= variant.hole_cards())
+        {
+            return Err(PokerError::InvalidDiscard);
+        }
+        let deck = storage.encrypted_deck.as_ref().expect("No encrypted deck");
+        let discarded: usize = storage
+            .discards
+            .iter()
+            .map(|(_, positions)| positions.len())
+            .sum();
+        if storage.deck_position + discarded + discards.len() > deck.len() {
+            return Err(PokerError::NotEnoughCards);
+        }
+
+        self.discard_cards(player_id, discards)
+    }
+
+    fn discard_cards(&mut self, player_id: ActorId, discards: Vec<u8>) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        storage.drawing.retain(|id| *id != player_id);
+        storage.start_phase_timer();
+
+        self.emit_event(Event::CardsDiscarded {
+            player_id,
+            count: discards.len() as u8,
+        })
+        .expect("Event Error");
+        if !discards.is_empty() {
+            storage.discards.push((player_id, discards));
+        }
+
+        if storage.drawing.is_empty() {
+            self.deal_draw_cards()?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the discarded cards with the next cards of the deck. The other players
+    /// then submit their decryption shares of them, as for the hole cards.
+    fn deal_draw_cards(&mut self) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        if storage.discards.is_empty() {
+            self.finish_draw();
+            return Ok(());
+        }
+        let deck = storage.encrypted_deck.as_ref().expect("No encrypted deck");
+        let discarded: usize = storage
+            .discards
+            .iter()
+            .map(|(_, positions)| positions.len())
+            .sum();
+        if storage.deck_position + discarded > deck.len() {
+            return Err(PokerError::NotEnoughCards);
+        }
 
-            let entry = storage
-                .partially_decrypted_cards
-                .entry(owner)
-                .or_insert_with(|| {
-                    storage
-                        .encrypted_cards
-                        .get(&owner)
-                        .expect("Missing owner in encrypted_cards")
-                        .clone()
-                });
+        let mut dealt = Vec::new();
+        for (id, positions) in &storage.discards {
+            let pos = storage.deck_position;
+            let cards = deck[pos..pos + positions.len()].to_vec();
+            storage.deck_position += positions.len();
 
-            let current_c1_point = curve::deserialize_bandersnatch_coords(&entry[idx].c1);
-            let new_c1_point = current_c1_point + delta_c0_neg;
-            entry[idx].c1 = curve::serialize_bandersnatch_coords(&new_c1_point);
+            let hand = storage
+                .encrypted_cards
+                .get_mut(id)
+                .expect("Missing player in encrypted_cards");
+            let partially_decrypted = storage
+                .partially_decrypted_cards
+                .get_mut(id)
+                .expect("Missing player in partially_decrypted_cards");
+            for (idx, card) in positions.iter().zip(&cards) {
+                hand[*idx as usize] = card.clone();
+                partially_decrypted[*idx as usize] = card.clone();
+            }
+            dealt.push((*id, cards));
         }
 
-        if storage.submitted_decrypters.len() == amounts_of_players {
+        storage.status = Status::Play {
+            stage: Stage::WaitingDrawnCards,
+        };
+        storage.start_phase_timer();
+        self.emit_event(Event::CardsDealtToPlayers(dealt))
+            .expect("Event Invocation Error");
+        Ok(())
+    }
+
+    /// Moves on after the draw: to the betting round after it,
+    /// or to the showdown if nobody can bet any more.
+    fn finish_draw(&mut self) {
+        let storage = self.get_mut();
+        if storage.active_participants.len() <= 1 {
+            storage.start_showdown();
+            self.emit_event(Event::WaitingForCardsToBeDisclosed)
+                .expect("Event Error");
+        } else {
             storage.status = Status::Play {
-                stage: Stage::PreFlop,
+                stage: Stage::PostDraw,
             };
-            if let Some(betting) = &mut storage.betting {
-                betting.last_active_time = Some(exec::block_timestamp());
-            }
-            storage.submitted_decrypters.clear();
-            self.emit_event(Event::AllPartialDecryptionsSubmited)
-                .expect("Event Invocation Error");
+            storage.start_turn_timer();
+            self.emit_event(Event::NextStage(Stage::PostDraw))
+                .expect("Event Error");
         }
     }
 
@@ -814,129 +2262,255 @@ impl PokerService {
         &mut self,
         player_decryptions: Vec<PartialDec>,
         session_for_account: Option<ActorId>,
-    ) {
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
-        let player_id = get_player(&session_for_account);
+        let player_id = get_player(&session_for_account)?;
         let (_, participant) = storage
             .participants
             .iter()
             .find(|(id, _)| *id == player_id)
-            .expect("Participant not found");
-        if !storage.submitted_decrypters.insert(player_id) {
-            panic!("Already submitted decryptions")
+            .ok_or(PokerError::NotRegistered)?;
+        if storage.submitted_decrypters.contains(&player_id) {
+            return Err(PokerError::AlreadySubmitted);
         }
-        let amounts_of_players = storage.participants.len();
         let (base_index, expected_count, next_stage) = match &storage.status {
             Status::Play { stage } => match stage {
                 Stage::WaitingTableCardsAfterPreFlop => (0, 3, Some(Stage::Flop)),
                 Stage::WaitingTableCardsAfterFlop => (3, 1, Some(Stage::Turn)),
                 Stage::WaitingTableCardsAfterTurn => (4, 1, Some(Stage::River)),
-                _ => panic!("Wrong stage"),
+                _ => return Err(PokerError::WrongStage),
             },
+            // the rest of the board, then the second board when running it twice
             Status::WaitingForAllTableCardsToBeDisclosed => {
-                match storage.revealed_table_cards.len() {
-                    0 => (0, 5, None),
-                    3 => (3, 2, None),
-                    4 => (4, 1, None),
-                    _ => panic!("Wrong amount of revealed cards"),
-                }
+                let base_index = storage.revealed_table_cards.len();
+                (base_index, storage.table_cards.len() - base_index, None)
             }
-            _ => panic!("Wrong status"),
+            _ => return Err(PokerError::WrongStatus),
         };
 
-        if player_decryptions.len() != expected_count {
-            panic!("Wrong amount of proofs");
-        }
-
-        let pk = curve::deserialize_public_key(&participant.pk);
-        let g = EdwardsProjective::generator();
-
-        for PartialDec {
-            c0,
-            delta_c0,
-            proof,
-        } in player_decryptions
-        {
-            let c0_point = curve::deserialize_bandersnatch_coords(&c0);
-            let delta_c0_neg = curve::deserialize_bandersnatch_coords(&delta_c0);
-            let delta_c0 = -delta_c0_neg;
-            let proof = proof.into_proof();
-            assert!(
-                verify::verify_chaum_pedersen(g, pk, c0_point, delta_c0, &proof),
-                "Decryption verification failed"
-            );
-            let idx =
-                find_table_idx_in_window(&storage.table_cards, base_index, expected_count, &c0)
-                    .expect("Target table card not found for given c0");
-            let current_c1_point =
-                curve::deserialize_bandersnatch_coords(&storage.table_cards[idx].c1);
-            let new_c1_point = current_c1_point + delta_c0_neg;
-            storage.table_cards[idx].c1 = curve::serialize_bandersnatch_coords(&new_c1_point);
-        }
-
-        let all_submitted = storage.submitted_decrypters.len() == amounts_of_players;
-        if all_submitted {
-            let mut revealed_cards = Vec::with_capacity(expected_count);
-            for i in base_index..base_index + expected_count {
-                let c1_point = curve::deserialize_bandersnatch_coords(&storage.table_cards[i].c1);
+        let (table_cards, revealed_cards) = storage.decrypt_table_cards(
+            &participant.pk,
+            player_decryptions,
+            base_index,
+            expected_count,
+        )?;
 
-                if let Some(card) = curve::find_card_by_point(&storage.original_card_map, &c1_point)
-                {
-                    revealed_cards.push(card);
-                } else {
-                    panic!("Failed to decrypt card");
-                }
-            }
+        storage.table_cards = table_cards;
+        storage.submitted_decrypters.insert(player_id);
 
+        if let Some(revealed_cards) = revealed_cards {
             storage.revealed_table_cards.extend(revealed_cards);
 
             if let Some(next_stage) = next_stage {
                 storage.status = Status::Play { stage: next_stage };
+                storage.start_turn_timer();
+            } else if storage.table_cards.len() == TABLE_CARDS && storage.runs_it_twice() {
+                // the second board replaces the cards that have just been revealed
+                self.deal_table_cards(expected_count)?;
+                storage.start_phase_timer();
             } else {
-                storage.status = Status::WaitingForCardsToBeDisclosed;
+                storage.start_showdown();
             };
 
-            if let Some(betting) = &mut storage.betting {
-                betting.last_active_time = Some(exec::block_timestamp());
-            }
             storage.submitted_decrypters.clear();
         }
 
         self.emit_event(Event::TablePartialDecryptionsSubmited)
             .expect("Event Invocation Error");
+        Ok(())
+    }
+
+    /// Reveals the rest of the board after a hand that ended before the river.
+    ///
+    /// Every player submits the decryption shares of the undealt table cards, as in
+    /// `submit_table_partial_decryptions`. The cards are added to the revealed table cards
+    /// and don't change the payouts. In a sit-and-go the next hand is dealt right away,
+    /// so there's no rabbit hunt there.
+    ///
+    /// Fails if:
+    /// - status is not `Finished`, or the whole board is revealed;
+    /// - the player is not registered or has already submitted;
+    /// - a decryption share doesn't verify.
+    ///
+    /// Emits `TablePartialDecryptionsSubmited`, and `RabbitHunted` once the cards are revealed.
+    pub async fn rabbit_hunt(
+        &mut self,
+        player_decryptions: Vec<PartialDec>,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+        let base_index = storage.revealed_table_cards.len();
+        if !matches!(storage.status, Status::Finished { .. })
+            || storage.table_cards.len() < TABLE_CARDS
+            || base_index >= TABLE_CARDS
+        {
+            return Err(PokerError::WrongStatus);
+        }
+        let (_, participant) = storage
+            .participants
+            .iter()
+            .find(|(id, _)| *id == player_id)
+            .ok_or(PokerError::NotRegistered)?;
+        if storage.submitted_decrypters.contains(&player_id) {
+            return Err(PokerError::AlreadySubmitted);
+        }
+        let (table_cards, revealed_cards) = storage.decrypt_table_cards(
+            &participant.pk,
+            player_decryptions,
+            base_index,
+            TABLE_CARDS - base_index,
+        )?;
+
+        storage.table_cards = table_cards;
+        storage.submitted_decrypters.insert(player_id);
+
+        self.emit_event(Event::TablePartialDecryptionsSubmited)
+            .expect("Event Invocation Error");
+
+        if let Some(cards) = revealed_cards {
+            storage.revealed_table_cards.extend(cards.clone());
+            storage.submitted_decrypters.clear();
+            self.emit_event(Event::RabbitHunted { cards })
+                .expect("Event Invocation Error");
+        }
+        Ok(())
+    }
+
+    /// Agrees to run the rest of the board twice when everyone is all-in.
+    ///
+    /// Once every player left in the hand has agreed, a second board is dealt from the rest
+    /// of the deck after the first one is revealed, and each board wins half of every pot.
+    ///
+    /// Fails if:
+    /// - status is not `WaitingForAllTableCardsToBeDisclosed`, or the first board is revealed;
+    /// - the player has folded;
+    /// - the player has already agreed;
+    /// - there are not enough cards left in the deck.
+    ///
+    /// Emits `RunItTwiceAgreed`.
+    pub fn run_it_twice(&mut self, session_for_account: Option<ActorId>) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+        if storage.status != Status::WaitingForAllTableCardsToBeDisclosed
+            || storage.table_cards.len() > TABLE_CARDS
+        {
+            return Err(PokerError::WrongStatus);
+        }
+        if !storage.active_participants.all().contains(&player_id)
+            && !storage.all_in_players.contains(&player_id)
+        {
+            return Err(PokerError::NoSuchPlayer);
+        }
+        if storage.run_it_twice.contains(&player_id) {
+            return Err(PokerError::AlreadySubmitted);
+        }
+        let deck = storage.encrypted_deck.as_ref().expect("No shuffled deck");
+        let second_run = TABLE_CARDS - storage.revealed_table_cards.len();
+        if storage.deck_position + second_run > deck.len() {
+            return Err(PokerError::NotEnoughCards);
+        }
+
+        storage.run_it_twice.push(player_id);
+        self.emit_event(Event::RunItTwiceAgreed { player_id })
+            .expect("Event Error");
+        Ok(())
     }
 
     /// Processes player actions during betting rounds.
     ///
-    /// Panics if:
+    /// Fails if:
     /// - Wrong game status
     /// - Not player's turn
     /// - Invalid action (e.g. check when bet exists)
+    /// - Raise smaller than the last bet or raise (the big blind when opening)
+    /// - Raise by a player who already acted before a short all-in
+    /// - Raise above the pot (pot limit), of a wrong size or past the raise cap (fixed limit)
     ///
     /// Handles:
     /// - Fold/Call/Check/Raise/AllIn actions
-    /// - Turn timers and skips
     /// - Game end conditions (single player left)
     /// - Stage transitions
     ///
     /// Emits TurnIsMade and NextStage events
-    pub fn turn(&mut self, action: Action, session_for_account: Option<ActorId>) {
-        let player = get_player(&session_for_account);
-        let storage = self.get_mut();
+    pub fn turn(
+        &mut self,
+        action: Action,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let player = get_player(&session_for_account)?;
+        let storage = self.get();
 
-        let Status::Play { stage } = &mut storage.status else {
-            panic!("Wrong status");
-        };
+        if !storage.is_betting() {
+            return Err(PokerError::WrongStatus);
+        }
 
-        if matches!(
-            stage,
-            Stage::WaitingTableCardsAfterPreFlop
-                | Stage::WaitingTableCardsAfterFlop
-                | Stage::WaitingTableCardsAfterTurn
-        ) {
-            panic!("Wrong stage");
+        let betting = storage.betting.as_ref().expect("No betting");
+        if betting.turn != player {
+            return Err(PokerError::NotYourTurn);
         }
 
+        self.make_turn(player, action)
+    }
+
+    /// Plays the turn of a player who didn't act in time: checks if possible, folds otherwise.
+    ///
+    /// The lobby sends it to itself as a delayed message when each turn starts.
+    /// Timers of the turns that are already over carry an old nonce and are ignored.
+    /// Anyone else can call it once `time_per_move_ms` of the turn has passed,
+    /// `turn_nonce` is ignored then.
+    ///
+    /// Fails if:
+    /// - wrong status (no betting round is on)
+    /// - the time for the turn isn't over yet
+    ///
+    /// Emits TurnTimedOut, then the same events as `turn`
+    pub fn turn_timeout(&mut self, turn_nonce: u64) -> Result<(), PokerError> {
+        let storage = self.get();
+        let from_timer = msg::source() == exec::program_id();
+        if from_timer && (turn_nonce != storage.turn_nonce || !storage.is_betting()) {
+            return Ok(());
+        }
+        if !storage.is_betting() {
+            return Err(PokerError::WrongStatus);
+        }
+
+        let betting = storage.betting.as_ref().expect("No betting");
+        if !from_timer {
+            let deadline = betting
+                .last_active_time
+                .map(|time| time + storage.config.time_per_move_ms);
+            if deadline.is_none_or(|deadline| exec::block_timestamp() < deadline) {
+                return Err(PokerError::DeadlineNotReached);
+            }
+        }
+        let player_id = betting.turn;
+        let already_invested = *storage
+            .already_invested_in_the_circle
+            .get(&player_id)
+            .unwrap_or(&0);
+        let action = if already_invested == betting.current_bet {
+            Action::Check
+        } else {
+            Action::Fold
+        };
+
+        self.emit_event(Event::TurnTimedOut {
+            player_id,
+            action: action.clone(),
+        })
+        .expect("Event Error");
+
+        self.make_turn(player_id, action)
+    }
+
+    fn make_turn(&mut self, player: ActorId, action: Action) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let (_, big_blind) = storage.blinds();
+
+        let Status::Play { stage } = &mut storage.status else {
+            return Err(PokerError::WrongStatus);
+        };
         let betting = storage.betting.as_mut().expect("No betting");
 
         let (_, participant) = storage
@@ -945,36 +2519,8 @@ impl PokerService {
             .find(|(id, _)| *id == player)
             .expect("There is no such participant");
 
-        let last_active_time = betting.last_active_time.expect("No last active time");
         let current_time = exec::block_timestamp();
-        let number_of_passes = (current_time - last_active_time) / storage.config.time_per_move_ms;
 
-        if number_of_passes != 0 {
-            if let Some(next_or_last) = storage
-                .active_participants
-                .skip_and_remove(number_of_passes)
-            {
-                if storage.active_participants.len() <= 1 {
-                    let prize = storage.betting_bank.values().sum();
-                    participant.balance += prize;
-                    storage.status = Status::Finished {
-                        pots: vec![(prize, vec![next_or_last])],
-                    };
-                    storage.betting = None;
-                    self.emit_event(Event::Finished {
-                        pots: vec![(prize, vec![next_or_last])],
-                    })
-                    .expect("Event Error");
-                    return;
-                } else if next_or_last != player {
-                    panic!("Not your turn!");
-                }
-            } else {
-                panic!("No active players");
-            }
-        } else if betting.turn != player {
-            panic!("Not your turn!");
-        }
         // Process the player's action
         match action {
             Action::Fold => {
@@ -987,7 +2533,7 @@ impl PokerService {
                     .unwrap_or(&0);
                 let call_value = betting.current_bet - already_invested;
                 if call_value == 0 || participant.balance <= call_value {
-                    panic!("Wrong action");
+                    return Err(PokerError::WrongAction);
                 }
                 participant.balance -= call_value;
                 betting.acted_players.push(player);
@@ -1009,7 +2555,7 @@ impl PokerService {
                     .unwrap_or(&0);
 
                 if betting.current_bet != already_invested {
-                    panic!("cannot check");
+                    return Err(PokerError::CannotCheck);
                 }
 
                 betting.acted_players.push(player);
@@ -1021,16 +2567,32 @@ impl PokerService {
                     .unwrap_or(&0);
 
                 if participant.balance <= bet {
-                    panic!("Wrong action");
+                    return Err(PokerError::WrongAction);
                 }
-                if already_invested + bet <= betting.current_bet {
-                    panic!("Raise must be higher");
+                if betting.raise_closed.contains(&player) {
+                    return Err(PokerError::RaiseNotAllowed);
                 }
-                betting.current_bet = already_invested + bet;
+                let pot = storage.betting_bank.values().sum();
+                let (min_bet, max_bet) =
+                    raise_bounds(&storage.config, stage, betting, pot, already_invested)?;
+                let new_bet = already_invested + bet;
+                if new_bet < min_bet {
+                    return Err(PokerError::RaiseTooLow);
+                }
+                if max_bet.is_some_and(|max_bet| new_bet > max_bet) {
+                    return Err(PokerError::RaiseTooHigh);
+                }
+                if betting.current_bet > 0 {
+                    betting.raises += 1;
+                }
+                betting.last_raise = new_bet - betting.current_bet;
+                betting.current_bet = new_bet;
+                betting.last_aggressor = Some(player);
                 participant.balance -= bet;
                 // if someone raises the bet, the betting round starts all over again
                 // so it is necessary to clear the acted_players
                 betting.acted_players.clear();
+                betting.raise_closed.clear();
                 betting.acted_players.push(player);
                 storage
                     .already_invested_in_the_circle
@@ -1050,8 +2612,29 @@ impl PokerService {
                     .unwrap_or(&0);
                 let bet = already_invested + participant.balance;
                 if bet > betting.current_bet {
+                    if betting.raise_closed.contains(&player) {
+                        return Err(PokerError::RaiseNotAllowed);
+                    }
+                    let pot = storage.betting_bank.values().sum();
+                    let (min_bet, max_bet) =
+                        raise_bounds(&storage.config, stage, betting, pot, already_invested)?;
+                    if max_bet.is_some_and(|max_bet| bet > max_bet) {
+                        return Err(PokerError::RaiseTooHigh);
+                    }
+                    if bet >= min_bet {
+                        if betting.current_bet > 0 {
+                            betting.raises += 1;
+                        }
+                        betting.last_raise = bet - betting.current_bet;
+                        betting.raise_closed.clear();
+                    } else {
+                        // a short all-in doesn't reopen the betting: the players who have
+                        // already acted have to match it, but can't raise again
+                        betting.raise_closed.append(&mut betting.acted_players);
+                    }
                     betting.current_bet = bet;
                     betting.acted_players.clear();
+                    betting.last_aggressor = Some(player);
                 }
 
                 storage.all_in_players.push(player);
@@ -1093,18 +2676,18 @@ impl PokerService {
 
             participant.balance += prize;
             storage.status = Status::Finished {
-                pots: vec![(prize, vec![*winner])],
+                pots: vec![(prize, vec![*winner], PotShare::Whole)],
             };
             self.emit_event(Event::Finished {
-                pots: vec![(prize, vec![*winner])],
+                pots: vec![(prize, vec![*winner], PotShare::Whole)],
             })
             .expect("Event Error");
         }
         // Check if the round is complete at the River stage
         else if betting.acted_players.len() == storage.active_participants.len()
-            && *stage == Stage::River
+            && matches!(stage, Stage::River | Stage::PostDraw)
         {
-            storage.status = Status::WaitingForCardsToBeDisclosed;
+            storage.start_showdown();
             self.emit_event(Event::WaitingForCardsToBeDisclosed)
                 .expect("Event Error");
         }
@@ -1112,12 +2695,23 @@ impl PokerService {
         else if betting.acted_players.len() == storage.active_participants.len() {
             // if there's only one active player left, there's no point in betting any more
             // and if there's nobody active player left(everybody call AllIn), there's no point in betting any more
-            if storage.active_participants.len() <= 1 {
+            if storage.active_participants.len() <= 1 && *stage == Stage::PreDraw {
+                // the players who are all-in still draw
+                storage.start_draw();
+                self.emit_event(Event::NextStage(Stage::Draw))
+                    .expect("Event Error");
+            } else if storage.active_participants.len() <= 1 {
                 storage.status = Status::WaitingForAllTableCardsToBeDisclosed;
+                storage.start_phase_timer();
                 self.emit_event(Event::WaitingForAllTableCardsToBeDisclosed)
                     .expect("Event Error");
             } else {
-                storage.active_participants.reset_turn_index();
+                // postflop the first player after the button acts first, heads-up it's the big blind
+                let button = storage.positions.as_ref().expect("No button").button;
+                let (_, first_player) =
+                    next_in_rotation(&storage.participants, &storage.active_participants, button)
+                        .expect("There is no next one");
+                storage.active_participants.set_turn(&first_player);
                 storage.already_invested_in_the_circle = HashMap::new();
                 betting.turn = storage
                     .active_participants
@@ -1126,9 +2720,20 @@ impl PokerService {
                 betting.last_active_time = None;
                 betting.acted_players.clear();
                 betting.current_bet = 0;
-
-                *stage = stage.clone().next().expect("There is no next one");
-                self.emit_event(Event::NextStage(stage.clone()))
+                betting.last_raise = big_blind;
+                betting.raise_closed.clear();
+                betting.raises = 0;
+                betting.last_aggressor = None;
+
+                let next_stage = stage.clone().next().expect("There is no next one");
+                if next_stage == Stage::Draw {
+                    storage.start_draw();
+                } else {
+                    *stage = next_stage.clone();
+                    // the next stage waits for the table cards to be decrypted
+                    storage.phase_deadline = Some(current_time + storage.config.time_per_move_ms);
+                }
+                self.emit_event(Event::NextStage(next_stage))
                     .expect("Event Error");
             }
         } else {
@@ -1136,18 +2741,23 @@ impl PokerService {
                 .active_participants
                 .next()
                 .expect("The player must exist");
-            betting.last_active_time = Some(current_time);
+            storage.start_turn_timer();
         }
         self.emit_event(Event::TurnIsMade { action })
             .expect("Event Error");
+
+        if matches!(storage.status, Status::Finished { .. }) {
+            self.continue_tournament();
+        }
+        Ok(())
     }
 
-    fn deal_table_cards(&mut self, count: usize) {
+    fn deal_table_cards(&mut self, count: usize) -> Result<(), PokerError> {
         let storage = self.get_mut();
         let deck = storage.encrypted_deck.as_ref().expect("No shuffled deck");
 
         if storage.deck_position + count > deck.len() {
-            panic!("Not enough cards");
+            return Err(PokerError::NotEnoughCards);
         }
 
         let mut new_cards = Vec::new();
@@ -1160,114 +2770,178 @@ impl PokerService {
 
         self.emit_event(Event::CardsDealtToTable(new_cards))
             .expect("Event Error");
+        Ok(())
     }
 
+    /// Shows the player's hole cards at the showdown, when it's their turn in the showdown order.
     pub async fn card_disclosure(
         &mut self,
         player_decryptions: Vec<PartialDec>,
         session_for_account: Option<ActorId>,
-    ) {
+    ) -> Result<(), PokerError> {
         let storage = self.get_mut();
 
-        let player_id = get_player(&session_for_account);
-        assert_eq!(player_decryptions.len(), 2, "Not enough decryptions");
-        let (_, participant) = storage
-            .participants
-            .iter()
-            .find(|(id, _)| *id == player_id)
-            .expect("Participant not found");
-        let cards_entry = storage
-            .partially_decrypted_cards
-            .get(&player_id)
-            .expect("Cards not found");
-        let pk = curve::deserialize_public_key(&participant.pk);
-        let g = EdwardsProjective::generator();
+        let player_id = get_player(&session_for_account)?;
+        if storage.status != Status::WaitingForCardsToBeDisclosed {
+            return Err(PokerError::WrongStatus);
+        }
+        if storage.showdown_order.first() != Some(&player_id) {
+            return Err(PokerError::NotYourTurn);
+        }
+        let cards = storage.decrypt_hole_cards(player_id, player_decryptions)?;
 
-        let mut cards = Vec::new();
-        for PartialDec {
-            c0,
-            delta_c0,
-            proof,
-        } in player_decryptions
-        {
-            let c0_point = curve::deserialize_bandersnatch_coords(&c0);
-            let delta_c0_neg = curve::deserialize_bandersnatch_coords(&delta_c0);
-            let delta_c0 = -delta_c0_neg;
-            let proof = proof.into_proof();
-            assert!(
-                verify::verify_chaum_pedersen(g, pk, c0_point, delta_c0, &proof),
-                "Decryption verification failed"
-            );
+        storage.revealed_players.insert(player_id, cards);
+        storage.showdown_order.remove(0);
+        storage.start_phase_timer();
 
-            let idx = if curve::compare_points(&cards_entry[0].c0, &c0) {
-                0
-            } else if curve::compare_points(&cards_entry[1].c0, &c0) {
-                1
-            } else {
-                panic!("Target hole card not found for given c0");
-            };
-            let current_c1_point = curve::deserialize_bandersnatch_coords(&cards_entry[idx].c1);
-            let new_c1_point = current_c1_point + delta_c0_neg;
-            if let Some(card) = curve::find_card_by_point(&storage.original_card_map, &new_c1_point)
-            {
-                cards.push(card);
-            } else {
-                panic!("Failed to decrypt card");
-            }
+        self.emit_event(Event::CardsDisclosed).expect("Event Error");
+
+        self.finish_showdown_if_done();
+        Ok(())
+    }
+
+    /// Gives up the player's claim on all pots without showing the cards.
+    ///
+    /// Fails if:
+    /// - status is not `WaitingForCardsToBeDisclosed`;
+    /// - it's not the player's turn to show or muck.
+    ///
+    /// Emits `CardsMucked`, and `Finished` if the hand is over.
+    pub fn muck(&mut self, session_for_account: Option<ActorId>) -> Result<(), PokerError> {
+        let storage = self.get_mut();
+        let player_id = get_player(&session_for_account)?;
+        if storage.status != Status::WaitingForCardsToBeDisclosed {
+            return Err(PokerError::WrongStatus);
+        }
+        if storage.showdown_order.first() != Some(&player_id) {
+            return Err(PokerError::NotYourTurn);
         }
 
-        storage
-            .revealed_players
-            .insert(player_id, (cards[0].clone(), cards[1].clone()));
+        self.muck_cards(player_id);
+        Ok(())
+    }
 
-        let expected_players: HashSet<ActorId> = storage
-            .active_participants
-            .all()
-            .iter()
-            .chain(storage.all_in_players.iter())
-            .cloned()
-            .collect();
-        let players: HashSet<ActorId> = storage.revealed_players.keys().cloned().collect();
+    /// Shows the player's hole cards after the hand, e.g. when everyone else has folded.
+    ///
+    /// Doesn't change the payouts. In a sit-and-go the next hand is dealt right away,
+    /// so there's nothing to show there.
+    ///
+    /// Fails if:
+    /// - status is not `Finished`;
+    /// - the player wasn't dealt cards in the hand;
+    /// - a decryption share doesn't verify.
+    ///
+    /// Emits `CardsShown`.
+    pub async fn show_cards(
+        &mut self,
+        player_decryptions: Vec<PartialDec>,
+        session_for_account: Option<ActorId>,
+    ) -> Result<(), PokerError> {
+        let storage = self.get();
+        let player_id = get_player(&session_for_account)?;
+        if !matches!(storage.status, Status::Finished { .. }) {
+            return Err(PokerError::WrongStatus);
+        }
+        let cards = storage.decrypt_hole_cards(player_id, player_decryptions)?;
 
-        if players.is_superset(&expected_players) {
-            let table_cards: [Card; 5] = match storage.revealed_table_cards.clone().try_into() {
-                Ok(array) => array,
-                Err(_) => unreachable!(),
-            };
+        self.emit_event(Event::CardsShown { player_id, cards })
+            .expect("Event Error");
+        Ok(())
+    }
 
-            let pots = evaluate_round(
-                storage.revealed_players.clone(),
-                table_cards,
-                &storage.betting_bank,
-            );
+    fn muck_cards(&mut self, player_id: ActorId) {
+        let storage = self.get_mut();
+        storage.showdown_order.retain(|id| *id != player_id);
+        storage.mucked.push(player_id);
+        storage.start_phase_timer();
 
-            let mut prizes_by_player: HashMap<ActorId, u128> = HashMap::new();
-            for (amount, winners) in &pots {
-                let share = *amount / winners.len() as u128;
-                for winner in winners {
-                    *prizes_by_player.entry(*winner).or_insert(0) += share;
-                }
-            }
+        self.emit_event(Event::CardsMucked { player_id })
+            .expect("Event Error");
 
-            for (winner, prize) in &prizes_by_player {
-                let (_, participant) = storage
-                    .participants
-                    .iter_mut()
-                    .find(|(id, _)| id == winner)
-                    .expect("There is no such participant");
-                participant.balance += *prize;
+        self.finish_showdown_if_done();
+    }
+
+    /// Awards the pots once every player has shown or mucked, or when the others
+    /// have mucked and the last one wins without showing.
+    ///
+    /// A pot is only won by the players eligible for it: one that none of them showed
+    /// down for goes to the last of them to give up their cards.
+    fn finish_showdown_if_done(&mut self) {
+        let storage = self.get_mut();
+        let uncontested = storage.revealed_players.is_empty() && storage.showdown_order.len() == 1;
+        if !storage.showdown_order.is_empty() && !uncontested {
+            return;
+        }
+
+        let (pots, payouts) = if uncontested {
+            // the last player doesn't show, the pots they're in go to them before those who mucked
+            let mut unrevealed = storage.mucked.clone();
+            unrevealed.push(storage.showdown_order[0]);
+            evaluate_round(
+                &storage.config.game_variant,
+                storage.config.hi_lo,
+                HashMap::new(),
+                &unrevealed,
+                &[],
+                &storage.betting_bank,
+                &storage.clockwise_from_button(),
+            )
+        } else if storage.config.game_variant.table_cards() == 0 {
+            // a draw hand has no board, mucked hands only take the pots nobody showed down for
+            evaluate_round(
+                &storage.config.game_variant,
+                storage.config.hi_lo,
+                storage.revealed_players.clone(),
+                &storage.mucked,
+                &[],
+                &storage.betting_bank,
+                &storage.clockwise_from_button(),
+            )
+        } else {
+            let order = storage.clockwise_from_button();
+            // mucked hands only take the pots nobody showed down for
+            match storage.boards() {
+                (board, None) => evaluate_round(
+                    &storage.config.game_variant,
+                    storage.config.hi_lo,
+                    storage.revealed_players.clone(),
+                    &storage.mucked,
+                    &board,
+                    &storage.betting_bank,
+                    &order,
+                ),
+                (first, Some(second)) => evaluate_two_boards(
+                    &storage.config.game_variant,
+                    storage.config.hi_lo,
+                    storage.revealed_players.clone(),
+                    &storage.mucked,
+                    [first, second],
+                    &storage.betting_bank,
+                    &order,
+                ),
             }
+        };
 
-            storage.status = Status::Finished { pots: pots.clone() };
-            self.emit_event(Event::Finished { pots })
-                .expect("Event Error");
+        for (winner, prize) in &payouts {
+            let (_, participant) = storage
+                .participants
+                .iter_mut()
+                .find(|(id, _)| id == winner)
+                .expect("There is no such participant");
+            participant.balance += *prize;
         }
 
-        self.emit_event(Event::CardsDisclosed).expect("Event Error");
+        storage.showdown_order.clear();
+        storage.phase_deadline = None;
+        storage.status = Status::Finished { pots: pots.clone() };
+        self.emit_event(Event::Finished { pots })
+            .expect("Event Error");
+
+        self.continue_tournament();
     }
 
     // Query
-    pub fn player_cards(&self, player_id: ActorId) -> Option<[EncryptedCard; 2]> {
+    pub fn player_cards(&self, player_id: ActorId) -> Option<Vec<EncryptedCard>> {
         self.get()
             .partially_decrypted_cards
             .get(&player_id)
@@ -1288,12 +2962,8 @@ impl PokerService {
                 _ => return vec![],
             },
             Status::WaitingForAllTableCardsToBeDisclosed => {
-                match storage.revealed_table_cards.len() {
-                    0 => (0, 5),
-                    3 => (3, 2),
-                    4 => (4, 1),
-                    _ => return vec![],
-                }
+                let base_index = storage.revealed_table_cards.len();
+                (base_index, storage.table_cards.len() - base_index)
             }
             _ => return vec![],
         };
@@ -1324,6 +2994,60 @@ impl PokerService {
     pub fn round(&self) -> u64 {
         self.get().round
     }
+    /// Nonce (LE bytes) the player's shuffle proof of the current hand must carry as a public input
+    pub fn shuffle_nonce(&self, player_id: ActorId) -> Vec<u8> {
+        verify::shuffle_nonce(exec::program_id(), self.get().round, player_id)
+            .into_bigint()
+            .to_bytes_le()
+    }
+    /// Version of the partial decryption proof transcript expected by this lobby
+    pub fn decryption_proof_version(&self) -> u32 {
+        verify::DECRYPTION_PROOF_VERSION
+    }
+    /// Timestamp after which `claim_timeout` can end the current phase, if it waits for players
+    pub fn phase_deadline(&self) -> Option<u64> {
+        self.get().phase_deadline
+    }
+    /// Progress of the sit-and-go, `None` in a cash game or before the first hand
+    pub fn tournament(&self) -> &'static Option<TournamentState> {
+        &self.get().tournament
+    }
+    /// Final places with prizes, from the first one down; empty until the sit-and-go is over
+    pub fn standings(&self) -> Vec<(ActorId, u128)> {
+        self.get()
+            .tournament
+            .as_ref()
+            .map(|tournament| tournament.standings.clone())
+            .unwrap_or_default()
+    }
+    /// Seats of the button and the blinds in the current hand, `None` before the first one
+    pub fn positions(&self) -> &'static Option<Positions> {
+        &self.get().positions
+    }
+    /// Players who still have to show or muck their cards at the showdown, the first one is next
+    pub fn showdown_order(&self) -> &'static Vec<ActorId> {
+        &self.get().showdown_order
+    }
+    /// Players of the all-in hand who have agreed to run it twice
+    pub fn run_it_twice_agreed(&self) -> &'static Vec<ActorId> {
+        &self.get().run_it_twice
+    }
+    /// Players who still have to draw in a draw game, the first one is next
+    pub fn drawing(&self) -> &'static Vec<ActorId> {
+        &self.get().drawing
+    }
+    /// Taken seats with their players, including the ones waiting for the next hand
+    pub fn seats(&self) -> Vec<(u8, ActorId)> {
+        let storage = self.get();
+        let mut seats: Vec<(u8, ActorId)> = storage
+            .participants
+            .iter()
+            .chain(storage.waiting_participants.iter())
+            .map(|(id, participant)| (participant.seat, *id))
+            .collect();
+        seats.sort();
+        seats
+    }
     pub fn betting(&self) -> &'static Option<BettingStage> {
         &self.get().betting
     }
@@ -1347,7 +3071,7 @@ impl PokerService {
         self.get().pts_actor_id
     }
 
-    pub fn revealed_players(&self) -> Vec<(ActorId, (Card, Card))> {
+    pub fn revealed_players(&self) -> Vec<(ActorId, Vec<Card>)> {
         self.get().revealed_players.clone().into_iter().collect()
     }
 
@@ -1357,11 +3081,11 @@ impl PokerService {
 }
 
 fn locate_owner_and_index(
-    encrypted_cards: &HashMap<ActorId, [EncryptedCard; 2]>,
+    encrypted_cards: &HashMap<ActorId, Vec<EncryptedCard>>,
     c0_bytes: &[Vec<u8>; 3],
 ) -> Option<(ActorId, usize)> {
     for (actor, cards) in encrypted_cards.iter() {
-        for (i, card) in cards.iter().enumerate().take(2) {
+        for (i, card) in cards.iter().enumerate() {
             if curve::compare_points(&card.c0, c0_bytes) {
                 return Some((*actor, i));
             }
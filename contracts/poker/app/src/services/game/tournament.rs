@@ -0,0 +1,147 @@
+use sails_rs::prelude::*;
+
+/// Kind of game played in a lobby
+#[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum GameMode {
+    /// Blinds from the config, every hand is started by the admin
+    Cash,
+    /// Players buy in with `starting_bank` and play hand after hand until one of them has all the chips
+    SitAndGo(TournamentConfig),
+}
+
+#[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct TournamentConfig {
+    /// Blinds by level, the last level lasts until the end of the tournament
+    pub blind_levels: Vec<BlindLevel>,
+    pub level_duration: LevelDuration,
+    /// Shares of the prize pool in percent, from the first place down
+    pub payouts: Vec<u8>,
+}
+
+impl TournamentConfig {
+    /// Every blind level must be set and last a while, and the payout table
+    /// must pay at least one and at most `max_players` places and at most 100 percent
+    /// in total. What's left of the prize pool goes to the winner.
+    pub fn is_valid(&self, max_players: u8) -> bool {
+        let levels_valid = !self.blind_levels.is_empty()
+            && self
+                .blind_levels
+                .iter()
+                .all(|level| level.small_blind > 0 && level.big_blind >= level.small_blind);
+        let duration_valid = !matches!(
+            self.level_duration,
+            LevelDuration::Hands(0) | LevelDuration::Millis(0)
+        );
+        let payouts_valid = !self.payouts.is_empty()
+            && self.payouts.len() <= max_players as usize
+            && self.payouts.iter().map(|share| *share as u32).sum::<u32>() <= 100;
+
+        levels_valid && duration_valid && payouts_valid
+    }
+}
+
+#[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct BlindLevel {
+    pub small_blind: u128,
+    pub big_blind: u128,
+}
+
+#[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum LevelDuration {
+    Hands(u32),
+    Millis(u64),
+}
+
+/// Progress of a sit-and-go, created when the first hand starts
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct TournamentState {
+    pub entrants: u32,
+    pub prize_pool: u128,
+    pub level: u32,
+    pub hands_at_level: u32,
+    pub level_started_at: u64,
+    // knocked out players, the first one finished last
+    pub eliminated: Vec<ActorId>,
+    // players with their prizes from the first place down, filled in when the tournament is over
+    pub standings: Vec<(ActorId, u128)>,
+    pub prizes_paid: bool,
+}
+
+impl TournamentState {
+    pub fn new(entrants: u32, buy_in: u128, now: u64) -> Self {
+        Self {
+            entrants,
+            prize_pool: buy_in * entrants as u128,
+            level: 0,
+            hands_at_level: 0,
+            level_started_at: now,
+            eliminated: Vec::new(),
+            standings: Vec::new(),
+            prizes_paid: false,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        !self.standings.is_empty()
+    }
+
+    pub fn blinds<'a>(&self, config: &'a TournamentConfig) -> &'a BlindLevel {
+        &config.blind_levels[self.level as usize]
+    }
+
+    /// Counts a new hand, moving to the next blind level first if the current one is over.
+    ///
+    /// Returns whether the level has changed.
+    pub fn start_hand(&mut self, config: &TournamentConfig, now: u64) -> bool {
+        let level_over = match config.level_duration {
+            LevelDuration::Hands(hands) => self.hands_at_level >= hands,
+            LevelDuration::Millis(ms) => now >= self.level_started_at + ms,
+        };
+        let next_level = level_over && (self.level as usize) + 1 < config.blind_levels.len();
+        if next_level {
+            self.level += 1;
+            self.hands_at_level = 0;
+            self.level_started_at = now;
+        }
+        self.hands_at_level += 1;
+        next_level
+    }
+
+    /// Knocks a player out and returns the place they finished in.
+    pub fn eliminate(&mut self, player_id: ActorId) -> u32 {
+        let place = self.entrants - self.eliminated.len() as u32;
+        self.eliminated.push(player_id);
+        place
+    }
+
+    /// Ranks the winner first and the rest in reverse order of elimination,
+    /// and splits the prize pool by the payout table.
+    ///
+    /// The chips lost to rounding go to the winner.
+    pub fn finish(&mut self, winner: ActorId, payouts: &[u8]) {
+        let mut prizes: Vec<u128> = payouts
+            .iter()
+            .map(|share| self.prize_pool * *share as u128 / 100)
+            .collect();
+        let rest = self.prize_pool - prizes.iter().sum::<u128>();
+        if let Some(first) = prizes.first_mut() {
+            *first += rest;
+        }
+
+        self.standings = core::iter::once(winner)
+            .chain(self.eliminated.iter().rev().copied())
+            .enumerate()
+            .map(|(i, player_id)| (player_id, prizes.get(i).copied().unwrap_or(0)))
+            .collect();
+    }
+}
@@ -1,4 +1,6 @@
+use super::GameVariant;
 use core::fmt::Debug;
+use hand_evaluator::{HandStrength, Rules};
 use sails_rs::collections::HashMap;
 use sails_rs::prelude::*;
 use sails_rs::{ActorId, Vec};
@@ -9,7 +11,6 @@ use sails_rs::{ActorId, Vec};
 pub struct TurnManager<Id> {
     active_ids: Vec<Id>,
     turn_index: u64,
-    first_index: u16,
 }
 
 #[allow(clippy::new_without_default)]
@@ -18,14 +19,9 @@ impl<Id: Eq + Clone + Debug> TurnManager<Id> {
         Self {
             active_ids: Vec::new(),
             turn_index: 0,
-            first_index: 0,
         }
     }
 
-    pub fn new_round(&mut self) {
-        self.first_index = (self.first_index + 1) % self.active_ids.len() as u16;
-    }
-
     pub fn add(&mut self, id: Id) {
         self.active_ids.push(id.clone());
     }
@@ -94,8 +90,11 @@ impl<Id: Eq + Clone + Debug> TurnManager<Id> {
         Some(result_id)
     }
 
-    pub fn reset_turn_index(&mut self) {
-        self.turn_index = 0;
+    /// Makes `id` the one returned by the next call of `next`.
+    pub fn set_turn(&mut self, id: &Id) {
+        if let Some(pos) = self.active_ids.iter().position(|x| x == id) {
+            self.turn_index = pos as u64;
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -139,22 +138,6 @@ impl<Id: Eq + Clone + Debug> TurnManager<Id> {
         self.active_ids.get(prev_index)
     }
 
-    pub fn set_first_index(&mut self) {
-        self.turn_index = self.first_index as u64;
-    }
-
-    pub fn remove_and_update_first_index(&mut self, id: &Id) {
-        if let Some(pos) = self.active_ids.iter().position(|x| x == id) {
-            self.active_ids.remove(pos);
-
-            if self.first_index as usize > pos {
-                self.first_index -= 1;
-            } else if self.first_index as usize >= self.active_ids.len() {
-                self.first_index = 0;
-            }
-        }
-    }
-
     pub fn clear_all(&mut self) {
         self.active_ids.clear();
         self.turn_index = 0;
@@ -168,8 +151,17 @@ pub struct BettingStage {
     pub turn: ActorId,
     pub last_active_time: Option<u64>,
     pub current_bet: u128,
-    pub acted_players: Vec<ActorId>, // players who have placed a bet (Check or Call)
-                                     // it's to keep track of when the lap ends
+    // players who have placed a bet (Check or Call)
+    // it's to keep track of when the lap ends
+    pub acted_players: Vec<ActorId>,
+    // size of the last full bet or raise, the minimum for the next raise
+    pub last_raise: u128,
+    // players who acted before a short all-in, they may only call or fold
+    pub raise_closed: Vec<ActorId>,
+    // raises made in the round after the opening bet (or the big blind)
+    pub raises: u32,
+    // the last player who bet or raised in the round, they show first at the showdown
+    pub last_aggressor: Option<ActorId>,
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
@@ -183,6 +175,12 @@ pub enum Stage {
     Turn,
     WaitingTableCardsAfterTurn,
     River,
+    // five-card draw: the betting before the draw, the draw itself,
+    // decryption of the replacement cards and the betting after the draw
+    PreDraw,
+    Draw,
+    WaitingDrawnCards,
+    PostDraw,
 }
 
 impl Stage {
@@ -195,6 +193,10 @@ impl Stage {
             Stage::Turn => Some(Stage::WaitingTableCardsAfterTurn),
             Stage::WaitingTableCardsAfterTurn => Some(Stage::River),
             Stage::River => None,
+            Stage::PreDraw => Some(Stage::Draw),
+            Stage::Draw => Some(Stage::WaitingDrawnCards),
+            Stage::WaitingDrawnCards => Some(Stage::PostDraw),
+            Stage::PostDraw => None,
         }
     }
 }
@@ -223,107 +225,82 @@ impl Card {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-enum HandRank {
-    HighCard(Vec<u8>),
-    Pair(u8, Vec<u8>),
-    TwoPair(u8, u8, u8),
-    ThreeOfAKind(u8, Vec<u8>),
-    Straight(u8),
-    Flush(Vec<u8>),
-    FullHouse(u8, u8),
-    FourOfAKind(u8, u8),
-    StraightFlush(u8),
-}
-
-fn rank_hand(cards: Vec<Card>) -> HandRank {
-    let mut values = Vec::new();
-    let mut suits: HashMap<Suit, Vec<u8>> = HashMap::new();
-
-    for card in &cards {
-        values.push(card.value);
-        suits.entry(card.suit.clone()).or_default().push(card.value);
-    }
-
-    values.sort_by(|a, b| b.cmp(a));
-    let mut counts = HashMap::with_capacity(7);
-    for &v in &values {
-        *counts.entry(v).or_insert(0) += 1;
-    }
-    // Flush
-    let flush = suits.iter().find(|(_, v)| v.len() >= 5).map(|(_, s)| {
-        let mut s = s.clone();
-        s.sort_by(|a, b| b.cmp(a));
-        s.truncate(5);
-        s
-    });
-    // Straight
-    let mut unique = values.clone();
-    unique.sort();
-    unique.dedup();
-    let mut straight = None;
-    if unique.len() >= 5 {
-        for w in unique.windows(5) {
-            if w[0] + 1 == w[1] && w[1] + 1 == w[2] && w[2] + 1 == w[3] && w[3] + 1 == w[4] {
-                straight = Some(w[4]);
-                break;
-            }
-        }
-        if unique.contains(&14)
-            && unique.contains(&2)
-            && unique.contains(&3)
-            && unique.contains(&4)
-            && unique.contains(&5)
-        {
-            straight = Some(5);
-        }
+impl From<&Card> for hand_evaluator::Card {
+    fn from(card: &Card) -> Self {
+        hand_evaluator::Card::new(card.value, card.suit.clone() as u8)
     }
+}
 
-    // Straight Flush
-    if let Some(s) = flush.clone() {
-        let mut flush_unique = s.clone();
-        flush_unique.sort();
-        flush_unique.dedup();
-
-        for w in flush_unique.windows(5) {
-            if w[0] + 1 == w[1] && w[1] + 1 == w[2] && w[2] + 1 == w[3] && w[3] + 1 == w[4] {
-                return HandRank::StraightFlush(w[4]);
-            }
-        }
+// highest card of a low hand that qualifies for the low half of a Hi-Lo pot
+const LOW_QUALIFIER: u8 = 8;
+
+/// Ranks the best hand a player makes with the board, the best ace-to-five low if `low` is set.
+///
+/// In Hold'em any five of the hole and board cards play, in Omaha exactly
+/// two hole cards and three board cards. A draw hand has no board and plays
+/// its five cards.
+fn rank_holding(
+    variant: &GameVariant,
+    hole_cards: &[Card],
+    board: &[Card],
+    low: bool,
+) -> HandStrength {
+    let hole_cards: Vec<hand_evaluator::Card> = hole_cards.iter().map(Into::into).collect();
+    let board: Vec<hand_evaluator::Card> = board.iter().map(Into::into).collect();
+    let rules = match variant {
+        _ if low => Rules::AceToFiveLow,
+        GameVariant::ShortDeck => Rules::ShortDeck,
+        _ => Rules::High,
+    };
+
+    match variant {
+        GameVariant::Omaha => hand_evaluator::best_omaha(&hole_cards, &board, rules),
+        _ => hand_evaluator::best_of(&[hole_cards, board].concat(), rules),
     }
+    .expect("Not enough cards for a hand")
+}
 
-    let mut count_vec: Vec<_> = counts.iter().collect();
-    count_vec.sort_by(|a, b| b.1.cmp(a.1).then(b.0.cmp(a.0)));
-
-    match count_vec[..] {
-        [(&a, &4), (&b, _)] => HandRank::FourOfAKind(a, b),
-        [(&a, &3), (&b, &2), ..] => HandRank::FullHouse(a, b),
-        [(&a, &3), ..] => {
-            let kickers: Vec<u8> = values.iter().filter(|&&v| v != a).copied().collect();
-            HandRank::ThreeOfAKind(a, kickers[..2].to_vec())
-        }
-        [(&a, &2), (&b, &2), (&c, _), ..] if a != b => HandRank::TwoPair(a.max(b), a.min(b), c),
-        [(&a, &2), ..] => {
-            let kickers: Vec<u8> = values.iter().filter(|&&v| v != a).copied().collect();
-            HandRank::Pair(a, kickers[..3].to_vec())
-        }
-        _ => {
-            if let Some(s) = flush {
-                HandRank::Flush(s)
-            } else if let Some(s) = straight {
-                HandRank::Straight(s)
-            } else {
-                HandRank::HighCard(values)
-            }
-        }
-    }
+/// Part of a pot taken by its winners
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum PotShare {
+    /// The whole pot, also when a Hi-Lo pot has no qualifying low hand
+    Whole,
+    /// The high half of a Hi-Lo pot, with the odd chip
+    High,
+    /// The low half of a Hi-Lo pot
+    Low,
 }
 
+/// Pots with their winners and the part they took, the main pot first
+pub type Pots = Vec<(u128, Vec<ActorId>, PotShare)>;
+
+/// Splits the bank into the main and side pots and awards each of them to the best hands.
+///
+/// With `hi_lo` every pot is split between the best high hand and the best eight-or-better
+/// low hand, the high half taking the odd chip. When no hand qualifies for the low half,
+/// the high hand scoops the pot.
+///
+/// Only the revealed `hands` compete. `unrevealed` lists the players still in the hand who
+/// didn't show, in the order they gave up their cards: a pot none of its players showed
+/// down for goes whole to the last of them eligible for it.
+///
+/// `order` lists the players clockwise from the first seat after the button. Winners of
+/// every pot are listed in this order, and when a pot doesn't split evenly the odd chips
+/// go one by one to the first of them.
+///
+/// Returns the pots with their winners and the payouts of the players in `order`,
+/// which add up to the whole bank.
 pub fn evaluate_round(
-    hands: HashMap<ActorId, (Card, Card)>,
-    table_cards: [Card; 5],
+    variant: &GameVariant,
+    hi_lo: bool,
+    hands: HashMap<ActorId, Vec<Card>>,
+    unrevealed: &[ActorId],
+    table_cards: &[Card],
     bank: &HashMap<ActorId, u128>,
-) -> Vec<(u128, Vec<ActorId>)> {
+    order: &[ActorId],
+) -> (Pots, Vec<(ActorId, u128)>) {
     let mut pots: Vec<(Vec<ActorId>, u128)> = Vec::new();
     let mut stakes: Vec<(ActorId, u128)> = bank.iter().map(|(id, amt)| (*id, *amt)).collect();
     stakes.sort_by_key(|&(_, amt)| amt);
@@ -349,44 +326,183 @@ pub fn evaluate_round(
         stakes.retain(|&(_, amt)| amt > 0);
     }
 
-    let mut rankings: HashMap<ActorId, HandRank> = HashMap::new();
-    for (id, (c1, c2)) in &hands {
-        let mut cards = vec![c1.clone(), c2.clone()];
-        cards.extend_from_slice(&table_cards);
-        rankings.insert(*id, rank_hand(cards));
-    }
-
-    let mut results: Vec<(u128, Vec<ActorId>)> = Vec::new();
+    // a pot nobody showed down for is taken without showing, and chips of folded players
+    // above every live stake go to the pot below
+    let mut contested: Vec<(Vec<ActorId>, u128, bool)> = Vec::new();
     for (eligible, pot_amount) in pots {
-        let mut ranked: Vec<_> = eligible
+        let contenders: Vec<ActorId> = eligible
             .iter()
-            .filter_map(|id| rankings.get(id).map(|r| (id, r)))
+            .filter(|id| hands.contains_key(*id))
+            .copied()
             .collect();
+        let last_unrevealed = unrevealed.iter().rev().find(|id| eligible.contains(id));
+        if !contenders.is_empty() {
+            contested.push((contenders, pot_amount, true));
+        } else if let Some(winner) = last_unrevealed {
+            contested.push((vec![*winner], pot_amount, false));
+        } else if let Some((_, amount, _)) = contested.last_mut() {
+            *amount += pot_amount;
+        }
+    }
 
-        ranked.sort_by(|a, b| b.1.cmp(a.1)); // strongest hand first
+    let mut high_hands: HashMap<ActorId, HandStrength> = HashMap::new();
+    let mut low_hands: HashMap<ActorId, HandStrength> = HashMap::new();
+    for (id, hole_cards) in &hands {
+        high_hands.insert(*id, rank_holding(variant, hole_cards, table_cards, false));
+        if hi_lo {
+            let low = rank_holding(variant, hole_cards, table_cards, true);
+            if low.qualifies_for_low(LOW_QUALIFIER) {
+                low_hands.insert(*id, low);
+            }
+        }
+    }
 
-        if let Some(best_rank) = ranked.clone().first().map(|(_, rank)| rank) {
-            let winners: Vec<ActorId> = ranked
-                .into_iter()
-                .filter(|(_, rank)| rank == best_rank)
-                .map(|(id, _)| *id)
-                .collect();
+    let mut results: Pots = Vec::new();
+    for (contenders, pot_amount, revealed) in contested {
+        if !revealed {
+            results.push((pot_amount, contenders, PotShare::Whole));
+            continue;
+        }
+        let high_winners =
+            best_hands(&contenders, &high_hands, order).expect("Every contender has a hand");
+        match best_hands(&contenders, &low_hands, order) {
+            Some(low_winners) => {
+                let low_half = pot_amount / 2;
+                results.push((pot_amount - low_half, high_winners, PotShare::High));
+                results.push((low_half, low_winners, PotShare::Low));
+            }
+            None => results.push((pot_amount, high_winners, PotShare::Whole)),
+        }
+    }
 
-            results.push((pot_amount, winners));
+    let mut prizes: HashMap<ActorId, u128> = HashMap::new();
+    for (amount, winners, _) in &results {
+        let share = amount / winners.len() as u128;
+        let odd_chips = (amount % winners.len() as u128) as usize;
+        for (i, winner) in winners.iter().enumerate() {
+            let odd_chip = if i < odd_chips { 1 } else { 0 };
+            *prizes.entry(*winner).or_default() += share + odd_chip;
         }
     }
+    let payouts = order
+        .iter()
+        .filter_map(|id| prizes.get(id).map(|prize| (*id, *prize)))
+        .collect();
+
+    (results, payouts)
+}
+
+/// Players of `contenders` holding the strongest of their ranked hands, listed in `order`.
+///
+/// Returns `None` if none of them has a ranked hand.
+fn best_hands(
+    contenders: &[ActorId],
+    hands: &HashMap<ActorId, HandStrength>,
+    order: &[ActorId],
+) -> Option<Vec<ActorId>> {
+    let best = contenders.iter().filter_map(|id| hands.get(id)).max()?;
+    let mut winners: Vec<ActorId> = contenders
+        .iter()
+        .filter(|id| hands.get(*id) == Some(best))
+        .copied()
+        .collect();
+    winners.sort_by_key(|id| order.iter().position(|player| player == id));
+    Some(winners)
+}
 
-    results
+/// Awards the pots of a hand run twice: each board wins half of every bet,
+/// the odd chip of a bet going to the first board.
+///
+/// Returns the pots of the first board followed by the pots of the second one,
+/// and the combined payouts of the players in `order`.
+pub fn evaluate_two_boards(
+    variant: &GameVariant,
+    hi_lo: bool,
+    hands: HashMap<ActorId, Vec<Card>>,
+    unrevealed: &[ActorId],
+    boards: [[Card; 5]; 2],
+    bank: &HashMap<ActorId, u128>,
+    order: &[ActorId],
+) -> (Pots, Vec<(ActorId, u128)>) {
+    let [first_board, second_board] = boards;
+    let first_half = bank.iter().map(|(id, bet)| (*id, bet - bet / 2)).collect();
+    let second_half = bank
+        .iter()
+        .filter(|(_, bet)| **bet > 1)
+        .map(|(id, bet)| (*id, bet / 2))
+        .collect();
+
+    let (mut pots, first_payouts) = evaluate_round(
+        variant,
+        hi_lo,
+        hands.clone(),
+        unrevealed,
+        &first_board,
+        &first_half,
+        order,
+    );
+    let (second_pots, second_payouts) = evaluate_round(
+        variant,
+        hi_lo,
+        hands,
+        unrevealed,
+        &second_board,
+        &second_half,
+        order,
+    );
+    pots.extend(second_pots);
+
+    let payouts = order
+        .iter()
+        .filter_map(|id| {
+            let prize: u128 = first_payouts
+                .iter()
+                .chain(&second_payouts)
+                .filter(|(winner, _)| winner == id)
+                .map(|(_, prize)| prize)
+                .sum();
+            (prize > 0).then_some((*id, prize))
+        })
+        .collect();
+
+    (pots, payouts)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn assert_pots_eq(actual: Vec<(u128, Vec<ActorId>)>, expected: Vec<(u128, Vec<ActorId>)>) {
+    /// Evaluates the round with the players seated in the order of their ids
+    /// and checks that the payouts share out the whole bank.
+    fn evaluate(
+        hands: HashMap<ActorId, Vec<Card>>,
+        table_cards: [Card; 5],
+        bank: &HashMap<ActorId, u128>,
+    ) -> Pots {
+        let mut order: Vec<ActorId> = bank.keys().copied().collect();
+        order.sort();
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[],
+            &table_cards,
+            bank,
+            &order,
+        );
+        assert_eq!(
+            payouts.iter().map(|(_, prize)| prize).sum::<u128>(),
+            bank.values().sum::<u128>(),
+            "Payouts don't add up to the bank"
+        );
+        pots
+    }
+
+    fn assert_pots_eq(actual: Pots, expected: Pots) {
         assert_eq!(actual.len(), expected.len(), "Number of pots differ");
         for (a, e) in actual.iter().zip(expected.iter()) {
             assert_eq!(a.0, e.0, "Pot amounts differ");
+            assert_eq!(a.2, e.2, "Pot shares differ");
             let mut actual_winners = a.1.clone();
             let mut expected_winners = e.1.clone();
             actual_winners.sort();
@@ -401,15 +517,15 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 6), Card::new(Suit::Spades, 12)),
+            vec![Card::new(Suit::Hearts, 6), Card::new(Suit::Spades, 12)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 9), Card::new(Suit::Hearts, 14)),
+            vec![Card::new(Suit::Spades, 9), Card::new(Suit::Hearts, 14)],
         );
         hands.insert(
             3.into(),
-            (Card::new(Suit::Diamonds, 11), Card::new(Suit::Spades, 13)),
+            vec![Card::new(Suit::Diamonds, 11), Card::new(Suit::Spades, 13)],
         );
 
         let table_cards = [
@@ -425,8 +541,8 @@ mod tests {
         bank.insert(2.into(), 100);
         bank.insert(3.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(300, vec![2.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(300, vec![2.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -434,15 +550,15 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 6), Card::new(Suit::Hearts, 8)),
+            vec![Card::new(Suit::Hearts, 6), Card::new(Suit::Hearts, 8)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 9), Card::new(Suit::Spades, 4)),
+            vec![Card::new(Suit::Spades, 9), Card::new(Suit::Spades, 4)],
         );
         hands.insert(
             3.into(),
-            (Card::new(Suit::Spades, 7), Card::new(Suit::Spades, 3)),
+            vec![Card::new(Suit::Spades, 7), Card::new(Suit::Spades, 3)],
         );
 
         let table_cards = [
@@ -458,8 +574,8 @@ mod tests {
         bank.insert(2.into(), 100);
         bank.insert(3.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(300, vec![1.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(300, vec![1.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -467,15 +583,15 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 6), Card::new(Suit::Hearts, 11)),
+            vec![Card::new(Suit::Hearts, 6), Card::new(Suit::Hearts, 11)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 9), Card::new(Suit::Spades, 11)),
+            vec![Card::new(Suit::Spades, 9), Card::new(Suit::Spades, 11)],
         );
         hands.insert(
             3.into(),
-            (Card::new(Suit::Spades, 7), Card::new(Suit::Spades, 3)),
+            vec![Card::new(Suit::Spades, 7), Card::new(Suit::Spades, 3)],
         );
 
         let table_cards = [
@@ -491,8 +607,8 @@ mod tests {
         bank.insert(2.into(), 100);
         bank.insert(3.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(300, vec![1.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(300, vec![1.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -500,11 +616,11 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 10), Card::new(Suit::Clubs, 14)),
+            vec![Card::new(Suit::Hearts, 10), Card::new(Suit::Clubs, 14)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 10), Card::new(Suit::Diamonds, 3)),
+            vec![Card::new(Suit::Spades, 10), Card::new(Suit::Diamonds, 3)],
         );
 
         let table_cards = [
@@ -519,8 +635,8 @@ mod tests {
         bank.insert(1.into(), 100);
         bank.insert(2.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(200, vec![1.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(200, vec![1.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -528,11 +644,11 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 2), Card::new(Suit::Clubs, 3)),
+            vec![Card::new(Suit::Hearts, 2), Card::new(Suit::Clubs, 3)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 8), Card::new(Suit::Diamonds, 9)),
+            vec![Card::new(Suit::Spades, 8), Card::new(Suit::Diamonds, 9)],
         );
 
         let table_cards = [
@@ -547,8 +663,8 @@ mod tests {
         bank.insert(1.into(), 100);
         bank.insert(2.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(200, vec![2.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(200, vec![2.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -556,11 +672,11 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 7), Card::new(Suit::Clubs, 7)),
+            vec![Card::new(Suit::Hearts, 7), Card::new(Suit::Clubs, 7)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 10), Card::new(Suit::Diamonds, 10)),
+            vec![Card::new(Suit::Spades, 10), Card::new(Suit::Diamonds, 10)],
         );
 
         let table_cards = [
@@ -575,8 +691,8 @@ mod tests {
         bank.insert(1.into(), 100);
         bank.insert(2.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(200, vec![1.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(200, vec![1.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -584,11 +700,11 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 5), Card::new(Suit::Hearts, 2)),
+            vec![Card::new(Suit::Hearts, 5), Card::new(Suit::Hearts, 2)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 6), Card::new(Suit::Diamonds, 6)),
+            vec![Card::new(Suit::Spades, 6), Card::new(Suit::Diamonds, 6)],
         );
 
         let table_cards = [
@@ -603,8 +719,8 @@ mod tests {
         bank.insert(1.into(), 100);
         bank.insert(2.into(), 100);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(200, vec![2.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(200, vec![2.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -612,15 +728,15 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 14), Card::new(Suit::Diamonds, 14)),
+            vec![Card::new(Suit::Hearts, 14), Card::new(Suit::Diamonds, 14)],
         ); // AA
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 13), Card::new(Suit::Clubs, 13)),
+            vec![Card::new(Suit::Spades, 13), Card::new(Suit::Clubs, 13)],
         ); // KK
         hands.insert(
             3.into(),
-            (Card::new(Suit::Spades, 2), Card::new(Suit::Clubs, 3)),
+            vec![Card::new(Suit::Spades, 2), Card::new(Suit::Clubs, 3)],
         );
 
         let table_cards = [
@@ -636,30 +752,100 @@ mod tests {
         bank.insert(2.into(), 200);
         bank.insert(3.into(), 200);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
+        let pots = evaluate(hands, table_cards, &bank);
         assert_pots_eq(
             pots,
             vec![
-                (300, vec![1.into()]), // main pot
-                (200, vec![2.into()]), // side pot
+                (300, vec![1.into()], PotShare::Whole), // main pot
+                (200, vec![2.into()], PotShare::Whole), // side pot
             ],
         );
     }
 
+    #[test]
+    fn test_side_pot_goes_to_last_eligible_muck() {
+        // the short all-in player 1 shows, players 2 and 3 muck in turn
+        let mut hands = HashMap::new();
+        hands.insert(
+            1.into(),
+            vec![Card::new(Suit::Spades, 2), Card::new(Suit::Clubs, 3)],
+        );
+
+        let table_cards = [
+            Card::new(Suit::Hearts, 10),
+            Card::new(Suit::Diamonds, 9),
+            Card::new(Suit::Clubs, 4),
+            Card::new(Suit::Spades, 7),
+            Card::new(Suit::Diamonds, 6),
+        ];
+
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 200);
+        bank.insert(3.into(), 200);
+        let order = vec![1.into(), 2.into(), 3.into()];
+
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[2.into(), 3.into()],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(
+            pots,
+            vec![
+                (300, vec![1.into()], PotShare::Whole),
+                (200, vec![3.into()], PotShare::Whole),
+            ],
+        );
+        assert_eq!(payouts, vec![(1.into(), 300), (3.into(), 200)]);
+    }
+
+    #[test]
+    fn test_uncontested_player_takes_only_the_pots_they_are_in() {
+        // players 2 and 3 muck, the short all-in player 1 is left and doesn't show
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 200);
+        bank.insert(3.into(), 200);
+        let order = vec![1.into(), 2.into(), 3.into()];
+
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            HashMap::new(),
+            &[2.into(), 3.into(), 1.into()],
+            &[],
+            &bank,
+            &order,
+        );
+        assert_pots_eq(
+            pots,
+            vec![
+                (300, vec![1.into()], PotShare::Whole),
+                (200, vec![3.into()], PotShare::Whole),
+            ],
+        );
+        assert_eq!(payouts, vec![(1.into(), 300), (3.into(), 200)]);
+    }
+
     #[test]
     fn test_split_pot_same_hand() {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Hearts, 10), Card::new(Suit::Clubs, 9)),
+            vec![Card::new(Suit::Hearts, 10), Card::new(Suit::Clubs, 9)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Spades, 10), Card::new(Suit::Diamonds, 9)),
+            vec![Card::new(Suit::Spades, 10), Card::new(Suit::Diamonds, 9)],
         );
         hands.insert(
             3.into(),
-            (Card::new(Suit::Spades, 2), Card::new(Suit::Hearts, 3)),
+            vec![Card::new(Suit::Spades, 2), Card::new(Suit::Hearts, 3)],
         );
 
         let table_cards = [
@@ -675,8 +861,8 @@ mod tests {
         bank.insert(2.into(), 150);
         bank.insert(3.into(), 150);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(450, vec![1.into(), 2.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(pots, vec![(450, vec![1.into(), 2.into()], PotShare::Whole)]);
     }
 
     #[test]
@@ -684,15 +870,15 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Diamonds, 6), Card::new(Suit::Hearts, 8)),
+            vec![Card::new(Suit::Diamonds, 6), Card::new(Suit::Hearts, 8)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Diamonds, 13), Card::new(Suit::Hearts, 3)),
+            vec![Card::new(Suit::Diamonds, 13), Card::new(Suit::Hearts, 3)],
         );
         hands.insert(
             3.into(),
-            (Card::new(Suit::Hearts, 13), Card::new(Suit::Diamonds, 8)),
+            vec![Card::new(Suit::Hearts, 13), Card::new(Suit::Diamonds, 8)],
         );
 
         let table_cards = [
@@ -708,8 +894,14 @@ mod tests {
         bank.insert(2.into(), 500);
         bank.insert(3.into(), 500);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
-        assert_pots_eq(pots, vec![(300, vec![1.into()]), (800, vec![3.into()])]);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(
+            pots,
+            vec![
+                (300, vec![1.into()], PotShare::Whole),
+                (800, vec![3.into()], PotShare::Whole),
+            ],
+        );
     }
 
     #[test]
@@ -717,15 +909,15 @@ mod tests {
         let mut hands = HashMap::new();
         hands.insert(
             1.into(),
-            (Card::new(Suit::Diamonds, 6), Card::new(Suit::Hearts, 8)),
+            vec![Card::new(Suit::Diamonds, 6), Card::new(Suit::Hearts, 8)],
         );
         hands.insert(
             2.into(),
-            (Card::new(Suit::Diamonds, 13), Card::new(Suit::Hearts, 3)),
+            vec![Card::new(Suit::Diamonds, 13), Card::new(Suit::Hearts, 3)],
         );
         hands.insert(
             3.into(),
-            (Card::new(Suit::Hearts, 13), Card::new(Suit::Diamonds, 3)),
+            vec![Card::new(Suit::Hearts, 13), Card::new(Suit::Diamonds, 3)],
         );
 
         let table_cards = [
@@ -741,10 +933,454 @@ mod tests {
         bank.insert(2.into(), 500);
         bank.insert(3.into(), 500);
 
-        let pots = evaluate_round(hands, table_cards, &bank);
+        let pots = evaluate(hands, table_cards, &bank);
+        assert_pots_eq(
+            pots,
+            vec![
+                (300, vec![1.into()], PotShare::Whole),
+                (800, vec![2.into(), 3.into()], PotShare::Whole),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_odd_chip_goes_to_first_winner_after_button() {
+        // the board plays, player 4 folded after the ante
+        let mut hands = HashMap::new();
+        hands.insert(
+            1.into(),
+            vec![Card::new(Suit::Diamonds, 2), Card::new(Suit::Hearts, 3)],
+        );
+        hands.insert(
+            2.into(),
+            vec![Card::new(Suit::Clubs, 2), Card::new(Suit::Spades, 3)],
+        );
+        hands.insert(
+            3.into(),
+            vec![Card::new(Suit::Hearts, 2), Card::new(Suit::Clubs, 3)],
+        );
+
+        let table_cards = [
+            Card::new(Suit::Hearts, 14),
+            Card::new(Suit::Clubs, 13),
+            Card::new(Suit::Diamonds, 12),
+            Card::new(Suit::Spades, 11),
+            Card::new(Suit::Spades, 10),
+        ];
+
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 33);
+        bank.insert(2.into(), 33);
+        bank.insert(3.into(), 33);
+        bank.insert(4.into(), 1);
+
+        let order: Vec<ActorId> = vec![2.into(), 3.into(), 4.into(), 1.into()];
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_eq!(
+            pots,
+            vec![
+                (4, vec![2.into(), 3.into(), 1.into()], PotShare::Whole),
+                (96, vec![2.into(), 3.into(), 1.into()], PotShare::Whole)
+            ]
+        );
+        assert_eq!(
+            payouts,
+            vec![(2.into(), 34), (3.into(), 33), (1.into(), 33)]
+        );
+    }
+
+    #[test]
+    fn test_two_boards_split_every_bet() {
+        let mut hands = HashMap::new();
+        hands.insert(
+            1.into(),
+            vec![Card::new(Suit::Spades, 14), Card::new(Suit::Hearts, 14)],
+        );
+        hands.insert(
+            2.into(),
+            vec![Card::new(Suit::Spades, 13), Card::new(Suit::Hearts, 13)],
+        );
+
+        // the aces hold on the first board, the kings hit a set on the second one
+        let first_board = [
+            Card::new(Suit::Clubs, 2),
+            Card::new(Suit::Diamonds, 7),
+            Card::new(Suit::Hearts, 9),
+            Card::new(Suit::Clubs, 11),
+            Card::new(Suit::Diamonds, 4),
+        ];
+        let second_board = [
+            Card::new(Suit::Clubs, 2),
+            Card::new(Suit::Diamonds, 7),
+            Card::new(Suit::Hearts, 9),
+            Card::new(Suit::Diamonds, 13),
+            Card::new(Suit::Clubs, 5),
+        ];
+
+        // player 3 folded after putting in one chip, which goes to the first board
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 100);
+        bank.insert(3.into(), 1);
+
+        let order = vec![1.into(), 2.into(), 3.into()];
+        let (pots, payouts) = evaluate_two_boards(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[],
+            [first_board, second_board],
+            &bank,
+            &order,
+        );
+
         assert_pots_eq(
             pots,
-            vec![(300, vec![1.into()]), (800, vec![2.into(), 3.into()])],
+            vec![
+                (3, vec![1.into()], PotShare::Whole),
+                (98, vec![1.into()], PotShare::Whole),
+                (100, vec![2.into()], PotShare::Whole),
+            ],
+        );
+        assert_eq!(payouts, vec![(1.into(), 101), (2.into(), 100)]);
+    }
+
+    #[test]
+    fn test_omaha_plays_exactly_two_hole_cards() {
+        let mut hands = HashMap::new();
+        // one heart in hand doesn't make a flush with four on the board
+        hands.insert(
+            1.into(),
+            vec![
+                Card::new(Suit::Hearts, 14),
+                Card::new(Suit::Spades, 7),
+                Card::new(Suit::Diamonds, 8),
+                Card::new(Suit::Clubs, 11),
+            ],
+        );
+        // the four and the six in hand make a straight with the board
+        hands.insert(
+            2.into(),
+            vec![
+                Card::new(Suit::Spades, 13),
+                Card::new(Suit::Diamonds, 13),
+                Card::new(Suit::Spades, 4),
+                Card::new(Suit::Diamonds, 6),
+            ],
+        );
+
+        let table_cards = [
+            Card::new(Suit::Hearts, 2),
+            Card::new(Suit::Hearts, 5),
+            Card::new(Suit::Hearts, 9),
+            Card::new(Suit::Hearts, 13),
+            Card::new(Suit::Clubs, 3),
+        ];
+
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 100);
+        let order = vec![1.into(), 2.into()];
+
+        let (pots, _) = evaluate_round(
+            &GameVariant::Omaha,
+            false,
+            hands.clone(),
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(200, vec![2.into()], PotShare::Whole)]);
+
+        // any five of the cards would make the ace-high flush
+        let (pots, _) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(200, vec![1.into()], PotShare::Whole)]);
+    }
+
+    #[test]
+    fn test_five_card_draw_plays_the_hand_alone() {
+        let mut hands = HashMap::new();
+        hands.insert(
+            1.into(),
+            vec![
+                Card::new(Suit::Spades, 2),
+                Card::new(Suit::Spades, 7),
+                Card::new(Suit::Spades, 9),
+                Card::new(Suit::Spades, 11),
+                Card::new(Suit::Spades, 4),
+            ],
+        );
+        hands.insert(
+            2.into(),
+            vec![
+                Card::new(Suit::Hearts, 10),
+                Card::new(Suit::Clubs, 11),
+                Card::new(Suit::Hearts, 12),
+                Card::new(Suit::Diamonds, 13),
+                Card::new(Suit::Hearts, 14),
+            ],
+        );
+        hands.insert(
+            3.into(),
+            vec![
+                Card::new(Suit::Diamonds, 14),
+                Card::new(Suit::Clubs, 14),
+                Card::new(Suit::Diamonds, 2),
+                Card::new(Suit::Clubs, 3),
+                Card::new(Suit::Hearts, 5),
+            ],
+        );
+
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 100);
+        bank.insert(3.into(), 100);
+        let order = vec![1.into(), 2.into(), 3.into()];
+
+        // the flush beats the straight, the pair of aces takes nothing
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::FiveCardDraw { max_discards: 3 },
+            false,
+            hands,
+            &[],
+            &[],
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(300, vec![1.into()], PotShare::Whole)]);
+        assert_eq!(payouts, vec![(1.into(), 300)]);
+    }
+
+    #[test]
+    fn test_short_deck_ranks_flush_over_full_house() {
+        let table_cards = [
+            Card::new(Suit::Hearts, 6),
+            Card::new(Suit::Hearts, 7),
+            Card::new(Suit::Clubs, 8),
+            Card::new(Suit::Hearts, 13),
+            Card::new(Suit::Spades, 13),
+        ];
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 100);
+        let order = vec![1.into(), 2.into()];
+
+        // a king-high flush against kings full of eights
+        let mut hands = HashMap::new();
+        hands.insert(
+            1.into(),
+            vec![Card::new(Suit::Hearts, 12), Card::new(Suit::Hearts, 10)],
+        );
+        hands.insert(
+            2.into(),
+            vec![Card::new(Suit::Diamonds, 13), Card::new(Suit::Diamonds, 8)],
+        );
+        let (pots, _) = evaluate_round(
+            &GameVariant::ShortDeck,
+            false,
+            hands.clone(),
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(200, vec![1.into()], PotShare::Whole)]);
+        let (pots, _) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(200, vec![2.into()], PotShare::Whole)]);
+
+        // A-6-7-8-9 against three kings
+        let mut hands = HashMap::new();
+        hands.insert(
+            1.into(),
+            vec![Card::new(Suit::Clubs, 14), Card::new(Suit::Diamonds, 9)],
+        );
+        hands.insert(
+            2.into(),
+            vec![Card::new(Suit::Clubs, 13), Card::new(Suit::Diamonds, 12)],
+        );
+        let (pots, _) = evaluate_round(
+            &GameVariant::ShortDeck,
+            false,
+            hands.clone(),
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(200, vec![1.into()], PotShare::Whole)]);
+        let (pots, _) = evaluate_round(
+            &GameVariant::Holdem,
+            false,
+            hands,
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(pots, vec![(200, vec![2.into()], PotShare::Whole)]);
+    }
+
+    #[test]
+    fn test_hi_lo_splits_every_pot() {
+        let mut hands = HashMap::new();
+        // three kings for the high half, 7-4-3-2-A for the low one
+        hands.insert(
+            1.into(),
+            vec![
+                Card::new(Suit::Hearts, 14),
+                Card::new(Suit::Diamonds, 2),
+                Card::new(Suit::Diamonds, 13),
+                Card::new(Suit::Spades, 13),
+            ],
+        );
+        // three queens and no low
+        hands.insert(
+            2.into(),
+            vec![
+                Card::new(Suit::Hearts, 12),
+                Card::new(Suit::Spades, 12),
+                Card::new(Suit::Diamonds, 9),
+                Card::new(Suit::Clubs, 9),
+            ],
+        );
+        // the same low as player 1
+        hands.insert(
+            3.into(),
+            vec![
+                Card::new(Suit::Diamonds, 14),
+                Card::new(Suit::Clubs, 2),
+                Card::new(Suit::Hearts, 11),
+                Card::new(Suit::Spades, 11),
+            ],
+        );
+
+        let table_cards = [
+            Card::new(Suit::Clubs, 3),
+            Card::new(Suit::Diamonds, 4),
+            Card::new(Suit::Spades, 7),
+            Card::new(Suit::Hearts, 13),
+            Card::new(Suit::Clubs, 12),
+        ];
+
+        // players 1 and 3 are all-in, the side pot of player 2 has no low hand
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 101);
+        bank.insert(2.into(), 201);
+        bank.insert(3.into(), 101);
+        let order = vec![1.into(), 2.into(), 3.into()];
+
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::Omaha,
+            true,
+            hands.clone(),
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        // the odd chip of the main pot goes to the high half, the one of the low half to player 1
+        assert_eq!(
+            pots,
+            vec![
+                (152, vec![1.into()], PotShare::High),
+                (151, vec![1.into(), 3.into()], PotShare::Low),
+                (100, vec![2.into()], PotShare::Whole),
+            ]
+        );
+        assert_eq!(
+            payouts,
+            vec![(1.into(), 228), (2.into(), 100), (3.into(), 75)]
+        );
+
+        let (pots, _) = evaluate_round(
+            &GameVariant::Omaha,
+            false,
+            hands,
+            &[],
+            &table_cards,
+            &bank,
+            &order,
+        );
+        assert_pots_eq(
+            pots,
+            vec![
+                (303, vec![1.into()], PotShare::Whole),
+                (100, vec![2.into()], PotShare::Whole),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_hi_lo_scoops_without_a_qualifying_low() {
+        let mut hands = HashMap::new();
+        // the ace, two and five make no low with only two low cards on the board
+        hands.insert(
+            1.into(),
+            vec![
+                Card::new(Suit::Hearts, 14),
+                Card::new(Suit::Diamonds, 2),
+                Card::new(Suit::Clubs, 5),
+                Card::new(Suit::Hearts, 6),
+            ],
+        );
+        hands.insert(
+            2.into(),
+            vec![
+                Card::new(Suit::Spades, 13),
+                Card::new(Suit::Diamonds, 13),
+                Card::new(Suit::Hearts, 8),
+                Card::new(Suit::Clubs, 8),
+            ],
+        );
+
+        let table_cards = [
+            Card::new(Suit::Clubs, 3),
+            Card::new(Suit::Diamonds, 4),
+            Card::new(Suit::Spades, 9),
+            Card::new(Suit::Hearts, 13),
+            Card::new(Suit::Clubs, 12),
+        ];
+
+        let mut bank = HashMap::new();
+        bank.insert(1.into(), 100);
+        bank.insert(2.into(), 100);
+        let order = vec![1.into(), 2.into()];
+
+        let (pots, payouts) = evaluate_round(
+            &GameVariant::Omaha,
+            true,
+            hands,
+            &[],
+            &table_cards,
+            &bank,
+            &order,
         );
+        assert_eq!(pots, vec![(200, vec![2.into()], PotShare::Whole)]);
+        assert_eq!(payouts, vec![(2.into(), 200)]);
     }
 }
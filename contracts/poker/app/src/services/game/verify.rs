@@ -1,24 +1,28 @@
 use crate::services::game::EdwardsProjective;
 use crate::services::game::{
-    EncryptedCard, ZkPublicKey,
+    EncryptedCard, PokerError, ZkPublicKey,
     curve::{
-        compare_points, compare_projective_and_coords, compare_public_keys,
-        deserialize_bandersnatch_coords,
+        compare_projective_and_coords, compare_public_keys, deserialize_bandersnatch_coords,
+        deserialize_checked_coords, deserialize_checked_public_key,
     },
 };
-use ark_ec::CurveGroup;
+use ark_ec::{CurveGroup, PrimeGroup};
 
 use ark_ed_on_bls12_381_bandersnatch::{Fq, Fr};
 use ark_ff::{BigInteger, One, PrimeField};
 use blake2::{Blake2b512, Digest};
 use sails_rs::prelude::*;
 use zk_verification_client::VerificationVariables;
+/// Cards dealt to the board
+pub const TABLE_CARDS: usize = 5;
+
 /// Card deck configuration constants
 #[derive(Debug, Clone, Copy)]
 pub struct DeckConfig {
     pub num_cards: usize,
     pub num_coords: usize, // Coordinates per encrypted card point (X, Y, Z for both c0 and c1)
     pub pk_size: usize,    // Public key components count
+    pub nonce_size: usize, // Per-hand shuffle nonce
 }
 
 impl DeckConfig {
@@ -26,24 +30,62 @@ impl DeckConfig {
         num_cards: 52,
         num_coords: 6, // c0.X, c0.Y, c0.Z, c1.X, c1.Y, c1.Z
         pk_size: 3,    // x, y, z coordinates
+        nonce_size: 1,
+    };
+
+    /// Short deck of the 36 cards from six up to the ace
+    pub const SHORT: Self = Self {
+        num_cards: 36,
+        ..Self::STANDARD
     };
 
+    /// Lowest card value, every value from it up to the ace is in each suit
+    pub const fn lowest_value(&self) -> u8 {
+        (15 - self.num_cards / 4) as u8
+    }
+
+    /// Most players that can be dealt their hole cards with the board still to come
+    pub const fn max_players(&self, hole_cards: usize) -> usize {
+        (self.num_cards - TABLE_CARDS) / hole_cards
+    }
+
     #[inline]
     pub const fn expected_input_length(&self) -> usize {
-        1 + self.pk_size + (self.num_coords * self.num_cards * 2) // valid + pk + original + permuted
+        1 + self.pk_size + self.nonce_size + (self.num_coords * self.num_cards * 2) // valid + pk + nonce + original + permuted
     }
 }
 
+/// Domain tag of the per-hand shuffle nonce
+const SHUFFLE_NONCE_DOMAIN: &[u8] = b"zk-mental-poker/shuffle-nonce/v1";
+
+/// Derives the nonce that a player's shuffle proof must carry as a public input.
+///
+/// The nonce is bound to the lobby, the round and the submitting player, so a
+/// shuffle from an earlier hand, another lobby or another seat can't be resubmitted.
+pub fn shuffle_nonce(lobby_id: ActorId, round: u64, player_id: ActorId) -> Fq {
+    let lobby_id: [u8; 32] = lobby_id.into();
+    let player_id: [u8; 32] = player_id.into();
+    let mut hasher = Blake2b512::new();
+    hasher.update(SHUFFLE_NONCE_DOMAIN);
+    hasher.update(lobby_id);
+    hasher.update(round.to_le_bytes());
+    hasher.update(player_id);
+
+    let hash_bytes = hasher.finalize();
+    Fq::from_le_bytes_mod_order(&hash_bytes[..32])
+}
+
 // ================================================================================================
 // Core Data Structures
 // ================================================================================================
 
-/// Parsed public input containing original deck, permuted deck, and public key
+/// Parsed public input containing original deck, permuted deck, public key and shuffle nonce
 #[derive(Debug, Clone)]
 pub struct ParsedPublicInput {
     pub original_deck: Vec<EncryptedCard>,
     pub permuted_deck: Vec<EncryptedCard>,
     pub public_key: ZkPublicKey,
+    pub nonce: Fq,
 }
 
 // ================================================================================================
@@ -58,48 +100,48 @@ impl PublicInputParser {
     pub fn parse_original_and_permuted(
         public_input: &[Vec<u8>],
         config: DeckConfig,
-    ) -> ParsedPublicInput {
+    ) -> Result<ParsedPublicInput, PokerError> {
         if public_input.len() != config.expected_input_length() {
-            panic!("Invalid proof length");
+            return Err(PokerError::InvalidPublicInput);
         }
 
         // Validate proof validity flag
         let is_valid = Fq::from_le_bytes_mod_order(&public_input[0]);
         if is_valid != Fq::one() {
-            panic!("Invalid proof flag");
+            return Err(PokerError::InvalidPublicInput);
         }
 
-        let public_key = Self::extract_public_key(public_input);
+        let public_key = Self::extract_public_key(public_input)?;
+        let nonce = Fq::from_le_bytes_mod_order(&public_input[1 + config.pk_size]);
 
         // Parse decks
-        let original_offset = 1 + config.pk_size;
+        let original_offset = 1 + config.pk_size + config.nonce_size;
         let permuted_offset = original_offset + config.num_coords * config.num_cards;
 
         let original_deck = Self::parse_encrypted_deck(public_input, original_offset, config);
         let permuted_deck = Self::parse_encrypted_deck(public_input, permuted_offset, config);
 
-        ParsedPublicInput {
+        Ok(ParsedPublicInput {
             original_deck,
             permuted_deck,
             public_key,
-        }
+            nonce,
+        })
     }
 
-    fn extract_public_key(public_input: &[Vec<u8>]) -> ZkPublicKey {
-        ZkPublicKey {
-            x: public_input[1]
-                .clone()
-                .try_into()
-                .expect("Deserialization failed: pk.x"),
-            y: public_input[2]
+    fn extract_public_key(public_input: &[Vec<u8>]) -> Result<ZkPublicKey, PokerError> {
+        let coord = |i: usize| -> Result<[u8; 32], PokerError> {
+            public_input[i]
                 .clone()
                 .try_into()
-                .expect("Deserialization failed: pk.y"),
-            z: public_input[3]
-                .clone()
-                .try_into()
-                .expect("Deserialization failed: pk.z"),
-        }
+                .map_err(|_| PokerError::InvalidPublicInput)
+        };
+
+        Ok(ZkPublicKey {
+            x: coord(1)?,
+            y: coord(2)?,
+            z: coord(3)?,
+        })
     }
 
     fn parse_encrypted_deck(
@@ -128,72 +170,70 @@ impl PublicInputParser {
 pub struct ShuffleChainValidator;
 
 impl ShuffleChainValidator {
-    /// Validates the integrity of a shuffle chain
-    pub fn validate_shuffle_chain(
-        instances: &[VerificationVariables],
+    /// Validates one link of a shuffle chain and returns the shuffled deck.
+    ///
+    /// `previous_deck` is the deck produced by the previous shuffle of the hand,
+    /// `None` for the first one, which must start from `original_deck`.
+    pub fn validate_shuffle_step(
+        instance: &VerificationVariables,
+        config: DeckConfig,
         original_deck: &[EdwardsProjective],
+        previous_deck: Option<&[EncryptedCard]>,
         expected_pub_key: &ZkPublicKey,
-        final_encrypted_deck: &[EncryptedCard],
-    ) {
-        let config = DeckConfig::STANDARD;
+        expected_nonce: Fq,
+    ) -> Result<Vec<EncryptedCard>, PokerError> {
+        let parsed =
+            PublicInputParser::parse_original_and_permuted(&instance.public_input, config)?;
 
-        // Parse and validate first instance
-        let first_parsed =
-            PublicInputParser::parse_original_and_permuted(&instances[0].public_input, config);
-
-        if !compare_public_keys(expected_pub_key, &first_parsed.public_key) {
-            panic!("Public key mismatch");
+        if !compare_public_keys(expected_pub_key, &parsed.public_key) {
+            return Err(PokerError::PublicKeyMismatch);
         }
-        Self::validate_initial_deck_matches(original_deck, &first_parsed.original_deck);
 
-        let mut current_deck = first_parsed.permuted_deck;
+        if parsed.nonce != expected_nonce {
+            return Err(PokerError::ShuffleNonceMismatch);
+        }
 
         // Validate chain continuity
-        for instance in instances[1..].iter() {
-            let parsed =
-                PublicInputParser::parse_original_and_permuted(&instance.public_input, config);
-
-            if !compare_public_keys(expected_pub_key, &parsed.public_key) {
-                panic!("Public key mismatch");
-            }
-
-            if parsed.original_deck != current_deck {
-                panic!("Shuffle chain discontinuity");
+        match previous_deck {
+            None => Self::validate_initial_deck_matches(original_deck, &parsed.original_deck)?,
+            Some(previous_deck) => {
+                if parsed.original_deck != previous_deck {
+                    return Err(PokerError::ShuffleChainDiscontinuity);
+                }
             }
-
-            current_deck = parsed.permuted_deck;
         }
 
-        // Validate final deck state
-        Self::validate_final_deck_matches(&current_deck, final_encrypted_deck);
+        Ok(parsed.permuted_deck)
     }
 
-    fn validate_initial_deck_matches(expected: &[EdwardsProjective], actual: &[EncryptedCard]) {
+    fn validate_initial_deck_matches(
+        expected: &[EdwardsProjective],
+        actual: &[EncryptedCard],
+    ) -> Result<(), PokerError> {
         if expected.len() != actual.len() {
-            panic!("Initial deck len mismatch");
+            return Err(PokerError::InitialDeckMismatch);
         }
 
         for (expected_card, actual_card) in expected.iter().zip(actual) {
             if !compare_projective_and_coords(expected_card, &actual_card.c1) {
-                panic!("Initial deck mismatch");
+                return Err(PokerError::InitialDeckMismatch);
             }
         }
-    }
 
-    fn validate_final_deck_matches(expected: &[EncryptedCard], actual: &[EncryptedCard]) {
-        for (expected_card, actual_card) in expected.iter().zip(actual) {
-            if !compare_points(&expected_card.c0, &actual_card.c0)
-                || !compare_points(&expected_card.c1, &actual_card.c1)
-            {
-                panic!("Final deck mismatch");
-            }
-        }
+        Ok(())
     }
 }
 
-fn hash_to_fr(points: &[EdwardsProjective]) -> Fr {
+/// Domain tag of the proof of knowledge of a player's secret key
+const SECRET_KEY_POK_DOMAIN: &[u8] = b"zk-mental-poker/sk-pok/v1";
+
+fn hash_to_fr(prefix: &[&[u8]], points: &[EdwardsProjective]) -> Fr {
     let mut hasher = Blake2b512::new();
 
+    for bytes in prefix {
+        hasher.update(bytes);
+    }
+
     for p in points {
         let affine = p.into_affine();
         let x_bytes = affine.x.into_bigint().to_bytes_le();
@@ -207,6 +247,45 @@ fn hash_to_fr(points: &[EdwardsProjective]) -> Fr {
     Fr::from_le_bytes_mod_order(&hash_bytes[..32])
 }
 
+/// Domain tag of the partial decryption (Chaum-Pedersen) proof transcript
+const DECRYPTION_PROOF_DOMAIN: &[u8] = b"zk-mental-poker/cp-dec";
+
+/// Version of the partial decryption proof transcript, bumped on every format change
+pub const DECRYPTION_PROOF_VERSION: u32 = 1;
+
+/// Position of a card whose partial decryption is proven
+#[derive(Debug, Clone, Copy, Encode, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+pub enum CardSlot {
+    Hole { owner: ActorId, index: u8 },
+    Table { index: u8 },
+}
+
+/// Context a partial decryption proof is bound to.
+///
+/// The transcript prefix is
+/// `tag || version (u32 LE) || lobby_id (32 bytes) || round (u64 LE) || SCALE(slot)`,
+/// so a proof can't be replayed in another lobby, hand or card position.
+pub struct DecryptionContext {
+    pub lobby_id: ActorId,
+    pub round: u64,
+    pub slot: CardSlot,
+}
+
+impl DecryptionContext {
+    fn transcript_prefix(&self) -> Vec<u8> {
+        let lobby_id: [u8; 32] = self.lobby_id.into();
+        [
+            DECRYPTION_PROOF_DOMAIN,
+            &DECRYPTION_PROOF_VERSION.to_le_bytes(),
+            &lobby_id,
+            &self.round.to_le_bytes(),
+            &self.slot.encode(),
+        ]
+        .concat()
+    }
+}
+
 pub struct ChaumPedersenProof {
     pub a: EdwardsProjective,
     pub b: EdwardsProjective,
@@ -238,8 +317,12 @@ pub fn verify_chaum_pedersen(
     c0: EdwardsProjective,
     delta_c0: EdwardsProjective,
     proof: &ChaumPedersenProof,
+    context: &DecryptionContext,
 ) -> bool {
-    let c = hash_to_fr(&[g, pk, c0, delta_c0, proof.a, proof.b]);
+    let c = hash_to_fr(
+        &[&context.transcript_prefix()],
+        &[g, pk, c0, delta_c0, proof.a, proof.b],
+    );
 
     let lhs1 = g * proof.z;
     let rhs1 = proof.a + pk * c;
@@ -249,3 +332,52 @@ pub fn verify_chaum_pedersen(
 
     lhs1 == rhs1 && lhs2 == rhs2
 }
+
+pub struct SchnorrProof {
+    pub a: EdwardsProjective,
+    pub z: Fr,
+}
+
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct SchnorrProofBytes {
+    pub a: [Vec<u8>; 3],
+    pub z: Vec<u8>,
+}
+
+impl SchnorrProofBytes {
+    /// Returns `None` if the commitment is not a point of the prime-order subgroup
+    pub fn into_proof(self) -> Option<SchnorrProof> {
+        let a = deserialize_checked_coords(&self.a[0], &self.a[1], &self.a[2])?;
+        let z = Fr::from_le_bytes_mod_order(&self.z);
+
+        Some(SchnorrProof { a, z })
+    }
+}
+
+/// Verifies a Schnorr proof of knowledge of `sk` such that `pk = g * sk`.
+///
+/// The challenge is bound to the player and the lobby, so a proof published
+/// for one registration cannot be replayed by another account or in another lobby.
+/// Keys and commitments outside the prime-order subgroup are rejected, since a
+/// small-order component would pass the check and corrupt the aggregate key.
+pub fn verify_secret_key_knowledge(
+    pk: &ZkPublicKey,
+    player_id: ActorId,
+    lobby_id: ActorId,
+    proof: SchnorrProofBytes,
+) -> bool {
+    let (Some(pk), Some(proof)) = (deserialize_checked_public_key(pk), proof.into_proof()) else {
+        return false;
+    };
+    let g = EdwardsProjective::generator();
+    let player_id: [u8; 32] = player_id.into();
+    let lobby_id: [u8; 32] = lobby_id.into();
+    let c = hash_to_fr(
+        &[SECRET_KEY_POK_DOMAIN, &player_id, &lobby_id],
+        &[g, pk, proof.a],
+    );
+
+    g * proof.z == proof.a + pk * c
+}
@@ -1,4 +1,6 @@
 #![no_std]
 #![allow(clippy::too_many_arguments)]
+// The IDL drops the indentation of continued list items in doc comments
+#![allow(clippy::doc_lazy_continuation)]
 // Incorporate code generated based on the IDL file
 include!(concat!(env!("OUT_DIR"), "/poker_client.rs"));
@@ -0,0 +1,487 @@
+//! R1CS port of the circom circuits in `circuits/`.
+//!
+//! Every gadget mirrors the template of the same name, so the public signals
+//! (and their order) are the ones snarkjs produces for the circom circuits.
+use ark_ec::{twisted_edwards::TECurveConfig, AffineRepr, CurveGroup, PrimeGroup};
+use ark_ed_on_bls12_381_bandersnatch::{
+    BandersnatchConfig, EdwardsAffine, EdwardsProjective, Fq, Fr,
+};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
+};
+
+type Result<T> = core::result::Result<T, SynthesisError>;
+
+/// Scalar width of the secret keys and of the summed encryption randomness
+pub const NUM_BITS: usize = 64;
+
+/// Circuit signal: a linear combination of variables together with its value
+#[derive(Clone)]
+struct Signal {
+    lc: LinearCombination<Fq>,
+    value: Fq,
+}
+
+impl Signal {
+    fn constant(value: Fq) -> Self {
+        Self {
+            lc: LinearCombination::from((value, Variable::One)),
+            value,
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self {
+            lc: &self.lc + &other.lc,
+            value: self.value + other.value,
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self {
+            lc: &self.lc - &other.lc,
+            value: self.value - other.value,
+        }
+    }
+
+    fn scale(&self, coeff: Fq) -> Self {
+        Self {
+            lc: &self.lc * coeff,
+            value: self.value * coeff,
+        }
+    }
+
+    /// `1 - self`, the negation of a boolean signal
+    fn not(&self) -> Self {
+        Self::constant(Fq::one()).sub(self)
+    }
+}
+
+#[derive(Clone)]
+struct Point {
+    x: Signal,
+    y: Signal,
+    z: Signal,
+}
+
+impl Point {
+    fn constant(point: EdwardsAffine) -> Self {
+        Self {
+            x: Signal::constant(point.x),
+            y: Signal::constant(point.y),
+            z: Signal::constant(Fq::one()),
+        }
+    }
+
+    fn identity() -> Self {
+        Self::constant(EdwardsAffine::zero())
+    }
+}
+
+struct Builder {
+    cs: ConstraintSystemRef<Fq>,
+}
+
+impl Builder {
+    fn input(&self, value: Fq) -> Result<Signal> {
+        let var = self.cs.new_input_variable(|| Ok(value))?;
+        Ok(Signal {
+            lc: var.into(),
+            value,
+        })
+    }
+
+    fn witness(&self, value: Fq) -> Result<Signal> {
+        let var = self.cs.new_witness_variable(|| Ok(value))?;
+        Ok(Signal {
+            lc: var.into(),
+            value,
+        })
+    }
+
+    fn input_point(&self, coords: [Fq; 3]) -> Result<Point> {
+        Ok(Point {
+            x: self.input(coords[0])?,
+            y: self.input(coords[1])?,
+            z: self.input(coords[2])?,
+        })
+    }
+
+    fn enforce(&self, a: &Signal, b: &Signal, c: &Signal) -> Result<()> {
+        self.cs
+            .enforce_constraint(a.lc.clone(), b.lc.clone(), c.lc.clone())
+    }
+
+    fn enforce_equal(&self, a: &Signal, b: &Signal) -> Result<()> {
+        self.enforce(a, &Signal::constant(Fq::one()), b)
+    }
+
+    fn mul(&self, a: &Signal, b: &Signal) -> Result<Signal> {
+        let out = self.witness(a.value * b.value)?;
+        self.enforce(a, b, &out)?;
+        Ok(out)
+    }
+
+    /// `IsZero` of circomlib
+    fn is_zero(&self, x: &Signal) -> Result<Signal> {
+        let inv = self.witness(x.value.inverse().unwrap_or_default())?;
+        let out = self.witness(if x.value.is_zero() {
+            Fq::one()
+        } else {
+            Fq::zero()
+        })?;
+        self.enforce(x, &inv, &out.not())?;
+        self.enforce(x, &out, &Signal::constant(Fq::zero()))?;
+        Ok(out)
+    }
+
+    /// `IsEqual` of circomlib
+    fn is_equal(&self, a: &Signal, b: &Signal) -> Result<Signal> {
+        self.is_zero(&a.sub(b))
+    }
+
+    /// `Num2Bits` of circomlib, least significant bit first
+    fn bits(&self, x: &Signal, num_bits: usize) -> Result<Vec<Signal>> {
+        let repr = x.value.into_bigint();
+        let mut sum = Signal::constant(Fq::zero());
+        let mut coeff = Fq::one();
+        let mut bits = Vec::with_capacity(num_bits);
+        for i in 0..num_bits {
+            let bit = self.witness(Fq::from(repr.get_bit(i)))?;
+            self.enforce(&bit, &bit.not(), &Signal::constant(Fq::zero()))?;
+            sum = sum.add(&bit.scale(coeff));
+            coeff += coeff;
+            bits.push(bit);
+        }
+        self.enforce_equal(&sum, x)?;
+        Ok(bits)
+    }
+
+    /// Selects `a` when `flag` is 1 and `b` when it is 0
+    fn select(&self, flag: &Signal, a: &Signal, b: &Signal) -> Result<Signal> {
+        Ok(b.add(&self.mul(flag, &a.sub(b))?))
+    }
+
+    fn is_neutral(&self, p: &Point) -> Result<Signal> {
+        let one = Signal::constant(Fq::one());
+        let x_zero = self.is_zero(&p.x)?;
+        let y_one = self.is_equal(&p.y, &one)?;
+        let z_one = self.is_equal(&p.z, &one)?;
+        let tmp = self.mul(&x_zero, &y_one)?;
+        self.mul(&tmp, &z_one)
+    }
+
+    /// `BandersnatchAddProjective`
+    fn add(&self, p1: &Point, p2: &Point) -> Result<Point> {
+        let a = BandersnatchConfig::COEFF_A;
+        let d = BandersnatchConfig::COEFF_D;
+
+        let p1_neutral = self.is_neutral(p1)?;
+        let p2_neutral = self.is_neutral(p2)?;
+
+        let aa = self.mul(&p1.z, &p2.z)?;
+        let b = self.mul(&aa, &aa)?;
+        let c = self.mul(&p1.x, &p2.x)?;
+        let dd = self.mul(&p1.y, &p2.y)?;
+        let e = self.mul(&c.scale(d), &dd)?;
+        let f = b.sub(&e);
+        let g = b.add(&e);
+
+        let mult_sum = self.mul(&p1.x.add(&p1.y), &p2.x.add(&p2.y))?;
+        let core = mult_sum.sub(&c).sub(&dd);
+        let af = self.mul(&aa, &f)?;
+        let core_x = self.mul(&af, &core)?;
+        let ag = self.mul(&aa, &g)?;
+        let core_y = self.mul(&ag, &dd.sub(&c.scale(a)))?;
+        let core_z = self.mul(&f, &g)?;
+
+        let alt = |c1: &Signal, c2: &Signal| -> Result<Signal> {
+            Ok(self.mul(&p1_neutral, c2)?.add(&self.mul(&p2_neutral, c1)?))
+        };
+        let alt_x = alt(&p1.x, &p2.x)?;
+        let alt_y = alt(&p1.y, &p2.y)?;
+        let alt_z = alt(&p1.z, &p2.z)?;
+
+        let either = p1_neutral
+            .add(&p2_neutral)
+            .sub(&self.mul(&p1_neutral, &p2_neutral)?);
+        let not_either = either.not();
+        let pick = |core: &Signal, alt: &Signal| -> Result<Signal> {
+            Ok(self.mul(&not_either, core)?.add(&self.mul(&either, alt)?))
+        };
+
+        Ok(Point {
+            x: pick(&core_x, &alt_x)?,
+            y: pick(&core_y, &alt_y)?,
+            z: pick(&core_z, &alt_z)?,
+        })
+    }
+
+    /// `BandersnatchDoubleProjective`
+    fn double(&self, p: &Point) -> Result<Point> {
+        let a = BandersnatchConfig::COEFF_A;
+        let neutral = self.is_neutral(p)?;
+
+        let x_plus_y = p.x.add(&p.y);
+        let b = self.mul(&x_plus_y, &x_plus_y)?;
+        let c = self.mul(&p.x, &p.x)?;
+        let d = self.mul(&p.y, &p.y)?;
+        let e = c.scale(a);
+        let f = e.add(&d);
+        let h = self.mul(&p.z, &p.z)?;
+        let j = f.sub(&h.scale(Fq::from(2u64)));
+
+        let raw_x = self.mul(&b.sub(&c).sub(&d), &j)?;
+        let raw_y = self.mul(&f, &e.sub(&d))?;
+        let raw_z = self.mul(&f, &j)?;
+
+        let not_neutral = neutral.not();
+        Ok(Point {
+            x: self.mul(&not_neutral, &raw_x)?,
+            y: neutral.add(&self.mul(&not_neutral, &raw_y)?),
+            z: neutral.add(&self.mul(&not_neutral, &raw_z)?),
+        })
+    }
+
+    /// `BandersnatchScalarMulProjective`, double-and-add from the most significant bit
+    fn scalar_mul(&self, p: &Point, scalar: &Signal, num_bits: usize) -> Result<Point> {
+        let neutral = self.is_neutral(p)?;
+        let not_neutral = neutral.not();
+        let base = Point {
+            x: self.mul(&not_neutral, &p.x)?,
+            y: neutral.add(&self.mul(&not_neutral, &p.y)?),
+            z: neutral.add(&self.mul(&not_neutral, &p.z)?),
+        };
+
+        let bits = self.bits(scalar, num_bits)?;
+        let top = &bits[num_bits - 1];
+        let mut acc = Point {
+            x: self.mul(top, &base.x)?,
+            y: self.mul(top, &base.y)?.add(&top.not()),
+            z: self.mul(top, &base.z)?.add(&top.not()),
+        };
+
+        for bit in bits[..num_bits - 1].iter().rev() {
+            let doubled = self.double(&acc)?;
+            let added = self.add(&doubled, &base)?;
+            acc = Point {
+                x: self.select(bit, &added.x, &doubled.x)?,
+                y: self.select(bit, &added.y, &doubled.y)?,
+                z: self.select(bit, &added.z, &doubled.z)?,
+            };
+        }
+
+        let zero_scalar = self.is_zero(scalar)?;
+        let not_zero = zero_scalar.not();
+        Ok(Point {
+            x: self.mul(&not_zero, &acc.x)?,
+            y: zero_scalar.add(&self.mul(&not_zero, &acc.y)?),
+            z: zero_scalar.add(&self.mul(&not_zero, &acc.z)?),
+        })
+    }
+
+    /// `IsEqualProjective`
+    fn is_equal_projective(&self, p1: &Point, p2: &Point) -> Result<Signal> {
+        let x_eq = self.is_equal(&self.mul(&p1.x, &p2.z)?, &self.mul(&p2.x, &p1.z)?)?;
+        let y_eq = self.is_equal(&self.mul(&p1.y, &p2.z)?, &self.mul(&p2.y, &p1.z)?)?;
+        self.mul(&x_eq, &y_eq)
+    }
+
+    fn sum(&self, points: &[Point]) -> Result<Point> {
+        points
+            .iter()
+            .try_fold(Point::identity(), |acc, point| self.add(&acc, point))
+    }
+}
+
+/// Projective coordinates of a point, `[X, Y, Z]`
+pub type Coords = [Fq; 3];
+
+/// Encrypted card, `[c0.X, c0.Y, c0.Z, c1.X, c1.Y, c1.Z]`
+pub type CardCoords = [Fq; 6];
+
+pub fn coords(point: &EdwardsProjective) -> Coords {
+    let affine = point.into_affine();
+    [affine.x, affine.y, Fq::one()]
+}
+
+pub fn point(coords: &Coords) -> EdwardsProjective {
+    let z_inv = coords[2].inverse().expect("Point at infinity");
+    EdwardsAffine::new_unchecked(coords[0] * z_inv, coords[1] * z_inv).into()
+}
+
+/// `ShuffleEncrypt(numCards)`: the permuted deck is a re-encryption of the original one
+/// under `pk` with the summed randomness `R`.
+///
+/// Public signals: `[isValid, pk[3], nonce, original[6][n], permuted[6][n]]`.
+#[derive(Clone)]
+pub struct ShuffleEncryptCircuit {
+    pub pk: Coords,
+    pub nonce: Fq,
+    pub original: Vec<CardCoords>,
+    pub permuted: Vec<CardCoords>,
+    pub r: Vec<Fq>,
+}
+
+impl ShuffleEncryptCircuit {
+    /// Circuit of an `num_cards` deck with placeholder values, used for the key setup
+    pub fn blank(num_cards: usize) -> Self {
+        let identity = coords(&EdwardsProjective::zero());
+        let card = [identity, identity]
+            .concat()
+            .try_into()
+            .expect("Six coordinates");
+        Self {
+            pk: coords(&EdwardsProjective::generator()),
+            nonce: Fq::zero(),
+            original: vec![card; num_cards],
+            permuted: vec![card; num_cards],
+            r: vec![Fq::zero(); num_cards],
+        }
+    }
+
+    /// Value of `isValid` computed outside of the circuit
+    fn is_valid(&self) -> Fq {
+        let sum = |deck: &[CardCoords], offset: usize| -> EdwardsProjective {
+            deck.iter()
+                .map(|card| point(&[card[offset], card[offset + 1], card[offset + 2]]))
+                .sum()
+        };
+        let r_sum: Fq = self.r.iter().sum();
+        let r_sum = Fr::from_le_bytes_mod_order(&r_sum.into_bigint().to_bytes_le());
+        let valid = sum(&self.permuted, 0)
+            == EdwardsProjective::generator() * r_sum + sum(&self.original, 0)
+            && sum(&self.permuted, 3) == point(&self.pk) * r_sum + sum(&self.original, 3);
+        Fq::from(valid)
+    }
+
+    /// Public signals in the snarkjs order
+    pub fn public_signals(&self) -> Vec<Fq> {
+        let mut signals = vec![self.is_valid()];
+        signals.extend(self.pk);
+        signals.push(self.nonce);
+        for deck in [&self.original, &self.permuted] {
+            for row in 0..6 {
+                signals.extend(deck.iter().map(|card| card[row]));
+            }
+        }
+        signals
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for ShuffleEncryptCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> Result<()> {
+        let b = Builder { cs };
+        let num_cards = self.original.len();
+
+        let is_valid = b.input(self.is_valid())?;
+        let pk = b.input_point(self.pk)?;
+        let nonce = b.input(self.nonce)?;
+        let mut decks = Vec::with_capacity(2);
+        for deck in [&self.original, &self.permuted] {
+            let mut rows = Vec::with_capacity(6);
+            for row in 0..6 {
+                rows.push(
+                    deck.iter()
+                        .map(|card| b.input(card[row]))
+                        .collect::<Result<Vec<_>>>()?,
+                );
+            }
+            let points = |offset: usize| -> Vec<Point> {
+                (0..num_cards)
+                    .map(|i| Point {
+                        x: rows[offset][i].clone(),
+                        y: rows[offset + 1][i].clone(),
+                        z: rows[offset + 2][i].clone(),
+                    })
+                    .collect()
+            };
+            decks.push((points(0), points(3)));
+        }
+        let r = self
+            .r
+            .iter()
+            .map(|r| b.witness(*r))
+            .collect::<Result<Vec<_>>>()?;
+
+        // BatchElGamalVerifyNoAlpha
+        let (original_c0, original_c1) = &decks[0];
+        let (encrypted_c0, encrypted_c1) = &decks[1];
+        let x_sum = b.sum(encrypted_c0)?;
+        let y_sum = b.sum(encrypted_c1)?;
+        let ic0_sum = b.sum(original_c0)?;
+        let ic1_sum = b.sum(original_c1)?;
+        let r_sum = r
+            .iter()
+            .fold(Signal::constant(Fq::zero()), |acc, r| acc.add(r));
+
+        let rg = b.scalar_mul(
+            &Point::constant(EdwardsAffine::generator()),
+            &r_sum,
+            NUM_BITS,
+        )?;
+        let rpk = b.scalar_mul(&pk, &r_sum, NUM_BITS)?;
+        let rg_plus_ic0 = b.add(&rg, &ic0_sum)?;
+        let rpk_plus_ic1 = b.add(&rpk, &ic1_sum)?;
+        let check_rg = b.is_equal_projective(&x_sum, &rg_plus_ic0)?;
+        let check_rpk = b.is_equal_projective(&y_sum, &rpk_plus_ic1)?;
+        b.enforce(&check_rg, &check_rpk, &is_valid)?;
+
+        // binds the nonce to the proof
+        b.mul(&nonce, &nonce)?;
+        Ok(())
+    }
+}
+
+/// `Decrypt`: `expected` is `-(sk * c0)` for the prover's secret key.
+///
+/// Public signals: `[isValid, c0[3], expected[3]]`.
+#[derive(Clone)]
+pub struct DecryptCircuit {
+    pub c0: Coords,
+    pub expected: Coords,
+    pub sk: Fq,
+}
+
+impl DecryptCircuit {
+    pub fn new(c0: &EdwardsProjective, sk: Fr) -> Self {
+        Self {
+            c0: coords(c0),
+            expected: coords(&-(*c0 * sk)),
+            sk: Fq::from_le_bytes_mod_order(&sk.into_bigint().to_bytes_le()),
+        }
+    }
+
+    pub fn blank() -> Self {
+        Self::new(&EdwardsProjective::generator(), Fr::one())
+    }
+
+    pub fn public_signals(&self) -> Vec<Fq> {
+        let mut signals = vec![Fq::one()];
+        signals.extend(self.c0);
+        signals.extend(self.expected);
+        signals
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for DecryptCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> Result<()> {
+        let b = Builder { cs };
+        let is_valid = b.input(Fq::one())?;
+        let c0 = b.input_point(self.c0)?;
+        let expected = b.input_point(self.expected)?;
+        let sk = b.witness(self.sk)?;
+
+        // ElGamalDecrypt
+        let product = b.scalar_mul(&c0, &sk, NUM_BITS)?;
+        let negated = Point {
+            x: product.x.scale(-Fq::one()),
+            ..product
+        };
+        let check = b.is_equal_projective(&expected, &negated)?;
+        b.enforce_equal(&is_valid, &check)?;
+        b.enforce_equal(&is_valid, &Signal::constant(Fq::one()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,503 @@
+//! Regenerates the zk fixtures of the poker tests:
+//!
+//! ```sh
+//! cargo run --release -p poker-fixtures
+//! ```
+//!
+//! For every fixture directory the players shuffle the deck in seat order, each
+//! one proving the shuffle with the nonce the lobby expects from them, and then
+//! prove the decryption shares of the dealt cards. Only `player_sks.json` and
+//! `card_map.json` are inputs, everything else is written by this tool.
+//!
+//! The circuits of `circuits/` are ported to arkworks in [`circuit`] and the keys
+//! come from a Groth16 setup with a fixed seed, so the verifying keys written next
+//! to the proofs only accept proofs made by this tool. The shuffle circuit depends
+//! on the deck size, the short-deck fixtures come with a shuffle key of their own. Proofs are bound to the lobby
+//! id and the player ids below; the gtests deploy the lobby at [`LOBBY_ID`], a test
+//! on a live node needs fixtures generated for the id its lobby gets.
+mod circuit;
+mod snarkjs;
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::PrimeGroup;
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsProjective, Fq, Fr};
+use ark_ff::{PrimeField, Zero};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_snark::SNARK;
+use blake2::{Blake2b512, Digest};
+use circuit::{coords, point, CardCoords, DecryptCircuit, ShuffleEncryptCircuit};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use snarkjs::{EcPoint, ProofEntry, VerifyingKeyJson};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Id the gtests deploy the poker program at
+pub const LOBBY_ID: u64 = 100;
+
+/// Domain tag of the per-hand shuffle nonce, as in the poker program
+const SHUFFLE_NONCE_DOMAIN: &[u8] = b"zk-mental-poker/shuffle-nonce/v1";
+
+/// Cards dealt to the board
+const TABLE_CARDS: usize = 5;
+
+/// Cards of the standard and of the short (6+) deck
+const STANDARD_DECK: usize = 52;
+const SHORT_DECK: usize = 36;
+
+/// Bits of the randomness of a single card, the sum over a deck stays below 2^64
+const RANDOMNESS_BITS: u32 = 50;
+
+/// Card indices (`i` for the point `i * G`) of the deck after the last shuffle of
+/// the 6-player fixtures, kept from the earlier fixtures the game tests were written for
+const SIX_PLAYERS_ORDER: [usize; STANDARD_DECK] = [
+    4, 44, 9, 26, 47, 46, 5, 29, 30, 13, 27, 39, 16, 33, 28, 32, 15, 45, 18, 34, 1, 49, 8, 25, 11,
+    12, 40, 22, 52, 37, 3, 20, 7, 38, 48, 10, 24, 43, 36, 42, 21, 2, 31, 35, 6, 41, 17, 50, 51, 19,
+    23, 14,
+];
+
+/// Same for the 3-player fixtures
+const THREE_PLAYERS_ORDER: [usize; STANDARD_DECK] = [
+    39, 19, 18, 46, 32, 20, 49, 24, 5, 11, 51, 34, 50, 7, 16, 36, 2, 37, 21, 30, 9, 10, 43, 47, 35,
+    14, 17, 31, 33, 13, 48, 45, 44, 40, 29, 3, 23, 4, 15, 28, 6, 27, 8, 41, 26, 42, 52, 12, 25, 22,
+    38, 1,
+];
+
+/// Decryption fixtures written for a directory
+#[derive(Default)]
+struct Outputs {
+    /// Verifying keys of the shuffle and decryption circuits
+    vkeys: bool,
+    /// `partial_decryptions.json` and `player_decryptions.json` of the hole cards
+    hole_cards: bool,
+    /// Files of the table card decryptions and how many of the board cards they cover
+    table_cards: &'static [(&'static str, usize)],
+}
+
+struct Profile {
+    /// Directory under `tests/`
+    dir: &'static str,
+    /// Cards in the deck, the points `1 * G..=num_cards * G`
+    num_cards: usize,
+    /// Players in seat order, which is the order they shuffle in
+    players: &'static [u64],
+    /// Rounds the shuffle proofs are made for, all of them shuffle the deck the same way
+    rounds: &'static [u64],
+    /// Final deck order, a random one if not set
+    order: Option<&'static [usize]>,
+    outputs: Outputs,
+}
+
+const PROFILES: &[Profile] = &[
+    Profile {
+        dir: "test_data_gtest/basic",
+        num_cards: STANDARD_DECK,
+        players: &[42, 43, 44, 45, 46, 47],
+        rounds: &[1, 2],
+        order: Some(&SIX_PLAYERS_ORDER),
+        outputs: Outputs {
+            vkeys: true,
+            hole_cards: true,
+            table_cards: &[
+                ("table_decryptions.json", TABLE_CARDS),
+                ("table_decryptions_after_preflop.json", TABLE_CARDS),
+            ],
+        },
+    },
+    Profile {
+        dir: "test_data_gtest/6_players_shuffle",
+        num_cards: STANDARD_DECK,
+        players: &[42, 43, 44, 45, 46, 47],
+        rounds: &[1],
+        order: None,
+        outputs: Outputs {
+            vkeys: false,
+            hole_cards: false,
+            table_cards: &[],
+        },
+    },
+    Profile {
+        dir: "test_data_gtest/6_players_new_shuffle",
+        num_cards: STANDARD_DECK,
+        players: &[42, 43, 44, 45, 46, 48],
+        rounds: &[2],
+        order: None,
+        outputs: Outputs {
+            vkeys: false,
+            hole_cards: false,
+            table_cards: &[],
+        },
+    },
+    Profile {
+        dir: "test_data",
+        num_cards: STANDARD_DECK,
+        players: &[42, 43, 44],
+        rounds: &[1],
+        order: Some(&THREE_PLAYERS_ORDER),
+        outputs: Outputs {
+            vkeys: true,
+            hole_cards: true,
+            table_cards: &[
+                ("table_decryptions_after_preflop.json", TABLE_CARDS),
+                ("table_decryptions_ater_preflop.json", 3),
+            ],
+        },
+    },
+    Profile {
+        dir: "test_data_gtest/short_deck",
+        num_cards: SHORT_DECK,
+        players: &[42, 43, 44, 45, 46, 47],
+        rounds: &[1],
+        order: None,
+        outputs: Outputs {
+            vkeys: true,
+            hole_cards: true,
+            table_cards: &[("table_decryptions.json", TABLE_CARDS)],
+        },
+    },
+];
+
+#[derive(Serialize, Deserialize)]
+struct SkEntry {
+    index: usize,
+    sk: String,
+}
+
+#[derive(Serialize)]
+struct PkEntry {
+    index: usize,
+    pk: EcPoint,
+}
+
+struct Keys {
+    shuffle: ProvingKey<Bls12_381>,
+    short_deck_shuffle: ProvingKey<Bls12_381>,
+    decrypt: ProvingKey<Bls12_381>,
+}
+
+impl Keys {
+    /// Shuffle key of the circuit for a deck of `num_cards`
+    fn shuffle_for(&self, num_cards: usize) -> &ProvingKey<Bls12_381> {
+        match num_cards {
+            STANDARD_DECK => &self.shuffle,
+            SHORT_DECK => &self.short_deck_shuffle,
+            _ => panic!("No shuffle circuit for a deck of {num_cards} cards"),
+        }
+    }
+}
+
+fn setup<C: ConstraintSynthesizer<Fq>>(circuit: C, seed: u64) -> ProvingKey<Bls12_381> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let (pk, _) = Groth16::<Bls12_381>::circuit_specific_setup(circuit, &mut rng)
+        .expect("Groth16 setup failed");
+    pk
+}
+
+fn prove<C: ConstraintSynthesizer<Fq>>(
+    pk: &ProvingKey<Bls12_381>,
+    vk: &VerifyingKey<Bls12_381>,
+    circuit: C,
+    public_signals: &[Fq],
+    rng: &mut ChaCha20Rng,
+) -> ProofEntry {
+    let proof = Groth16::<Bls12_381>::prove(pk, circuit, rng).expect("Proving failed");
+    assert!(
+        Groth16::<Bls12_381>::verify(vk, public_signals, &proof).expect("Verification failed"),
+        "Generated proof does not verify"
+    );
+    ProofEntry::new(&proof, public_signals)
+}
+
+fn actor_id(id: u64) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[12..20].copy_from_slice(&id.to_le_bytes());
+    bytes
+}
+
+/// Mirror of `verify::shuffle_nonce` of the poker program
+fn shuffle_nonce(lobby_id: u64, round: u64, player_id: u64) -> Fq {
+    let mut hasher = Blake2b512::new();
+    hasher.update(SHUFFLE_NONCE_DOMAIN);
+    hasher.update(actor_id(lobby_id));
+    hasher.update(round.to_le_bytes());
+    hasher.update(actor_id(player_id));
+    Fq::from_le_bytes_mod_order(&hasher.finalize()[..32])
+}
+
+fn card_coords(c0: &EdwardsProjective, c1: &EdwardsProjective) -> CardCoords {
+    let [x0, y0, z0] = coords(c0);
+    let [x1, y1, z1] = coords(c1);
+    [x0, y0, z0, x1, y1, z1]
+}
+
+fn card_points(card: &CardCoords) -> (EdwardsProjective, EdwardsProjective) {
+    (
+        point(&[card[0], card[1], card[2]]),
+        point(&[card[3], card[4], card[5]]),
+    )
+}
+
+fn write_json(path: PathBuf, value: &impl Serialize) {
+    let json = serde_json::to_string_pretty(value).expect("Serialization failed");
+    fs::write(&path, json + "\n").unwrap_or_else(|_| panic!("Failed to write {path:?}"));
+}
+
+fn read_sks(dir: &Path) -> Vec<Fr> {
+    let path = dir.join("player_sks.json");
+    let raw = fs::read_to_string(&path).unwrap_or_else(|_| panic!("Failed to read {path:?}"));
+    serde_json::from_str::<Vec<SkEntry>>(&raw)
+        .expect("Invalid player_sks.json")
+        .into_iter()
+        .map(|entry| Fr::from_str(&entry.sk).expect("Invalid secret key"))
+        .collect()
+}
+
+/// One shuffle of the deck: encryption of every card under `agg_pk`, then the permutation
+struct Shuffle {
+    permutation: Vec<usize>,
+    r: Vec<Fq>,
+}
+
+impl Shuffle {
+    fn apply(&self, deck: &[CardCoords], agg_pk: &EdwardsProjective) -> Vec<CardCoords> {
+        let g = EdwardsProjective::generator();
+        let encrypted: Vec<CardCoords> = deck
+            .iter()
+            .zip(&self.r)
+            .map(|(card, r)| {
+                let (c0, c1) = card_points(card);
+                let r = Fr::from(r.into_bigint().0[0]);
+                card_coords(&(g * r + c0), &(*agg_pk * r + c1))
+            })
+            .collect();
+        self.permutation.iter().map(|&i| encrypted[i]).collect()
+    }
+}
+
+fn generate(profile: &Profile, keys: &Keys, tests_dir: &Path, seed: u64) {
+    let dir = tests_dir.join(profile.dir);
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let g = EdwardsProjective::generator();
+    let sks = read_sks(&dir);
+    assert_eq!(
+        sks.len(),
+        profile.players.len(),
+        "{}: one key per player",
+        profile.dir
+    );
+    let pks: Vec<EdwardsProjective> = sks.iter().map(|sk| g * sk).collect();
+    let agg_pk: EdwardsProjective = pks.iter().sum();
+    let agg_sk: Fr = sks.iter().sum();
+
+    let pk_entries: Vec<PkEntry> = pks
+        .iter()
+        .enumerate()
+        .map(|(index, pk)| PkEntry {
+            index,
+            pk: pk.into(),
+        })
+        .collect();
+    write_json(dir.join("player_pks.json"), &pk_entries);
+
+    // shuffles of every seat, the last one brings the deck to the requested order
+    let num_cards = profile.num_cards;
+    let target: Vec<usize> = match profile.order {
+        Some(order) => order.to_vec(),
+        None => {
+            let mut order: Vec<usize> = (1..=num_cards).collect();
+            order.shuffle(&mut rng);
+            order
+        }
+    };
+    let mut cards: Vec<usize> = (1..=num_cards).collect();
+    let mut shuffles = Vec::with_capacity(profile.players.len());
+    for seat in 0..profile.players.len() {
+        let permutation: Vec<usize> = if seat + 1 == profile.players.len() {
+            target
+                .iter()
+                .map(|card| cards.iter().position(|c| c == card).expect("Unknown card"))
+                .collect()
+        } else {
+            let mut permutation: Vec<usize> = (0..num_cards).collect();
+            permutation.shuffle(&mut rng);
+            permutation
+        };
+        cards = permutation.iter().map(|&i| cards[i]).collect();
+        let r = (0..num_cards)
+            .map(|_| Fq::from(rng.gen::<u64>() >> (64 - RANDOMNESS_BITS)))
+            .collect();
+        shuffles.push(Shuffle { permutation, r });
+    }
+    assert_eq!(cards, target);
+
+    let initial: Vec<CardCoords> = (1..=num_cards as u64)
+        .map(|i| card_coords(&EdwardsProjective::zero(), &(g * Fr::from(i))))
+        .collect();
+    let mut decks = vec![initial];
+    for shuffle in &shuffles {
+        let next = shuffle.apply(decks.last().expect("Initial deck"), &agg_pk);
+        decks.push(next);
+    }
+    let deck = decks.last().expect("Shuffled deck").clone();
+
+    // the same shuffles are proven for every round, only the nonce differs
+    let shuffle_pk = keys.shuffle_for(num_cards);
+    let shuffle_vk = &shuffle_pk.vk;
+    let mut shuffle_proofs = BTreeMap::new();
+    for &round in profile.rounds {
+        let proofs: Vec<ProofEntry> = profile
+            .players
+            .iter()
+            .zip(&shuffles)
+            .enumerate()
+            .map(|(seat, (&player, shuffle))| {
+                let circuit = ShuffleEncryptCircuit {
+                    pk: coords(&agg_pk),
+                    nonce: shuffle_nonce(LOBBY_ID, round, player),
+                    original: decks[seat].clone(),
+                    permuted: decks[seat + 1].clone(),
+                    r: shuffle.r.clone(),
+                };
+                let signals = circuit.public_signals();
+                prove(shuffle_pk, shuffle_vk, circuit, &signals, &mut rng)
+            })
+            .collect();
+        shuffle_proofs.insert(round, proofs);
+    }
+    write_json(dir.join("shuffle_proofs.json"), &shuffle_proofs);
+    write_json(dir.join("encrypted_deck.json"), &snarkjs::deck(&deck));
+
+    // decryption shares, the hole cards of the seat `i` are at 2i and 2i + 1
+    let decrypt_vk = &keys.decrypt.vk;
+    let mut prove_share = |position: usize, seat: usize| {
+        let (c0, _) = card_points(&deck[position]);
+        let circuit = DecryptCircuit::new(&c0, sks[seat]);
+        let signals = circuit.public_signals();
+        let entry = prove(
+            &keys.decrypt,
+            decrypt_vk,
+            circuit.clone(),
+            &signals,
+            &mut rng,
+        );
+        (circuit, entry)
+    };
+    let hole_positions = 0..2 * profile.players.len();
+
+    let mut partial_decrypt_proofs = Vec::new();
+    for seat in 0..profile.players.len() {
+        for position in hole_positions.clone() {
+            if position / 2 != seat {
+                partial_decrypt_proofs.push(prove_share(position, seat).1);
+            }
+        }
+    }
+    write_json(
+        dir.join("partial_decrypt_proofs.json"),
+        &partial_decrypt_proofs,
+    );
+
+    for &(file, count) in profile.outputs.table_cards {
+        let table = hole_positions.end..hole_positions.end + count;
+        let entries: Vec<_> = (0..profile.players.len())
+            .map(|seat| {
+                let decryptions: Vec<_> = table
+                    .clone()
+                    .map(|position| {
+                        let (circuit, entry) = prove_share(position, seat);
+                        let (c0, c1) = card_points(&deck[position]);
+                        json!({
+                            "encryptedCard": {
+                                "c0": EcPoint::from(&c0),
+                                "c1": EcPoint::from(&c1),
+                            },
+                            "dec": EcPoint::from(&circuit.expected),
+                            "proof": entry.proof,
+                            "publicSignals": entry.public_signals,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "playerPubKey": EcPoint::from(&pks[seat]),
+                    "decryptions": decryptions,
+                })
+            })
+            .collect();
+        write_json(dir.join(file), &entries);
+    }
+
+    if profile.outputs.hole_cards {
+        let mut player_decryptions = Vec::new();
+        let mut partial_decryptions = Vec::new();
+        for seat in 0..profile.players.len() {
+            let own = [2 * seat, 2 * seat + 1];
+            let cards: Vec<_> = own
+                .iter()
+                .map(|&position| {
+                    let (_, entry) = prove_share(position, seat);
+                    let plaintext = g * Fr::from(target[position] as u64);
+                    json!({
+                        "decrypted": EcPoint::from(&plaintext),
+                        "proof": entry.proof,
+                        "publicSignals": entry.public_signals,
+                    })
+                })
+                .collect();
+            player_decryptions.push(json!({
+                "publicKey": EcPoint::from(&pks[seat]),
+                "cards": cards,
+            }));
+
+            let others_sk = agg_sk - sks[seat];
+            let partial: Vec<_> = own
+                .iter()
+                .map(|&position| {
+                    let (c0, c1) = card_points(&deck[position]);
+                    json!({
+                        "c0": EcPoint::from(&c0),
+                        "c1_partial": EcPoint::from(&(c1 - c0 * others_sk)),
+                    })
+                })
+                .collect();
+            partial_decryptions.push(json!({
+                "publicKey": EcPoint::from(&pks[seat]),
+                "cards": partial,
+            }));
+        }
+        write_json(dir.join("player_decryptions.json"), &player_decryptions);
+        write_json(dir.join("partial_decryptions.json"), &partial_decryptions);
+    }
+
+    if profile.outputs.vkeys {
+        write_json(
+            dir.join("shuffle_vkey.json"),
+            &VerifyingKeyJson::from(shuffle_vk),
+        );
+        write_json(
+            dir.join("decrypt_vkey.json"),
+            &VerifyingKeyJson::from(&keys.decrypt.vk),
+        );
+    }
+    println!("{}: done", profile.dir);
+}
+
+fn main() {
+    let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests");
+
+    println!("Groth16 setup");
+    let keys = Keys {
+        shuffle: setup(ShuffleEncryptCircuit::blank(STANDARD_DECK), 1),
+        decrypt: setup(DecryptCircuit::blank(), 2),
+        short_deck_shuffle: setup(ShuffleEncryptCircuit::blank(SHORT_DECK), 3),
+    };
+    for (seed, profile) in PROFILES.iter().enumerate() {
+        generate(profile, &keys, &tests_dir, 100 + seed as u64);
+    }
+}
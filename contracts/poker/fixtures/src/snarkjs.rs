@@ -0,0 +1,135 @@
+//! Proofs, verifying keys and points in the JSON layout of snarkjs and the zk-shuffle-proof scripts.
+use ark_bls12_381::{Bls12_381, Fq2, Fq6, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsProjective, Fq};
+use ark_ff::PrimeField;
+use ark_groth16::{Proof, VerifyingKey};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::circuit::{CardCoords, Coords};
+
+fn decimal(value: impl PrimeField) -> String {
+    value.into_bigint().to_string()
+}
+
+fn g1(point: &G1Affine) -> Value {
+    json!([decimal(point.x), decimal(point.y), "1"])
+}
+
+fn fq2(value: &Fq2) -> Value {
+    json!([decimal(value.c0), decimal(value.c1)])
+}
+
+fn g2(point: &G2Affine) -> Value {
+    json!([fq2(&point.x), fq2(&point.y), ["1", "0"]])
+}
+
+#[derive(Serialize, Clone)]
+pub struct EcPoint {
+    #[serde(rename = "X")]
+    x: String,
+    #[serde(rename = "Y")]
+    y: String,
+    #[serde(rename = "Z")]
+    z: String,
+}
+
+impl From<&Coords> for EcPoint {
+    fn from(coords: &Coords) -> Self {
+        Self {
+            x: decimal(coords[0]),
+            y: decimal(coords[1]),
+            z: decimal(coords[2]),
+        }
+    }
+}
+
+impl From<&EdwardsProjective> for EcPoint {
+    fn from(point: &EdwardsProjective) -> Self {
+        let affine = point.into_affine();
+        Self {
+            x: decimal(affine.x),
+            y: decimal(affine.y),
+            z: "1".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProofJson {
+    pi_a: Value,
+    pi_b: Value,
+    pi_c: Value,
+    protocol: &'static str,
+    curve: &'static str,
+}
+
+impl From<&Proof<Bls12_381>> for ProofJson {
+    fn from(proof: &Proof<Bls12_381>) -> Self {
+        Self {
+            pi_a: g1(&proof.a),
+            pi_b: g2(&proof.b),
+            pi_c: g1(&proof.c),
+            protocol: "groth16",
+            curve: "bls12381",
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProofEntry {
+    pub proof: ProofJson,
+    #[serde(rename = "publicSignals")]
+    pub public_signals: Vec<String>,
+}
+
+impl ProofEntry {
+    pub fn new(proof: &Proof<Bls12_381>, public_signals: &[Fq]) -> Self {
+        Self {
+            proof: proof.into(),
+            public_signals: public_signals.iter().copied().map(decimal).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct VerifyingKeyJson {
+    protocol: &'static str,
+    curve: &'static str,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: Value,
+    vk_beta_2: Value,
+    vk_gamma_2: Value,
+    vk_delta_2: Value,
+    vk_alphabeta_12: Value,
+    #[serde(rename = "IC")]
+    ic: Vec<Value>,
+}
+
+impl From<&VerifyingKey<Bls12_381>> for VerifyingKeyJson {
+    fn from(vk: &VerifyingKey<Bls12_381>) -> Self {
+        let alphabeta = Bls12_381::pairing(vk.alpha_g1, vk.beta_g2).0;
+        let fq6 = |value: &Fq6| json!([fq2(&value.c0), fq2(&value.c1), fq2(&value.c2)]);
+        Self {
+            protocol: "groth16",
+            curve: "bls12381",
+            n_public: vk.gamma_abc_g1.len() - 1,
+            vk_alpha_1: g1(&vk.alpha_g1),
+            vk_beta_2: g2(&vk.beta_g2),
+            vk_gamma_2: g2(&vk.gamma_g2),
+            vk_delta_2: g2(&vk.delta_g2),
+            vk_alphabeta_12: json!([fq6(&alphabeta.c0), fq6(&alphabeta.c1)]),
+            ic: vk.gamma_abc_g1.iter().map(g1).collect(),
+        }
+    }
+}
+
+/// Deck as six rows of coordinates, `[c0.X, c0.Y, c0.Z, c1.X, c1.Y, c1.Z]`
+pub fn deck(cards: &[CardCoords]) -> Value {
+    let rows: Vec<Vec<String>> = (0..6)
+        .map(|row| cards.iter().map(|card| decimal(card[row])).collect())
+        .collect();
+    json!(rows)
+}
@@ -39,7 +39,13 @@ async fn upload_contracts_to_testnet() -> Result<()> {
     println!("Upload zk verification contract");
     let path = "../target/wasm32-gear/release/zk_verification.opt.wasm";
     let shuffle_vkey = ZkLoaderData::load_verifying_key("tests/test_data/shuffle_vkey.json");
-    let request = ["New".encode(), (shuffle_vkey).encode()].concat();
+    let short_deck_shuffle_vkey =
+        ZkLoaderData::load_verifying_key("tests/test_data_gtest/short_deck/shuffle_vkey.json");
+    let request = [
+        "New".encode(),
+        (shuffle_vkey, Some(short_deck_shuffle_vkey)).encode(),
+    ]
+    .concat();
 
     let (message_id, zk_program_id, _hash) = api
         .upload_program_bytes(
@@ -65,6 +71,7 @@ async fn upload_contracts_to_testnet() -> Result<()> {
         CodeId::generate(code.as_ref())
     };
     let pks = ZkLoaderData::load_player_public_keys("tests/test_data/player_pks.json");
+    let sks = ZkLoaderData::load_player_secret_keys("tests/test_data/player_sks.json");
 
     // PTS
     let path = "../target/wasm32-gear/release/pts.opt.wasm";
@@ -137,12 +144,33 @@ async fn upload_contracts_to_testnet() -> Result<()> {
         big_blind: 10,
         starting_bank: 1000,
         time_per_move_ms: 15_000,
+        betting_structure: None,
+        game_mode: poker_factory_client::GameMode::Cash,
+        ante: 0,
+        allow_straddle: false,
+        max_players: 9,
+        min_players_to_start: 2,
+        game_variant: poker_factory_client::GameVariant::Holdem,
+        hi_lo: false,
     };
 
+    // the lobby checks the admin's key proof against the deployer, i.e. the factory
+    let pk_proof = prove_secret_key(
+        pk_to_point(&pks[0].1),
+        sks[0].1.scalar,
+        api.get_actor_id(),
+        factory_id,
+    );
     let request = [
         "PokerFactory".encode(),
         "CreateLobby".encode(),
-        (config.clone(), pks[0].1.clone(), None::<SignatureInfo>).encode(),
+        (
+            config.clone(),
+            pks[0].1.clone(),
+            pk_proof.clone(),
+            None::<SignatureInfo>,
+        )
+            .encode(),
     ]
     .concat();
     let gas = api
@@ -150,7 +178,7 @@ async fn upload_contracts_to_testnet() -> Result<()> {
         .await?;
     println!("GAS {gas:?}");
 
-    let message_id = send_request!(api: &api, program_id: factory_program_id, service_name: "PokerFactory", action: "CreateLobby", payload: (config, pks[0].1.clone(), None::<SignatureInfo>), value: 1_000_000_000_000);
+    let message_id = send_request!(api: &api, program_id: factory_program_id, service_name: "PokerFactory", action: "CreateLobby", payload: (config, pks[0].1.clone(), pk_proof, None::<SignatureInfo>), value: 1_000_000_000_000);
     assert!(listener.message_processed(message_id).await?.succeed());
 
     Ok(())
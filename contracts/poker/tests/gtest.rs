@@ -9,8 +9,9 @@ use gtest::WasmProgram;
 use hex_literal::hex;
 use poker_client::ZkPublicKey;
 use poker_client::{
-    traits::*, ChaumPedersenProofBytes, GameConfig, PartialDec, SessionConfig, Stage, Status,
-    VerificationVariables,
+    traits::*, BettingStructure, BlindLevel, ChaumPedersenProofBytes, GameConfig, GameMode,
+    GameVariant, LevelDuration, PartialDec, PokerError, Positions, PotShare, SessionConfig, Stage,
+    Status, TournamentConfig, VerificationVariables,
 };
 use pts_client::traits::{Pts, PtsFactory};
 use sails_rs::ActorId;
@@ -18,14 +19,18 @@ use sails_rs::{
     calls::*,
     gtest::{calls::*, System},
 };
+use std::collections::BTreeMap;
 use std::ops::Range;
 use std::path::Path;
 mod utils_gclient;
 use utils_gclient::zk_loader::{ZkLoaderData, ZkSecretKey};
-use utils_gclient::{build_player_card_disclosure, init_deck_and_card_map};
+use utils_gclient::{build_player_card_disclosure, init_deck_and_card_map, prove_secret_key};
 use zk_verification_client::traits::*;
 const USERS: [u64; 6] = [42, 43, 44, 45, 46, 47];
 
+// the poker program is deployed at a fixed id, the shuffle proofs of the test data are bound to it
+const LOBBY_ID: u64 = 100;
+
 const BUILTIN_BLS381: ActorId = ActorId::new(hex!(
     "6b6e292c382945e80bf51af2ba7fe9f458dcff81ae6075c46f9095e1bbecdc37"
 ));
@@ -34,10 +39,11 @@ use gbuiltin_bls381::{
     ark_bls12_381::{Bls12_381, G1Affine, G1Projective as G1, G2Affine},
     ark_ec::{
         pairing::{MillerLoopOutput, Pairing},
-        Group, VariableBaseMSM,
+        AffineRepr, Group, VariableBaseMSM,
     },
     ark_scale,
     ark_scale::hazmat::ArkScaleProjective,
+    ark_serialize::{CanonicalDeserialize, CanonicalSerialize},
     Request, Response,
 };
 
@@ -53,7 +59,7 @@ fn hash_prefix_agrees() {
     println!("p2 = {p2:?}");
     let p3 = p2 + g;
     println!("p3 = {p3:?}");
-    let result = hash_to_fr(&[g, p2, p3]);
+    let result = hash_to_fr(&[], &[g, p2, p3]);
     println!("result = {result:?}",);
 }
 
@@ -110,6 +116,17 @@ async fn test_basic_poker_workflow() {
     println!("Decrypt 1 card after turn");
     env.reveal_table_cards(&test_data, 4..5).await;
 
+    // river
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Check),
+        (USERS[1], poker_client::Action::Check),
+        (USERS[2], poker_client::Action::Check),
+        (USERS[3], poker_client::Action::Check),
+        (USERS[4], poker_client::Action::Check),
+        (USERS[5], poker_client::Action::Check),
+    ])
+    .await;
+
     env.print_table_cards().await;
 
     env.reveal_player_cards(&test_data).await;
@@ -152,6 +169,105 @@ async fn test_basic_poker_workflow() {
     }
 }
 
+#[tokio::test]
+async fn gtest_showdown_order_and_muck() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    env.run_actions(vec![
+        (USERS[2], poker_client::Action::Call),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 0..3).await;
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Check),
+        (USERS[1], poker_client::Action::Check),
+        (USERS[2], poker_client::Action::Check),
+        (USERS[3], poker_client::Action::Check),
+        (USERS[4], poker_client::Action::Check),
+        (USERS[5], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 3..4).await;
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Check),
+        (USERS[1], poker_client::Action::Check),
+        (USERS[2], poker_client::Action::Check),
+        (USERS[3], poker_client::Action::Check),
+        (USERS[4], poker_client::Action::Check),
+        (USERS[5], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 4..5).await;
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Check),
+        (USERS[1], poker_client::Action::Check),
+        (USERS[2], poker_client::Action::Raise { bet: 50 }),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Call),
+    ])
+    .await;
+
+    // the last aggressor shows first, then the others clockwise
+    let showdown_order = env
+        .service_client
+        .showdown_order()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    let expected_order: Vec<ActorId> = [2, 3, 4, 5, 0, 1]
+        .into_iter()
+        .map(|i| USERS[i].into())
+        .collect();
+    assert_eq!(showdown_order, expected_order);
+
+    let result = env
+        .service_client
+        .muck(None)
+        .with_args(|args| args.with_actor_id(USERS[3].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::NotYourTurn));
+
+    // the aggressor gives up the hand without showing it
+    env.service_client
+        .muck(None)
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    env.reveal_player_cards(&test_data).await;
+
+    let Status::Finished { pots } = env.verify_game_finished().await else {
+        unreachable!()
+    };
+    assert!(pots
+        .iter()
+        .all(|(_, winners, _)| !winners.contains(&USERS[2].into())));
+    let participants = env.participants().await;
+    let total: u128 = participants
+        .iter()
+        .map(|(_, participant)| participant.balance)
+        .sum();
+    assert_eq!(total, 6 * 1000);
+    let (_, mucked) = participants
+        .iter()
+        .find(|(id, _)| *id == USERS[2].into())
+        .expect("No such participant");
+    assert_eq!(mucked.balance, 1000 - 10 - 50);
+}
+
 #[tokio::test]
 async fn gtest_check_null_balance() {
     let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
@@ -230,22 +346,24 @@ async fn gtest_check_null_balance() {
         .await
         .unwrap();
 
-    if let Status::Finished { pots } = result {
-        let prize = pots[0].0;
-        let winners = pots[0].1.clone();
-        for winner in winners.iter() {
-            participants.iter().for_each(|(id, info)| {
-                if winner == id {
-                    assert_eq!(
-                        info.balance,
-                        prize / winners.len() as u128,
-                        "Wrong balance!"
-                    );
-                }
-            });
-        }
+    let Status::Finished { pots } = result else {
+        unreachable!()
+    };
+    let prize = pots[0].0;
+    let winners = pots[0].1.clone();
+    for winner in winners.iter() {
+        participants.iter().for_each(|(id, info)| {
+            if winner == id {
+                assert_eq!(
+                    info.balance,
+                    prize / winners.len() as u128,
+                    "Wrong balance!"
+                );
+            }
+        });
     }
 
+    // everyone went all-in, so only the winners are left with chips
     env.restart_game().await;
     let participants = env
         .service_client
@@ -253,7 +371,116 @@ async fn gtest_check_null_balance() {
         .recv(env.program_id)
         .await
         .unwrap();
-    assert_eq!(participants.len(), 2);
+    assert_eq!(participants.len(), winners.len());
+}
+
+#[tokio::test]
+async fn gtest_run_it_twice() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // preflop
+    env.run_actions(vec![
+        (USERS[2], poker_client::Action::Call),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 0..3).await;
+
+    // everyone checks the flop
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Check),
+        (USERS[1], poker_client::Action::Check),
+        (USERS[2], poker_client::Action::Check),
+        (USERS[3], poker_client::Action::Check),
+        (USERS[4], poker_client::Action::Check),
+        (USERS[5], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 3..4).await;
+
+    // it can't be run twice before everyone is all-in
+    let res = env
+        .service_client
+        .run_it_twice(None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::WrongStatus));
+
+    // turn
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::AllIn),
+        (USERS[1], poker_client::Action::AllIn),
+        (USERS[2], poker_client::Action::AllIn),
+        (USERS[3], poker_client::Action::AllIn),
+        (USERS[4], poker_client::Action::AllIn),
+        (USERS[5], poker_client::Action::AllIn),
+    ])
+    .await;
+    env.check_status(Status::WaitingForAllTableCardsToBeDisclosed)
+        .await;
+
+    for user in USERS {
+        env.service_client
+            .run_it_twice(None)
+            .with_args(|args| args.with_actor_id(user.into()))
+            .send_recv(env.program_id)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    let res = env
+        .service_client
+        .run_it_twice(None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::AlreadySubmitted));
+    let agreed = env
+        .service_client
+        .run_it_twice_agreed()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(agreed.len(), USERS.len());
+
+    // once the river is revealed, a second one is dealt from the deck
+    env.reveal_table_cards(&test_data, 4..5).await;
+    env.check_status(Status::WaitingForAllTableCardsToBeDisclosed)
+        .await;
+    let table_cards = env
+        .service_client
+        .encrypted_table_cards()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(table_cards.len(), 6);
+    let to_decrypt = env
+        .service_client
+        .table_cards_to_decrypt()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(to_decrypt, table_cards[5..].to_vec());
+
+    // agreeing is closed once the first board is revealed
+    let res = env
+        .service_client
+        .run_it_twice(None)
+        .with_args(|args| args.with_actor_id(USERS[1].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::WrongStatus));
 }
 
 #[tokio::test]
@@ -336,6 +563,8 @@ async fn gtest_one_player_left() {
         .await
         .unwrap();
     if let Status::Finished { pots } = result {
+        assert_eq!(pots.len(), 1);
+        assert_eq!(pots[0].2, PotShare::Whole);
         let prize = pots[0].0;
         let winners = pots[0].1.clone();
         for winner in winners.iter() {
@@ -349,6 +578,90 @@ async fn gtest_one_player_left() {
         }
     }
     println!("participants {participants:?}");
+
+    // the winner shows the bluff, the payouts stay the same
+    let decryptions = env.hole_card_decryptions(&test_data, 5).await;
+    let same_card_twice = vec![decryptions[0].clone(), decryptions[0].clone()];
+    let res = env
+        .service_client
+        .show_cards(same_card_twice, None)
+        .with_args(|args| args.with_actor_id(USERS[5].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::CardNotFound));
+
+    env.service_client
+        .show_cards(decryptions, None)
+        .with_args(|args| args.with_actor_id(USERS[5].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(env.participants().await, participants);
+}
+
+#[tokio::test]
+async fn gtest_rabbit_hunt() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // preflop
+    env.run_actions(vec![
+        (USERS[2], poker_client::Action::Call),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 0..3).await;
+
+    // everyone folds to USERS[5] on the flop
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Fold),
+        (USERS[1], poker_client::Action::Fold),
+        (USERS[2], poker_client::Action::Fold),
+        (USERS[3], poker_client::Action::Fold),
+        (USERS[4], poker_client::Action::Fold),
+    ])
+    .await;
+    env.verify_game_finished().await;
+    let participants = env.participants().await;
+
+    // the turn and the river are revealed without touching the balances
+    for (i, user) in USERS.iter().enumerate() {
+        let items = env.table_card_decryptions(&test_data, i, 3..5).await;
+        env.service_client
+            .rabbit_hunt(items, None)
+            .with_args(|args| args.with_actor_id((*user).into()))
+            .send_recv(env.program_id)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    let revealed = env
+        .service_client
+        .revealed_table_cards()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(revealed.len(), 5);
+    assert_eq!(env.participants().await, participants);
+
+    // the whole board is out
+    let items = env.table_card_decryptions(&test_data, 0, 3..5).await;
+    let res = env
+        .service_client
+        .rabbit_hunt(items, None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::WrongStatus));
 }
 
 #[tokio::test]
@@ -388,7 +701,12 @@ async fn gtest_delete_player() {
 
     env.register_players(&test_data).await;
     env.delete_player(USERS[1]).await;
-    env.register(USERS[1], test_data.pks[1].1.clone()).await;
+    env.register(
+        USERS[1],
+        test_data.pks[1].1.clone(),
+        test_data.sks[1].1.scalar,
+    )
+    .await;
     env.start_and_setup_game(&test_data).await;
 }
 
@@ -430,14 +748,20 @@ async fn gtest_check_cancel_registration_and_turn() {
     env.verify_game_finished().await;
     env.restart_game().await;
 
-    let active_participants = env
+    let positions = env
         .service_client
-        .active_participants()
+        .positions()
         .recv(env.program_id)
         .await
         .unwrap();
-    println!("active_participants: {active_participants:?}");
-    assert_eq!(active_participants.first_index, 2);
+    assert_eq!(
+        positions,
+        Some(Positions {
+            button: 0,
+            small_blind: 1,
+            big_blind: 2,
+        })
+    );
 
     // Cancel registration
     env.service_client
@@ -445,6 +769,7 @@ async fn gtest_check_cancel_registration_and_turn() {
         .with_args(|args| args.with_actor_id(USERS[1].into()))
         .send_recv(env.program_id)
         .await
+        .unwrap()
         .unwrap();
 
     let active_participants = env
@@ -454,7 +779,50 @@ async fn gtest_check_cancel_registration_and_turn() {
         .await
         .unwrap();
     println!("active_participants: {active_participants:?}");
-    assert_eq!(active_participants.first_index, 1);
+    assert!(!active_participants.active_ids.contains(&USERS[1].into()));
+    let seats = env
+        .service_client
+        .seats()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    let expected_seats: Vec<(u8, ActorId)> = [0, 2, 3, 4, 5]
+        .into_iter()
+        .map(|seat| (seat, USERS[seat as usize].into()))
+        .collect();
+    assert_eq!(seats, expected_seats);
+
+    // the button moves to the empty seat, so the last small blind doesn't skip the big one
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let positions = env
+        .service_client
+        .positions()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        positions,
+        Some(Positions {
+            button: 1,
+            small_blind: 2,
+            big_blind: 3,
+        })
+    );
+    let mut invested = env
+        .service_client
+        .already_invested_in_the_circle()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    invested.sort();
+    let mut expected_invested = vec![(USERS[2].into(), 5), (USERS[3].into(), 10)];
+    expected_invested.sort();
+    assert_eq!(invested, expected_invested);
 }
 
 #[tokio::test]
@@ -475,7 +843,9 @@ async fn gtest_check_waiting_participants() {
         .mint_to(new_player_id, 1_000_000_000_000_000);
     let new_test_data = TestData::load_from_profile(TestDataProfile::SixPlayersNew);
     let new_player_pk = new_test_data.pks[5].1.clone();
-    env.register(new_player_id, new_player_pk).await;
+    let new_player_sk = new_test_data.sks[5].1.scalar;
+    env.register(new_player_id, new_player_pk, new_player_sk)
+        .await;
     // check length of the waiting participants state (1)
     let waiting_participants = env.waiting_participants().await;
     assert_eq!(waiting_participants.len(), 1);
@@ -529,7 +899,9 @@ async fn gtest_check_cancel_registration_waiting_participants() {
         .mint_to(new_player_id, 1_000_000_000_000_000);
     let new_test_data = TestData::load_from_profile(TestDataProfile::SixPlayersNew);
     let new_player_pk = new_test_data.pks[5].1.clone();
-    env.register(new_player_id, new_player_pk).await;
+    let new_player_sk = new_test_data.sks[5].1.scalar;
+    env.register(new_player_id, new_player_pk, new_player_sk)
+        .await;
 
     // check length of the waiting participants state (1)
     let waiting_participants = env.waiting_participants().await;
@@ -549,6 +921,7 @@ async fn gtest_check_cancel_registration_waiting_participants() {
         .with_args(|args| args.with_actor_id(new_player_id.into()))
         .send_recv(env.program_id)
         .await
+        .unwrap()
         .unwrap();
 
     // check length of the waiting participants state (0)
@@ -574,7 +947,9 @@ async fn gtest_agg_key_calc() {
         .mint_to(new_player_id, 1_000_000_000_000_000);
     let new_test_data = TestData::load_from_profile(TestDataProfile::SixPlayersNew);
     let new_player_pk = new_test_data.pks[5].1.clone();
-    env.register(new_player_id, new_player_pk).await;
+    let new_player_sk = new_test_data.sks[5].1.scalar;
+    env.register(new_player_id, new_player_pk, new_player_sk)
+        .await;
 
     // check length of the waiting participants state (1)
     let waiting_participants = env.waiting_participants().await;
@@ -594,23 +969,1206 @@ async fn gtest_agg_key_calc() {
         .with_args(|args| args.with_actor_id(new_player_id.into()))
         .send_recv(env.program_id)
         .await
+        .unwrap()
         .unwrap();
 
     // check length of the waiting participants state (0)
     let waiting_participants = env.waiting_participants().await;
     assert_eq!(waiting_participants.len(), 0);
 }
-struct TestEnvironment {
-    remoting: GTestRemoting,
-    pts_id: ActorId,
+
+#[tokio::test]
+async fn gtest_register_rejects_rogue_key() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    let player: ActorId = USERS[1].into();
+    env.pts_service_client
+        .get_accural()
+        .with_args(|args| args.with_actor_id(player))
+        .send_recv(env.pts_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // rogue key: pk_evil - pk_admin, the attacker only knows sk_evil
+    let sk_evil = Fr::rand(&mut rand::thread_rng());
+    let rogue_pk = G::generator() * sk_evil - deserialize_public_key(&test_data.pks[0].1);
+    let pk_proof = prove_secret_key(rogue_pk, sk_evil, player, env.program_id);
+    let result = env
+        .service_client
+        .register(
+            "Rogue".to_string(),
+            serialize_public_key(&rogue_pk),
+            pk_proof,
+            None,
+            None,
+        )
+        .with_args(|args| args.with_actor_id(player))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::InvalidSecretKeyProof));
+
+    // a valid proof made for another player can't be replayed
+    let pk_proof = prove_secret_key(
+        deserialize_public_key(&test_data.pks[2].1),
+        test_data.sks[2].1.scalar,
+        USERS[2].into(),
+        env.program_id,
+    );
+    let result = env
+        .service_client
+        .register(
+            "Replay".to_string(),
+            test_data.pks[2].1.clone(),
+            pk_proof,
+            None,
+            None,
+        )
+        .with_args(|args| args.with_actor_id(player))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::InvalidSecretKeyProof));
+
+    // a key with a small-order component (the order-2 point (0, -1)): every proof with
+    // an even challenge satisfies the Schnorr equation, so it must fail the subgroup check
+    let sk = Fr::rand(&mut rand::thread_rng());
+    let order_two = G::new_unchecked(
+        Fq::from(0u64),
+        -Fq::from(1u64),
+        Fq::from(0u64),
+        Fq::from(1u64),
+    );
+    let small_pk = G::generator() * sk + order_two;
+    for _ in 0..4 {
+        let pk_proof = prove_secret_key(small_pk, sk, player, env.program_id);
+        let result = env
+            .service_client
+            .register(
+                "Small order".to_string(),
+                serialize_public_key(&small_pk),
+                pk_proof,
+                None,
+                None,
+            )
+            .with_args(|args| args.with_actor_id(player))
+            .send_recv(env.program_id)
+            .await;
+        assert_eq!(result.unwrap(), Err(PokerError::InvalidSecretKeyProof));
+    }
+
+    assert_eq!(env.participants().await.len(), 1);
+}
+
+#[tokio::test]
+async fn gtest_shuffle_out_of_turn() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the second seat can't shuffle before the first one
+    let result = env
+        .service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][1].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[1].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::NotYourTurn));
+
+    env.service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][0].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // and nobody shuffles twice
+    let result = env
+        .service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][0].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::NotYourTurn));
+
+    env.check_status(Status::WaitingShuffle {
+        turn: USERS[1].into(),
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn gtest_shuffle_replayed_from_another_seat() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    env.service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][0].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the first seat's proof carries its own nonce, the second seat can't submit it as its shuffle
+    let result = env
+        .service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][0].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[1].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::ShuffleNonceMismatch));
+
+    env.check_status(Status::WaitingShuffle {
+        turn: USERS[1].into(),
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn gtest_sequential_shuffle_by_every_seat() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let proofs = &test_data.shuffle_proofs[&1];
+    for (i, user) in test_data.players.iter().enumerate() {
+        env.check_status(Status::WaitingShuffle {
+            turn: (*user).into(),
+        })
+        .await;
+
+        // the next seat has to wait for this one
+        if let Some(next) = test_data.players.get(i + 1) {
+            let result = env
+                .service_client
+                .submit_shuffle(proofs[i + 1].clone(), None)
+                .with_args(|args| args.with_actor_id((*next).into()))
+                .send_recv(env.program_id)
+                .await;
+            assert_eq!(result.unwrap(), Err(PokerError::NotYourTurn));
+        }
+        // and can't resubmit the proof of the previous seat
+        if i > 0 {
+            let result = env
+                .service_client
+                .submit_shuffle(proofs[i - 1].clone(), None)
+                .with_args(|args| args.with_actor_id((*user).into()))
+                .send_recv(env.program_id)
+                .await;
+            assert_eq!(result.unwrap(), Err(PokerError::ShuffleNonceMismatch));
+        }
+
+        env.service_client
+            .submit_shuffle(proofs[i].clone(), None)
+            .with_args(|args| args.with_actor_id((*user).into()))
+            .send_recv(env.program_id)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    // the cards are dealt from the deck of the last seat
+    env.check_status(Status::WaitingPartialDecryptionsForPlayersCards)
+        .await;
+    let table_cards = env
+        .service_client
+        .encrypted_table_cards()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(table_cards.len(), 5);
+    for card in table_cards.iter() {
+        find_card_index(&test_data.encrypted_deck, &card.c0);
+    }
+}
+
+#[tokio::test]
+async fn gtest_claim_timeout_on_stalled_shuffle() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    env.service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][0].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the second seat has the whole phase to shuffle
+    let result = env
+        .service_client
+        .claim_timeout()
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::DeadlineNotReached));
+
+    for _ in 0..15 {
+        env.remoting.system().run_next_block();
+    }
+    let points_before = env
+        .pts_service_client
+        .get_balance(USERS[1].into())
+        .recv(env.pts_id)
+        .await
+        .unwrap()
+        .unwrap();
+    env.service_client
+        .claim_timeout()
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    env.check_status(Status::Registration).await;
+
+    // the stalled player leaves the table, their big blind stays with the others
+    // and the rest of their balance goes back to them
+    let participants = env.participants().await;
+    assert_eq!(participants.len(), USERS.len() - 1);
+    assert!(participants.iter().all(|(id, _)| *id != USERS[1].into()));
+    assert!(participants.iter().all(|(_, p)| p.balance == 1002));
+    let points_after = env
+        .pts_service_client
+        .get_balance(USERS[1].into())
+        .recv(env.pts_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(points_after - points_before, 990);
+}
+
+#[tokio::test]
+async fn gtest_turn_timeout_auto_fold() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // USERS[2] has to call the big blind and doesn't act, so the lobby folds for them
+    for _ in 0..15 {
+        env.remoting.system().run_next_block();
+    }
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.turn, USERS[3].into());
+    let active_participants = env
+        .service_client
+        .active_participants()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert!(!active_participants.active_ids.contains(&USERS[2].into()));
+
+    env.run_actions(vec![(USERS[3], poker_client::Action::Call)])
+        .await;
+
+    // nobody else can time out USERS[4] before the time is up
+    let result = env
+        .service_client
+        .turn_timeout(0)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::DeadlineNotReached));
+
+    // the timer folds USERS[4], but there's no gas left in it to schedule the timer of USERS[5]
+    for _ in 0..15 {
+        env.remoting.system().run_next_block();
+    }
+    let active_participants = env
+        .service_client
+        .active_participants()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert!(!active_participants.active_ids.contains(&USERS[4].into()));
+    for _ in 0..15 {
+        env.remoting.system().run_next_block();
+    }
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.turn, USERS[5].into());
+
+    // so anyone can time them out once the time is up
+    env.service_client
+        .turn_timeout(0)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.turn, USERS[0].into());
+}
+
+#[tokio::test]
+async fn gtest_min_raise_and_short_all_in() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // the opening raise has to be at least the big blind
+    let result = env
+        .service_client
+        .turn(poker_client::Action::Raise { bet: 15 }, None)
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::RaiseTooLow));
+
+    // raise to 600, the next raise has to be at least 590 more
+    env.run_actions(vec![(USERS[2], poker_client::Action::Raise { bet: 600 })])
+        .await;
+    let result = env
+        .service_client
+        .turn(poker_client::Action::Raise { bet: 900 }, None)
+        .with_args(|args| args.with_actor_id(USERS[3].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::RaiseTooLow));
+
+    // all-in for 1000 is only 400 more, so USERS[2] can't raise again
+    env.run_actions(vec![
+        (USERS[3], poker_client::Action::AllIn),
+        (USERS[4], poker_client::Action::Fold),
+        (USERS[5], poker_client::Action::Fold),
+        (USERS[0], poker_client::Action::Fold),
+        (USERS[1], poker_client::Action::Fold),
+    ])
+    .await;
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.current_bet, 1000);
+    assert_eq!(betting.last_raise, 590);
+    assert_eq!(betting.raise_closed, vec![USERS[2].into()]);
+
+    let result = env
+        .service_client
+        .turn(poker_client::Action::Raise { bet: 100 }, None)
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::RaiseNotAllowed));
+
+    // but can still match it
+    env.run_actions(vec![(USERS[2], poker_client::Action::AllIn)])
+        .await;
+    env.check_status(Status::WaitingForAllTableCardsToBeDisclosed)
+        .await;
+}
+
+#[tokio::test]
+async fn gtest_pot_limit_caps_raises() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.betting_structure = Some(BettingStructure::PotLimit)
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // pot of 15 plus the call of 10: raise to at most 10 + 25
+    let result = env
+        .service_client
+        .turn(poker_client::Action::Raise { bet: 36 }, None)
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::RaiseTooHigh));
+    env.run_actions(vec![(USERS[2], poker_client::Action::Raise { bet: 35 })])
+        .await;
+
+    // pot of 50 plus the call of 35: raise to at most 35 + 85
+    for action in [
+        poker_client::Action::Raise { bet: 121 },
+        poker_client::Action::AllIn,
+    ] {
+        let result = env
+            .service_client
+            .turn(action, None)
+            .with_args(|args| args.with_actor_id(USERS[3].into()))
+            .send_recv(env.program_id)
+            .await;
+        assert_eq!(result.unwrap(), Err(PokerError::RaiseTooHigh));
+    }
+    env.run_actions(vec![(USERS[3], poker_client::Action::Raise { bet: 120 })])
+        .await;
+
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.current_bet, 120);
+    assert_eq!(betting.turn, USERS[4].into());
+}
+
+#[tokio::test]
+async fn gtest_fixed_limit_bet_sizes_and_raise_cap() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.betting_structure = Some(BettingStructure::FixedLimit {
+                small_bet: 10,
+                big_bet: 20,
+                max_raises: 2,
+            })
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // preflop raises are exactly the small bet
+    for (bet, error) in [
+        (15, PokerError::RaiseTooLow),
+        (30, PokerError::RaiseTooHigh),
+    ] {
+        let result = env
+            .service_client
+            .turn(poker_client::Action::Raise { bet }, None)
+            .with_args(|args| args.with_actor_id(USERS[2].into()))
+            .send_recv(env.program_id)
+            .await;
+        assert_eq!(result.unwrap(), Err(error));
+    }
+    env.run_actions(vec![
+        (USERS[2], poker_client::Action::Raise { bet: 20 }),
+        (USERS[3], poker_client::Action::Raise { bet: 30 }),
+    ])
+    .await;
+
+    // two raises after the big blind close the raising
+    let result = env
+        .service_client
+        .turn(poker_client::Action::Raise { bet: 40 }, None)
+        .with_args(|args| args.with_actor_id(USERS[4].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::RaiseCapReached));
+    env.run_actions(vec![(USERS[4], poker_client::Action::Call)])
+        .await;
+
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.current_bet, 30);
+    assert_eq!(betting.raises, 2);
+}
+
+#[tokio::test]
+async fn gtest_sit_and_go_eliminates_and_deals_next_hand() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.game_mode = GameMode::SitAndGo(TournamentConfig {
+                blind_levels: vec![
+                    BlindLevel {
+                        small_blind: 5,
+                        big_blind: 10,
+                    },
+                    BlindLevel {
+                        small_blind: 10,
+                        big_blind: 20,
+                    },
+                ],
+                level_duration: LevelDuration::Hands(1),
+                payouts: vec![70, 30],
+            })
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    // the chips can't be taken away once the tournament has started
+    let result = env
+        .service_client
+        .cancel_registration(None)
+        .with_args(|args| args.with_actor_id(USERS[1].into()))
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::WrongStatus));
+    let result = env
+        .service_client
+        .restart_game(None)
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::WrongStatus));
+
+    env.run_actions(vec![
+        (USERS[2], poker_client::Action::Call),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Check),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 0..3).await;
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::Raise { bet: 50 }),
+        (USERS[1], poker_client::Action::Raise { bet: 100 }),
+        (USERS[2], poker_client::Action::Call),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 3..4).await;
+    env.run_actions(vec![
+        (USERS[0], poker_client::Action::AllIn),
+        (USERS[1], poker_client::Action::AllIn),
+        (USERS[2], poker_client::Action::AllIn),
+        (USERS[3], poker_client::Action::AllIn),
+        (USERS[4], poker_client::Action::AllIn),
+        (USERS[5], poker_client::Action::Fold),
+    ])
+    .await;
+    env.reveal_table_cards(&test_data, 4..5).await;
+    env.reveal_player_cards(&test_data).await;
+
+    // the busted players are knocked out, the winner plays the player who folded
+    // and the next hand is dealt at the second level
+    let participants = env.participants().await;
+    assert_eq!(participants.len(), 2);
+    let tournament = env
+        .service_client
+        .tournament()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No tournament");
+    assert_eq!(tournament.entrants, 6);
+    assert_eq!(tournament.prize_pool, 6000);
+    assert_eq!(tournament.eliminated.len(), 4);
+    assert_eq!(tournament.level, 1);
+    assert!(env
+        .service_client
+        .standings()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(env.round().await, 2);
+    env.check_status(Status::WaitingShuffle {
+        turn: participants[0].0,
+    })
+    .await;
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.current_bet, 20);
+
+    // heads-up the button posts the small blind and acts first preflop
+    let positions = env
+        .service_client
+        .positions()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No positions");
+    assert_eq!(positions.button, positions.small_blind);
+    let seats = env
+        .service_client
+        .seats()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    let (_, button) = seats
+        .into_iter()
+        .find(|(seat, _)| *seat == positions.button)
+        .expect("The button seat is empty");
+    assert_eq!(betting.turn, button);
+    let invested = env
+        .service_client
+        .already_invested_in_the_circle()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert!(invested.contains(&(button, 10)));
+}
+
+#[tokio::test]
+async fn gtest_sit_and_go_pays_no_more_places_than_players() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.game_mode = GameMode::SitAndGo(TournamentConfig {
+                blind_levels: vec![BlindLevel {
+                    small_blind: 5,
+                    big_blind: 10,
+                }],
+                level_duration: LevelDuration::Hands(1),
+                payouts: vec![40, 20, 10, 10, 10, 5, 5],
+            })
+        })
+        .await;
+    env.register_players(&test_data).await;
+
+    // seven paid places for six players
+    let result = env
+        .service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::NotEnoughParticipants));
+    env.check_status(Status::Registration).await;
+}
+
+#[tokio::test]
+async fn gtest_ante_and_straddle() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.ante = 5;
+            config.allow_straddle = true;
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // antes go to the pot, but only the blinds count as bets of the round
+    let mut bank = env
+        .service_client
+        .betting_bank()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    bank.sort();
+    let mut expected_bank: Vec<(ActorId, u128)> = USERS
+        .iter()
+        .enumerate()
+        .map(|(i, user)| ((*user).into(), [10, 15].get(i).copied().unwrap_or(5)))
+        .collect();
+    expected_bank.sort();
+    assert_eq!(bank, expected_bank);
+    let mut invested = env
+        .service_client
+        .already_invested_in_the_circle()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    invested.sort();
+    let mut expected_invested = vec![(USERS[0].into(), 5), (USERS[1].into(), 10)];
+    expected_invested.sort();
+    assert_eq!(invested, expected_invested);
+
+    // only the player after the big blind may straddle, and only once
+    for user in [USERS[1], USERS[3]] {
+        let result = env
+            .service_client
+            .post_straddle(None)
+            .with_args(|args| args.with_actor_id(user.into()))
+            .send_recv(env.program_id)
+            .await;
+        assert_eq!(result.unwrap(), Err(PokerError::NotYourTurn));
+    }
+    env.service_client
+        .post_straddle(None)
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let betting = env
+        .service_client
+        .betting()
+        .recv(env.program_id)
+        .await
+        .unwrap()
+        .expect("No betting");
+    assert_eq!(betting.current_bet, 20);
+    assert_eq!(betting.turn, USERS[3].into());
+
+    env.setup_game(&test_data).await;
+
+    // the straddler acts last
+    env.run_actions(vec![
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Call),
+        (USERS[2], poker_client::Action::Check),
+    ])
+    .await;
+    env.check_status(Status::Play {
+        stage: Stage::WaitingTableCardsAfterPreFlop,
+    })
+    .await;
+
+    let participants = env.participants().await;
+    assert!(participants
+        .iter()
+        .all(|(_, participant)| participant.balance == 1000 - 5 - 20));
+}
+
+#[tokio::test]
+async fn gtest_heads_up_blinds_and_turn_order() {
+    let (mut env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    env.register(
+        USERS[1],
+        test_data.pks[1].1.clone(),
+        test_data.sks[1].1.scalar,
+    )
+    .await;
+
+    for (button, big_blind) in [(0, 1), (1, 0)] {
+        env.service_client
+            .start_game(None)
+            .send_recv(env.program_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // the button posts the small blind and acts first preflop
+        let positions = env
+            .service_client
+            .positions()
+            .recv(env.program_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            positions,
+            Some(Positions {
+                button,
+                small_blind: button,
+                big_blind,
+            })
+        );
+        let (button, big_blind) = (USERS[button as usize], USERS[big_blind as usize]);
+        let betting = env
+            .service_client
+            .betting()
+            .recv(env.program_id)
+            .await
+            .unwrap()
+            .expect("No betting");
+        assert_eq!(betting.turn, button.into());
+        let mut invested = env
+            .service_client
+            .already_invested_in_the_circle()
+            .recv(env.program_id)
+            .await
+            .unwrap();
+        invested.sort();
+        let mut expected_invested = vec![(button.into(), 5), (big_blind.into(), 10)];
+        expected_invested.sort();
+        assert_eq!(invested, expected_invested);
+
+        // the bets are refunded, and the button moves on with the next hand
+        env.restart_game().await;
+    }
+}
+
+#[tokio::test]
+async fn gtest_table_size() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.max_players = 6;
+            config.min_players_to_start = 3;
+        })
+        .await;
+    env.register(
+        USERS[1],
+        test_data.pks[1].1.clone(),
+        test_data.sks[1].1.scalar,
+    )
+    .await;
+
+    let result = env
+        .service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await;
+    assert_eq!(result.unwrap(), Err(PokerError::NotEnoughParticipants));
+
+    env.pts_service_client
+        .get_accural()
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.pts_id)
+        .await
+        .unwrap()
+        .unwrap();
+    for (seat, error) in [(6, PokerError::InvalidSeat), (1, PokerError::SeatTaken)] {
+        let pk_proof = prove_secret_key(
+            deserialize_public_key(&test_data.pks[2].1),
+            test_data.sks[2].1.scalar,
+            USERS[2].into(),
+            env.program_id,
+        );
+        let result = env
+            .service_client
+            .register(
+                "Player".to_string(),
+                test_data.pks[2].1.clone(),
+                pk_proof,
+                Some(seat),
+                None,
+            )
+            .with_args(|args| args.with_actor_id(USERS[2].into()))
+            .send_recv(env.program_id)
+            .await;
+        assert_eq!(result.unwrap(), Err(error));
+    }
+    let pk_proof = prove_secret_key(
+        deserialize_public_key(&test_data.pks[2].1),
+        test_data.sks[2].1.scalar,
+        USERS[2].into(),
+        env.program_id,
+    );
+    env.service_client
+        .register(
+            "Player".to_string(),
+            test_data.pks[2].1.clone(),
+            pk_proof,
+            Some(5),
+            None,
+        )
+        .with_args(|args| args.with_actor_id(USERS[2].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let seats = env
+        .service_client
+        .seats()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        seats,
+        vec![
+            (0, USERS[0].into()),
+            (1, USERS[1].into()),
+            (5, USERS[2].into())
+        ]
+    );
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn gtest_omaha_deals_four_hole_cards() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.game_variant = GameVariant::Omaha;
+            config.max_players = 11;
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    env.shuffle_deck(&test_data).await;
+
+    // the other five players have four hole cards each, the count is checked before the proofs
+    let partial_decs = get_decs_from_proofs(&test_data.decrypt_proofs.clone());
+    let items: Vec<PartialDec> = partial_decs[..10]
+        .iter()
+        .map(|(c0, delta_c0)| PartialDec {
+            c0: c0.clone(),
+            delta_c0: delta_c0.clone(),
+            proof: ChaumPedersenProofBytes {
+                a: c0.clone(),
+                b: delta_c0.clone(),
+                z: vec![0; 32],
+            },
+        })
+        .collect();
+    let res = env
+        .service_client
+        .submit_partial_decryptions(items, None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::WrongNumberOfDecryptions));
+}
+
+#[tokio::test]
+async fn gtest_five_card_draw_deals_five_cards() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.game_variant = GameVariant::FiveCardDraw { max_discards: 3 };
+            // 52 cards are enough for six hands of five plus three replacements
+            config.max_players = 6;
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    env.shuffle_deck(&test_data).await;
+
+    // there's no board in a draw game
+    let table_cards = env
+        .service_client
+        .encrypted_table_cards()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert!(table_cards.is_empty());
+
+    // nobody draws before the first betting round
+    let res = env
+        .service_client
+        .draw(vec![0], None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::WrongStatus));
+
+    // the other five players have five cards each
+    let partial_decs = get_decs_from_proofs(&test_data.decrypt_proofs.clone());
+    let items: Vec<PartialDec> = partial_decs[..10]
+        .iter()
+        .map(|(c0, delta_c0)| PartialDec {
+            c0: c0.clone(),
+            delta_c0: delta_c0.clone(),
+            proof: ChaumPedersenProofBytes {
+                a: c0.clone(),
+                b: delta_c0.clone(),
+                z: vec![0; 32],
+            },
+        })
+        .collect();
+    let res = env
+        .service_client
+        .submit_partial_decryptions(items, None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::WrongNumberOfDecryptions));
+}
+
+#[tokio::test]
+async fn gtest_short_deck_rejects_standard_deck_shuffle() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::Basic, |config| {
+            config.game_variant = GameVariant::ShortDeck;
+        })
+        .await;
+    env.register_players(&test_data).await;
+    env.service_client
+        .start_game(None)
+        .send_recv(env.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the proof shuffles all 52 cards, a short-deck shuffle has 36 in it
+    let res = env
+        .service_client
+        .submit_shuffle(test_data.shuffle_proofs[&1][0].clone(), None)
+        .with_args(|args| args.with_actor_id(USERS[0].into()))
+        .send_recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(res, Err(PokerError::InvalidPublicInput));
+}
+
+#[tokio::test]
+async fn gtest_short_deck_hand_is_played_to_showdown() {
+    let (mut env, test_data) =
+        TestEnvironment::setup_with_config(TestDataProfile::ShortDeck, |config| {
+            config.game_variant = GameVariant::ShortDeck;
+        })
+        .await;
+
+    // the 36-card shuffles are verified with the short-deck key
+    env.register_players(&test_data).await;
+    env.start_and_setup_game(&test_data).await;
+
+    env.run_actions(vec![
+        (USERS[2], poker_client::Action::Call),
+        (USERS[3], poker_client::Action::Call),
+        (USERS[4], poker_client::Action::Call),
+        (USERS[5], poker_client::Action::Call),
+        (USERS[0], poker_client::Action::Call),
+        (USERS[1], poker_client::Action::Check),
+    ])
+    .await;
+
+    let checks: Vec<_> = USERS
+        .iter()
+        .map(|&user| (user, poker_client::Action::Check))
+        .collect();
+    env.reveal_table_cards(&test_data, 0..3).await;
+    env.run_actions(checks.clone()).await;
+    env.reveal_table_cards(&test_data, 3..4).await;
+    env.run_actions(checks.clone()).await;
+    env.reveal_table_cards(&test_data, 4..5).await;
+    env.run_actions(checks).await;
+
+    let table_cards = env
+        .service_client
+        .revealed_table_cards()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    assert_eq!(table_cards.len(), 5);
+    assert!(table_cards.iter().all(|card| (6..=14).contains(&card.value)));
+
+    env.reveal_player_cards(&test_data).await;
+    env.verify_game_finished().await;
+
+    let participants = env
+        .service_client
+        .participants()
+        .recv(env.program_id)
+        .await
+        .unwrap();
+    let total: u128 = participants.iter().map(|(_, info)| info.balance).sum();
+    assert_eq!(total, 1000 * USERS.len() as u128);
+}
+
+#[tokio::test]
+async fn gtest_verify_shuffle_picks_the_key_of_every_instance() {
+    let (env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    let short_deck_data = TestData::load_from_profile(TestDataProfile::ShortDeck);
+    let mut zk_service_client = zk_verification_client::ZkVerification::new(env.remoting.clone());
+
+    let short_deck_instance = to_zk_instance(&short_deck_data.shuffle_proofs[&1][0]);
+    zk_service_client
+        .verify_shuffle(vec![short_deck_instance.clone()])
+        .send_recv(env.zk_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the short-deck shuffle passes with its own key and the standard one behind
+    // it is decoded with the standard key, not rejected for its input count
+    let mut standard_instance = to_zk_instance(&test_data.shuffle_proofs[&1][0]);
+    standard_instance.public_input[0].truncate(1);
+    let result = zk_service_client
+        .verify_shuffle(vec![short_deck_instance, standard_instance])
+        .send_recv(env.zk_program_id)
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        Err(zk_verification_client::ZkVerificationError::InvalidPublicInput)
+    );
+}
+
+#[tokio::test]
+async fn gtest_batch_verification_rejects_cancelling_proofs() {
+    let (env, test_data) = TestEnvironment::setup(TestDataProfile::Basic).await;
+    let mut zk_service_client = zk_verification_client::ZkVerification::new(env.remoting.clone());
+
+    let valid_batch: Vec<_> = test_data.shuffle_proofs[&1][..2]
+        .iter()
+        .map(to_zk_instance)
+        .collect();
+    zk_service_client
+        .verify_shuffle(valid_batch.clone())
+        .send_recv(env.zk_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Shift C of the first proof by D and C of the second one by -D:
+    // both proofs are invalid, but the terms e(C, -delta) of the unweighted
+    // batch product cancel each other out
+    let shift = G1Affine::generator();
+    let mut crafted_batch = valid_batch;
+    crafted_batch[0].proof_bytes.c = shift_g1(&crafted_batch[0].proof_bytes.c, shift);
+    crafted_batch[1].proof_bytes.c = shift_g1(&crafted_batch[1].proof_bytes.c, -shift);
+
+    let result = zk_service_client
+        .verify_shuffle(crafted_batch)
+        .send_recv(env.zk_program_id)
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        Err(zk_verification_client::ZkVerificationError::VerificationFailed)
+    );
+}
+
+struct TestEnvironment {
+    remoting: GTestRemoting,
+    pts_id: ActorId,
     program_id: ActorId,
+    zk_program_id: ActorId,
     service_client: poker_client::Poker<GTestRemoting>,
     pts_service_client: pts_client::Pts<GTestRemoting>,
 }
 struct TestData {
     pks: Vec<(usize, poker_client::ZkPublicKey)>,
     sks: Vec<(usize, ZkSecretKey)>,
-    shuffle_proofs: Vec<poker_client::VerificationVariables>,
+    // player ids in seat order
+    players: Vec<u64>,
+    // shuffle proofs of the seats by round
+    shuffle_proofs: BTreeMap<u64, Vec<poker_client::VerificationVariables>>,
     encrypted_deck: Vec<poker_client::EncryptedCard>,
     decrypt_proofs: Vec<poker_client::VerificationVariables>,
     table_cards_proofs: Option<
@@ -634,14 +2192,21 @@ pub enum TestDataProfile {
     Basic,
     SixPlayers,
     SixPlayersNew,
+    ShortDeck,
 }
 
 impl TestData {
     pub fn load_from_profile(profile: TestDataProfile) -> Self {
-        let prefix = match profile {
-            TestDataProfile::Basic => "tests/test_data_gtest/basic",
-            TestDataProfile::SixPlayers => "tests/test_data_gtest/6_players_shuffle",
-            TestDataProfile::SixPlayersNew => "tests/test_data_gtest/6_players_new_shuffle",
+        let (prefix, players) = match profile {
+            TestDataProfile::Basic => ("tests/test_data_gtest/basic", USERS.to_vec()),
+            TestDataProfile::SixPlayers => {
+                ("tests/test_data_gtest/6_players_shuffle", USERS.to_vec())
+            }
+            TestDataProfile::SixPlayersNew => (
+                "tests/test_data_gtest/6_players_new_shuffle",
+                vec![42, 43, 44, 45, 46, 48],
+            ),
+            TestDataProfile::ShortDeck => ("tests/test_data_gtest/short_deck", USERS.to_vec()),
         };
 
         println!("prefix {prefix:?}");
@@ -663,6 +2228,7 @@ impl TestData {
         Self {
             pks: ZkLoaderData::load_player_public_keys(&format!("{prefix}/player_pks.json")),
             sks: ZkLoaderData::load_player_secret_keys(&format!("{prefix}/player_sks.json")),
+            players,
             shuffle_proofs: ZkLoaderData::load_shuffle_proofs(&format!(
                 "{prefix}/shuffle_proofs.json"
             )),
@@ -680,6 +2246,13 @@ impl TestData {
 
 impl TestEnvironment {
     async fn setup(data: TestDataProfile) -> (Self, TestData) {
+        Self::setup_with_config(data, |_| {}).await
+    }
+
+    async fn setup_with_config(
+        data: TestDataProfile,
+        configure: impl FnOnce(&mut GameConfig),
+    ) -> (Self, TestData) {
         let system = System::new();
         system.init_logger();
 
@@ -703,8 +2276,19 @@ impl TestEnvironment {
         // Setup PTS system
         let pts_id = Self::setup_pts_system(&remoting).await;
 
+        // Setup zk verification program
+        let zk_program_id = Self::setup_zk_verification(&remoting).await;
+
         // Setup poker program
-        let program_id = Self::setup_poker_program(&remoting, pts_id, &test_data.pks[0].1).await;
+        let program_id = Self::setup_poker_program(
+            &remoting,
+            pts_id,
+            zk_program_id,
+            &test_data.pks[0].1,
+            test_data.sks[0].1.scalar,
+            configure,
+        )
+        .await;
 
         // Create service clients
         let service_client = poker_client::Poker::new(remoting.clone());
@@ -715,12 +2299,14 @@ impl TestEnvironment {
             .add_admin(program_id)
             .send_recv(pts_id)
             .await
+            .unwrap()
             .unwrap();
 
         let env = TestEnvironment {
             remoting,
             pts_id,
             program_id,
+            zk_program_id,
             service_client,
             pts_service_client,
         };
@@ -741,51 +2327,78 @@ impl TestEnvironment {
             .unwrap()
     }
 
-    async fn setup_poker_program(
-        remoting: &GTestRemoting,
-        pts_id: ActorId,
-        admin_pk: &ZkPublicKey,
-    ) -> ActorId {
+    async fn setup_zk_verification(remoting: &GTestRemoting) -> ActorId {
         let shuffle_vkey_bytes =
             ZkLoaderData::load_verifying_key("tests/test_data/shuffle_vkey.json");
+        let short_deck_shuffle_vkey_bytes =
+            ZkLoaderData::load_verifying_key("tests/test_data_gtest/short_deck/shuffle_vkey.json");
 
         let zk_code_id = remoting.system().submit_code(zk_verification::WASM_BINARY);
         let zk_factory = zk_verification_client::ZkVerificationFactory::new(remoting.clone());
 
-        let zk_program_id = zk_factory
-            .new(shuffle_vkey_bytes)
+        zk_factory
+            .new(shuffle_vkey_bytes, Some(short_deck_shuffle_vkey_bytes))
             .send_recv(zk_code_id, b"salt")
             .await
-            .unwrap();
-        let program_code_id = remoting.system().submit_code(poker::WASM_BINARY);
-        let program_factory = poker_client::PokerFactory::new(remoting.clone());
-
-        program_factory
-            .new(
-                GameConfig {
-                    admin_id: USERS[0].into(),
-                    admin_name: "Player_1".to_string(),
-                    lobby_name: "Lobby name".to_string(),
-                    small_blind: 5,
-                    big_blind: 10,
-                    starting_bank: 1000,
-                    time_per_move_ms: 30_000,
-                },
-                SessionConfig {
-                    gas_to_delete_session: 10_000_000_000,
-                    minimum_session_duration_ms: 180_000,
-                    ms_per_block: 3_000,
-                },
-                pts_id,
-                admin_pk.clone(),
-                None,
-                zk_program_id,
-            )
-            .send_recv(program_code_id, b"salt")
-            .await
             .unwrap()
     }
 
+    async fn setup_poker_program(
+        remoting: &GTestRemoting,
+        pts_id: ActorId,
+        zk_program_id: ActorId,
+        admin_pk: &ZkPublicKey,
+        admin_sk: Fr,
+        configure: impl FnOnce(&mut GameConfig),
+    ) -> ActorId {
+        // the program is deployed by the admin, so the proof is bound to the admin's address
+        let admin_pk_proof = prove_secret_key(
+            deserialize_public_key(admin_pk),
+            admin_sk,
+            USERS[0].into(),
+            USERS[0].into(),
+        );
+
+        let mut config = GameConfig {
+            admin_id: USERS[0].into(),
+            admin_name: "Player_1".to_string(),
+            lobby_name: "Lobby name".to_string(),
+            small_blind: 5,
+            big_blind: 10,
+            starting_bank: 1000,
+            time_per_move_ms: 30_000,
+            betting_structure: None,
+            game_mode: GameMode::Cash,
+            ante: 0,
+            allow_straddle: false,
+            max_players: 9,
+            min_players_to_start: 2,
+            game_variant: GameVariant::Holdem,
+            hi_lo: false,
+        };
+        configure(&mut config);
+
+        let program = Program::from_binary_with_id(remoting.system(), LOBBY_ID, poker::WASM_BINARY);
+        let payload = poker_client::poker_factory::io::New::encode_call(
+            config,
+            SessionConfig {
+                gas_to_delete_session: 10_000_000_000,
+                minimum_session_duration_ms: 180_000,
+                ms_per_block: 3_000,
+            },
+            pts_id,
+            admin_pk.clone(),
+            admin_pk_proof,
+            None,
+            zk_program_id,
+        );
+        let init_message_id = program.send_bytes(USERS[0], payload);
+        let block_run_result = remoting.system().run_next_block();
+        assert!(block_run_result.succeed.contains(&init_message_id));
+
+        LOBBY_ID.into()
+    }
+
     async fn register_players(&mut self, test_data: &TestData) {
         println!("REGISTER");
 
@@ -796,16 +2409,30 @@ impl TestEnvironment {
                 .with_args(|args| args.with_actor_id(user_id.into()))
                 .send_recv(self.pts_id)
                 .await
+                .unwrap()
                 .unwrap();
         }
 
         // Register players (skip index 0 as it's admin)
         for (i, user) in USERS.iter().enumerate().skip(1) {
+            let pk_proof = prove_secret_key(
+                deserialize_public_key(&test_data.pks[i].1),
+                test_data.sks[i].1.scalar,
+                (*user).into(),
+                self.program_id,
+            );
             self.service_client
-                .register("Player".to_string(), test_data.pks[i].1.clone(), None)
+                .register(
+                    "Player".to_string(),
+                    test_data.pks[i].1.clone(),
+                    pk_proof,
+                    None,
+                    None,
+                )
                 .with_args(|args| args.with_actor_id((*user).into()))
                 .send_recv(self.program_id)
                 .await
+                .unwrap()
                 .unwrap();
         }
     }
@@ -816,27 +2443,29 @@ impl TestEnvironment {
             .start_game(None)
             .send_recv(self.program_id)
             .await
+            .unwrap()
             .unwrap();
-        self.check_status(Status::WaitingShuffleVerification).await;
 
-        println!("SHUFFLE");
-        self.service_client
-            .shuffle_deck(
-                test_data.encrypted_deck.clone(),
-                test_data.shuffle_proofs.clone(),
-            )
-            .send_recv(self.program_id)
-            .await
-            .unwrap();
-        self.check_status(Status::WaitingPartialDecryptionsForPlayersCards)
-            .await;
+        self.setup_game(test_data).await;
+    }
+
+    /// Shuffles the deck and decrypts the hole cards of a started hand
+    async fn setup_game(&mut self, test_data: &TestData) {
+        self.shuffle_deck(test_data).await;
 
         println!("DECRYPT");
 
         let partial_decs = get_decs_from_proofs(&test_data.decrypt_proofs.clone());
+        let round = self.round().await;
+        let participant_ids: Vec<ActorId> = self
+            .participants()
+            .await
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
 
         let g = G::generator();
-        for (i, user) in USERS.iter().enumerate() {
+        for (i, user) in test_data.players.iter().enumerate() {
             let pk = deserialize_public_key(&(test_data.pks[i].1.clone()));
             let sk = test_data.sks[i].1.scalar;
             let mut items = Vec::new();
@@ -847,7 +2476,15 @@ impl TestEnvironment {
                 println!("delta_c0 {:?}", partial_decs[10 * i + k].1.clone());
                 let delta_c0_neg = -delta_c0;
 
-                let proof = prove(g, pk, c0, delta_c0_neg, sk);
+                // hole cards are dealt two per participant from the top of the deck
+                let deck_idx =
+                    find_card_index(&test_data.encrypted_deck, &partial_decs[10 * i + k].0);
+                let slot = CardSlot::Hole {
+                    owner: participant_ids[deck_idx / 2],
+                    index: (deck_idx % 2) as u8,
+                };
+                let transcript = decryption_transcript(self.program_id, round, slot);
+                let proof = prove(g, pk, c0, delta_c0_neg, sk, &transcript);
 
                 let item = PartialDec {
                     c0: partial_decs[10 * i + k].0.clone(),
@@ -862,6 +2499,7 @@ impl TestEnvironment {
                 .with_args(|args| args.with_actor_id((*user).into()))
                 .send_recv(self.program_id)
                 .await
+                .unwrap()
                 .unwrap();
         }
 
@@ -871,11 +2509,32 @@ impl TestEnvironment {
         .await;
     }
 
+    async fn shuffle_deck(&mut self, test_data: &TestData) {
+        println!("SHUFFLE");
+        let proofs = &test_data.shuffle_proofs[&self.round().await];
+        for (i, user) in test_data.players.iter().enumerate() {
+            self.check_status(Status::WaitingShuffle {
+                turn: (*user).into(),
+            })
+            .await;
+            self.service_client
+                .submit_shuffle(proofs[i].clone(), None)
+                .with_args(|args| args.with_actor_id((*user).into()))
+                .send_recv(self.program_id)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        self.check_status(Status::WaitingPartialDecryptionsForPlayersCards)
+            .await;
+    }
+
     async fn restart_game(&mut self) {
         self.service_client
             .restart_game(None)
             .send_recv(self.program_id)
             .await
+            .unwrap()
             .unwrap();
     }
 
@@ -884,21 +2543,26 @@ impl TestEnvironment {
             .delete_player(id.into(), None)
             .send_recv(self.program_id)
             .await
+            .unwrap()
             .unwrap();
     }
 
-    async fn register(&mut self, id: u64, pk: ZkPublicKey) {
+    async fn register(&mut self, id: u64, pk: ZkPublicKey, sk: Fr) {
         self.pts_service_client
             .get_accural()
             .with_args(|args| args.with_actor_id(id.into()))
             .send_recv(self.pts_id)
             .await
+            .unwrap()
             .unwrap();
+        let pk_proof =
+            prove_secret_key(deserialize_public_key(&pk), sk, id.into(), self.program_id);
         self.service_client
-            .register("".to_string(), pk, None)
+            .register("".to_string(), pk, pk_proof, None, None)
             .with_args(|args| args.with_actor_id(id.into()))
             .send_recv(self.program_id)
             .await
+            .unwrap()
             .unwrap();
     }
 
@@ -910,80 +2574,141 @@ impl TestEnvironment {
                 .with_args(|args| args.with_actor_id(user_id.into()))
                 .send_recv(self.program_id)
                 .await
+                .unwrap()
                 .unwrap();
         }
     }
 
     pub async fn reveal_table_cards(&mut self, test_data: &TestData, range: Range<usize>) {
+        for (i, user) in test_data.players.iter().enumerate() {
+            let items = self
+                .table_card_decryptions(test_data, i, range.clone())
+                .await;
+            self.service_client
+                .submit_table_partial_decryptions(items, None)
+                .with_args(|args| args.with_actor_id((*user).into()))
+                .send_recv(self.program_id)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+
+    // decryption shares of the table cards in `range` by the player of seat `i` with their proofs
+    async fn table_card_decryptions(
+        &self,
+        test_data: &TestData,
+        i: usize,
+        range: Range<usize>,
+    ) -> Vec<PartialDec> {
         let table_cards_proofs = test_data
             .table_cards_proofs
             .as_ref()
             .expect("No table_cards_proofs for this data profile");
+        let round = self.round().await;
+        let table_cards = self
+            .service_client
+            .encrypted_table_cards()
+            .recv(self.program_id)
+            .await
+            .unwrap();
         let g = G::generator();
-        for (i, user) in USERS.iter().enumerate() {
-            let partial_decs = get_decs_from_proofs(&table_cards_proofs[i].1 .1[range.clone()]);
-            let pk = deserialize_public_key(&(test_data.pks[i].1.clone()));
-            let sk = test_data.sks[i].1.scalar;
-            let mut items = Vec::new();
-            for dec in partial_decs {
-                let c0 = deserialize_bandersnatch_coords(&dec.0.clone());
-                let delta_c0 = deserialize_bandersnatch_coords(&dec.1.clone());
-                let delta_c0_neg = -delta_c0;
-
-                let proof = prove(g, pk, c0, delta_c0_neg, sk);
+        let partial_decs = get_decs_from_proofs(&table_cards_proofs[i].1 .1[range]);
+        let pk = deserialize_public_key(&(test_data.pks[i].1.clone()));
+        let sk = test_data.sks[i].1.scalar;
+        let mut items = Vec::new();
+        for dec in partial_decs {
+            let c0 = deserialize_bandersnatch_coords(&dec.0.clone());
+            let delta_c0 = deserialize_bandersnatch_coords(&dec.1.clone());
+            let delta_c0_neg = -delta_c0;
+
+            let slot = CardSlot::Table {
+                index: find_card_index(&table_cards, &dec.0) as u8,
+            };
+            let transcript = decryption_transcript(self.program_id, round, slot);
+            let proof = prove(g, pk, c0, delta_c0_neg, sk, &transcript);
+
+            let item = PartialDec {
+                c0: dec.0.clone(),
+                delta_c0: dec.1.clone(),
+                proof: proof.to_bytes(),
+            };
+            items.push(item.clone());
+        }
+        items
+    }
 
-                let item = PartialDec {
-                    c0: dec.0.clone(),
-                    delta_c0: dec.1.clone(),
-                    proof: proof.to_bytes(),
-                };
-                items.push(item.clone());
-            }
+    async fn reveal_player_cards(&mut self, test_data: &TestData) {
+        println!("Players reveal their cards..");
+        // players show in the showdown order until the hand is over
+        loop {
+            let showdown_order = self
+                .service_client
+                .showdown_order()
+                .recv(self.program_id)
+                .await
+                .unwrap();
+            let Some(player) = showdown_order.first() else {
+                break;
+            };
+            let i = test_data
+                .players
+                .iter()
+                .position(|user| ActorId::from(*user) == *player)
+                .expect("Unknown player");
+            let items = self.hole_card_decryptions(test_data, i).await;
             self.service_client
-                .submit_table_partial_decryptions(items, None)
-                .with_args(|args| args.with_actor_id((*user).into()))
+                .card_disclosure(items, None)
+                .with_args(|args| args.with_actor_id(test_data.players[i].into()))
                 .send_recv(self.program_id)
                 .await
+                .unwrap()
                 .unwrap();
         }
     }
 
-    async fn reveal_player_cards(&mut self, test_data: &TestData) {
-        println!("Players reveal their cards..");
+    // decryption shares of the hole cards of the player of seat `i` with their proofs
+    async fn hole_card_decryptions(&self, test_data: &TestData, i: usize) -> Vec<PartialDec> {
         let player_cards = test_data
             .player_cards
             .as_ref()
             .expect("No player_cards for this data profile");
         let (_, card_map) = init_deck_and_card_map();
         let hands = build_player_card_disclosure(player_cards.clone(), &card_map);
+        let round = self.round().await;
         let g = G::generator();
-        for i in 0..USERS.len() {
-            let proofs = hands[i].1.clone();
-            let partial_decs = get_decs_from_proofs(&proofs);
-            let pk = deserialize_public_key(&(test_data.pks[i].1.clone()));
-            let sk = test_data.sks[i].1.scalar;
-            let mut items = Vec::new();
-            for dec in partial_decs {
-                let c0 = deserialize_bandersnatch_coords(&dec.0.clone());
-                let delta_c0 = deserialize_bandersnatch_coords(&dec.1.clone());
-                let delta_c0_neg = -delta_c0;
-
-                let proof = prove(g, pk, c0, delta_c0_neg, sk);
-
-                let item = PartialDec {
-                    c0: dec.0.clone(),
-                    delta_c0: dec.1.clone(),
-                    proof: proof.to_bytes(),
-                };
-                items.push(item.clone());
-            }
-            self.service_client
-                .card_disclosure(items, None)
-                .with_args(|args| args.with_actor_id(USERS[i].into()))
-                .send_recv(self.program_id)
-                .await
-                .unwrap();
+        let proofs = hands[i].1.clone();
+        let partial_decs = get_decs_from_proofs(&proofs);
+        let hole_cards = self
+            .service_client
+            .player_cards(test_data.players[i].into())
+            .recv(self.program_id)
+            .await
+            .unwrap()
+            .expect("No cards for the player");
+        let pk = deserialize_public_key(&(test_data.pks[i].1.clone()));
+        let sk = test_data.sks[i].1.scalar;
+        let mut items = Vec::new();
+        for dec in partial_decs {
+            let c0 = deserialize_bandersnatch_coords(&dec.0.clone());
+            let delta_c0 = deserialize_bandersnatch_coords(&dec.1.clone());
+            let delta_c0_neg = -delta_c0;
+
+            let slot = CardSlot::Hole {
+                owner: test_data.players[i].into(),
+                index: find_card_index(&hole_cards, &dec.0) as u8,
+            };
+            let transcript = decryption_transcript(self.program_id, round, slot);
+            let proof = prove(g, pk, c0, delta_c0_neg, sk, &transcript);
+
+            let item = PartialDec {
+                c0: dec.0.clone(),
+                delta_c0: dec.1.clone(),
+                proof: proof.to_bytes(),
+            };
+            items.push(item.clone());
         }
+        items
     }
 
     async fn print_table_cards(&mut self) {
@@ -1011,6 +2736,14 @@ impl TestEnvironment {
         result
     }
 
+    async fn round(&self) -> u64 {
+        self.service_client
+            .round()
+            .recv(self.program_id)
+            .await
+            .unwrap()
+    }
+
     async fn participants(&self) -> Vec<(ActorId, poker_client::Participant)> {
         let participants = self
             .service_client
@@ -1106,6 +2839,29 @@ impl WasmProgram for BlsBuiltinMock {
     fn debug(&mut self, _data: &str) {}
 }
 
+fn to_zk_instance(
+    instance: &VerificationVariables,
+) -> zk_verification_client::VerificationVariables {
+    zk_verification_client::VerificationVariables {
+        proof_bytes: zk_verification_client::ProofBytes {
+            a: instance.proof_bytes.a.clone(),
+            b: instance.proof_bytes.b.clone(),
+            c: instance.proof_bytes.c.clone(),
+        },
+        public_input: instance.public_input.clone(),
+    }
+}
+
+fn shift_g1(bytes: &[u8], shift: G1Affine) -> Vec<u8> {
+    let point = G1Affine::deserialize_uncompressed_unchecked(bytes).expect("Invalid G1 point");
+    let shifted: G1Affine = (point + shift).into();
+    let mut shifted_bytes = Vec::new();
+    shifted
+        .serialize_uncompressed(&mut shifted_bytes)
+        .expect("Unable to serialize G1 point");
+    shifted_bytes
+}
+
 pub fn get_decs_from_proofs(proofs: &[VerificationVariables]) -> Vec<([Vec<u8>; 3], [Vec<u8>; 3])> {
     let mut results = Vec::new();
     for proof in proofs {
@@ -1155,8 +2911,49 @@ impl ChaumPedersenProof {
     }
 }
 
-fn hash_to_fr(points: &[G]) -> Fr {
+/// Position of a card whose partial decryption is proven
+#[derive(Debug, Clone, Copy)]
+pub enum CardSlot {
+    Hole { owner: ActorId, index: u8 },
+    Table { index: u8 },
+}
+
+const DECRYPTION_PROOF_DOMAIN: &[u8] = b"zk-mental-poker/cp-dec";
+const DECRYPTION_PROOF_VERSION: u32 = 1;
+
+/// Builds the transcript prefix a partial decryption proof is bound to:
+/// `tag || version (u32 LE) || lobby_id || round (u64 LE) || SCALE(slot)`
+pub fn decryption_transcript(lobby_id: ActorId, round: u64, slot: CardSlot) -> Vec<u8> {
+    let lobby_id: [u8; 32] = lobby_id.into();
+    let slot = match slot {
+        CardSlot::Hole { owner, index } => {
+            let owner: [u8; 32] = owner.into();
+            [&[0u8][..], &owner, &[index]].concat()
+        }
+        CardSlot::Table { index } => vec![1, index],
+    };
+
+    [
+        DECRYPTION_PROOF_DOMAIN,
+        &DECRYPTION_PROOF_VERSION.to_le_bytes(),
+        &lobby_id,
+        &round.to_le_bytes(),
+        &slot,
+    ]
+    .concat()
+}
+
+fn find_card_index(cards: &[poker_client::EncryptedCard], c0: &[Vec<u8>; 3]) -> usize {
+    let c0 = deserialize_bandersnatch_coords(c0);
+    cards
+        .iter()
+        .position(|card| deserialize_bandersnatch_coords(&card.c0) == c0)
+        .expect("Card not found")
+}
+
+fn hash_to_fr(prefix: &[u8], points: &[G]) -> Fr {
     let mut hasher = Blake2b512::new();
+    hasher.update(prefix);
 
     for p in points {
         let affine = p.into_affine();
@@ -1172,21 +2969,21 @@ fn hash_to_fr(points: &[G]) -> Fr {
 }
 
 // prove: D = c1^sk and pk = g^sk
-pub fn prove(g: G, pk: G, c1: G, d: G, sk: Fr) -> ChaumPedersenProof {
+pub fn prove(g: G, pk: G, c1: G, d: G, sk: Fr, transcript: &[u8]) -> ChaumPedersenProof {
     let r = Fr::rand(&mut rand::thread_rng());
 
     let a = g * r;
     let b = c1 * r;
 
-    let c = hash_to_fr(&[g, pk, c1, d, a, b]);
+    let c = hash_to_fr(transcript, &[g, pk, c1, d, a, b]);
 
     let z = r + c * sk;
 
     ChaumPedersenProof { a, b, z }
 }
 
-pub fn verify(g: G, pk: G, c1: G, d: G, proof: &ChaumPedersenProof) -> bool {
-    let c = hash_to_fr(&[g, pk, c1, d, proof.a, proof.b]);
+pub fn verify(g: G, pk: G, c1: G, d: G, proof: &ChaumPedersenProof, transcript: &[u8]) -> bool {
+    let c = hash_to_fr(transcript, &[g, pk, c1, d, proof.a, proof.b]);
 
     let lhs1 = g * proof.z;
     let rhs1 = proof.a + pk * c;
@@ -1206,6 +3003,21 @@ pub fn deserialize_bandersnatch_coords(coords: &[Vec<u8>; 3]) -> G {
     G::new_unchecked(x, y, t, z).into_affine().into()
 }
 
+fn serialize_public_key(point: &G) -> ZkPublicKey {
+    fn fq_to_bytes(x: &Fq) -> [u8; 32] {
+        x.into_bigint()
+            .to_bytes_le()
+            .try_into()
+            .expect("Fq is 32 bytes")
+    }
+
+    ZkPublicKey {
+        x: fq_to_bytes(&point.x),
+        y: fq_to_bytes(&point.y),
+        z: fq_to_bytes(&point.z),
+    }
+}
+
 fn deserialize_public_key(pk: &ZkPublicKey) -> G {
     let x = Fq::from_le_bytes_mod_order(&pk.x);
     let y = Fq::from_le_bytes_mod_order(&pk.y);
@@ -2,13 +2,14 @@
 use crate::send_request;
 use gclient::{EventListener, EventProcessor, GearApi, Result};
 use gear_core::ids::ProgramId;
-use poker_client::{Card, GameConfig, SessionConfig, Suit, ZkPublicKey};
+use poker_client::{Card, GameConfig, GameMode, GameVariant, SessionConfig, Suit, ZkPublicKey};
 use sails_rs::{ActorId, Encode};
 pub mod zk_loader;
-use ark_ec::AffineRepr;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
 use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsProjective, Fq, Fr};
-use ark_ff::PrimeField;
-use poker_client::{SignatureInfo, VerificationVariables};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use blake2::{Blake2b512, Digest};
+use poker_client::{SchnorrProofBytes, SignatureInfo, VerificationVariables};
 use sails_rs::collections::HashMap;
 use zk_loader::{DecryptedCardWithProof, ZkLoaderData};
 
@@ -60,14 +61,20 @@ pub async fn get_new_client(api: &GearApi, name: &str) -> GearApi {
 pub async fn init(
     api: &GearApi,
     pk: ZkPublicKey,
+    pk_proof: SchnorrProofBytes,
     listener: &mut EventListener,
 ) -> Result<(ProgramId, ProgramId)> {
     // ZK VERIFICATION
     println!("Upload zk verification contract");
     let path = "../target/wasm32-gear/release/zk_verification.opt.wasm";
     let shuffle_vkey = ZkLoaderData::load_verifying_key("tests/test_data/shuffle_vkey.json");
-    let decrypt_vkey = ZkLoaderData::load_verifying_key("tests/test_data/decrypt_vkey.json");
-    let request = ["New".encode(), (shuffle_vkey, decrypt_vkey).encode()].concat();
+    let short_deck_shuffle_vkey =
+        ZkLoaderData::load_verifying_key("tests/test_data_gtest/short_deck/shuffle_vkey.json");
+    let request = [
+        "New".encode(),
+        (shuffle_vkey, Some(short_deck_shuffle_vkey)).encode(),
+    ]
+    .concat();
 
     let (message_id, zk_program_id, _hash) = api
         .upload_program_bytes(
@@ -114,6 +121,14 @@ pub async fn init(
         small_blind: 5,
         big_blind: 10,
         starting_bank: 1000,
+        betting_structure: None,
+        game_mode: GameMode::Cash,
+        ante: 0,
+        allow_straddle: false,
+        max_players: 9,
+        min_players_to_start: 2,
+        game_variant: GameVariant::Holdem,
+        hi_lo: false,
     };
     let session_config = SessionConfig {
         gas_to_delete_session: 10_000_000_000,
@@ -129,6 +144,7 @@ pub async fn init(
         session_config,
         pts_id,
         pk,
+        pk_proof,
         session_for_admin,
         zk_program_id,
     );
@@ -166,8 +182,8 @@ pub async fn make_zk_actions(
     listener: &mut EventListener,
 ) -> Result<(ProgramId, Vec<(ZkPublicKey, ActorId, &'static str)>)> {
     let pks = ZkLoaderData::load_player_public_keys("tests/test_data/player_pks.json");
+    let sks = ZkLoaderData::load_player_secret_keys("tests/test_data/player_sks.json");
     let proofs = ZkLoaderData::load_shuffle_proofs("tests/test_data/shuffle_proofs.json");
-    let deck = ZkLoaderData::load_encrypted_table_cards("tests/test_data/encrypted_deck.json");
 
     let decrypt_proofs =
         ZkLoaderData::load_partial_decrypt_proofs("tests/test_data/partial_decrypt_proofs.json");
@@ -178,7 +194,10 @@ pub async fn make_zk_actions(
     pk_to_actor_id.push((pks[0].1.clone(), id, USERS_STR[0]));
 
     // Init
-    let (pts_id, program_id) = init(&api, pks[0].1.clone(), listener).await?;
+    let pk_proof = prove_secret_key(pk_to_point(&pks[0].1), sks[0].1.scalar, id, id);
+    let (pts_id, program_id) = init(&api, pks[0].1.clone(), pk_proof, listener).await?;
+    let poker_id_bytes: [u8; 32] = program_id.into();
+    let poker_id: ActorId = poker_id_bytes.into();
 
     // Resgiter
     println!("REGISTER");
@@ -190,7 +209,8 @@ pub async fn make_zk_actions(
     let message_id = send_request!(api: &api, program_id: pts_id, service_name: "Pts", action: "GetAccural", payload: ());
     assert!(listener.message_processed(message_id).await?.succeed());
 
-    let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "Register", payload: (player_name, pks[1].1.clone(), session_for_account.clone()));
+    let pk_proof = prove_secret_key(pk_to_point(&pks[1].1), sks[1].1.scalar, id, poker_id);
+    let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "Register", payload: (player_name, pks[1].1.clone(), pk_proof, None::<u8>, session_for_account.clone()));
     assert!(listener.message_processed(message_id).await?.succeed());
 
     player_name = "Bob".to_string();
@@ -201,7 +221,8 @@ pub async fn make_zk_actions(
     let message_id = send_request!(api: &api, program_id: pts_id, service_name: "Pts", action: "GetAccural", payload: ());
     assert!(listener.message_processed(message_id).await?.succeed());
 
-    let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "Register", payload: (player_name, pks[2].1.clone(), session_for_account.clone()));
+    let pk_proof = prove_secret_key(pk_to_point(&pks[2].1), sks[2].1.scalar, id, poker_id);
+    let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "Register", payload: (player_name, pks[2].1.clone(), pk_proof, None::<u8>, session_for_account.clone()));
     assert!(listener.message_processed(message_id).await?.succeed());
 
     // Start game
@@ -213,10 +234,17 @@ pub async fn make_zk_actions(
     let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "StartGame", payload: (session_for_account.clone()));
     assert!(listener.message_processed(message_id).await?.succeed());
 
-    // Shuffle deck
+    // Shuffle deck, one proof per player in seat order. The proofs carry the nonces of the
+    // first round of the lobby the fixtures were generated for, see the poker-fixtures crate
     println!("SHUFFLE");
-    let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "ShuffleDeck", payload: (deck, proofs));
-    assert!(listener.message_processed(message_id).await?.succeed());
+    for (i, proof) in proofs[&1].iter().enumerate() {
+        let api = api
+            .clone()
+            .with(USERS_STR[i])
+            .expect("Unable to change signer.");
+        let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "SubmitShuffle", payload: (proof.clone(), None::<ActorId>));
+        assert!(listener.message_processed(message_id).await?.succeed());
+    }
 
     println!("DECRYPT");
     let message_id = send_request!(api: &api, program_id: program_id, service_name: "Poker", action: "SubmitAllPartialDecryptions", payload: (decrypt_proofs));
@@ -225,6 +253,44 @@ pub async fn make_zk_actions(
     Ok((program_id, pk_to_actor_id))
 }
 
+/// Proves knowledge of `sk` for `pk = g * sk`, bound to the player and the lobby
+/// (the factory or deployer address when the proof is checked on lobby init).
+pub fn prove_secret_key(
+    pk: EdwardsProjective,
+    sk: Fr,
+    player_id: ActorId,
+    lobby_id: ActorId,
+) -> SchnorrProofBytes {
+    let g = EdwardsProjective::generator();
+    let r = Fr::rand(&mut rand::thread_rng());
+    let a = g * r;
+
+    let mut hasher = Blake2b512::new();
+    hasher.update(b"zk-mental-poker/sk-pok/v1");
+    hasher.update(<[u8; 32]>::from(player_id));
+    hasher.update(<[u8; 32]>::from(lobby_id));
+    for p in [g, pk, a] {
+        let affine = p.into_affine();
+        hasher.update(affine.x.into_bigint().to_bytes_le());
+        hasher.update(affine.y.into_bigint().to_bytes_le());
+    }
+    let c = Fr::from_le_bytes_mod_order(&hasher.finalize()[..32]);
+    let z = r + c * sk;
+
+    SchnorrProofBytes {
+        a: [
+            a.x.into_bigint().to_bytes_le(),
+            a.y.into_bigint().to_bytes_le(),
+            a.z.into_bigint().to_bytes_le(),
+        ],
+        z: z.into_bigint().to_bytes_le(),
+    }
+}
+
+pub fn pk_to_point(pk: &ZkPublicKey) -> EdwardsProjective {
+    deserialize_bandersnatch_coords(&[pk.x.to_vec(), pk.y.to_vec(), pk.z.to_vec()])
+}
+
 fn deserialize_bandersnatch_coords(coords: &[Vec<u8>; 3]) -> EdwardsProjective {
     let x = Fq::from_le_bytes_mod_order(&coords[0]);
     let y = Fq::from_le_bytes_mod_order(&coords[1]);
@@ -11,6 +11,7 @@ use num_bigint::BigUint;
 use num_traits::Num;
 use poker_client::{EncryptedCard, ProofBytes, VerificationVariables, ZkPublicKey};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
 use std::ops::Neg;
 use std::str::FromStr;
@@ -20,7 +21,6 @@ use zk_verification_client::VerifyingKeyBytes;
 const FIELD_ELEMENT_SIZE: usize = 32;
 const G1_UNCOMPRESSED_SIZE: usize = 96;
 const EXPECTED_DECK_ROWS: usize = 6;
-const DECK_SIZE: usize = 52;
 
 // === Data Structures ===
 #[derive(Debug, Deserialize)]
@@ -165,8 +165,20 @@ impl ZkLoaderData {
         Self::load_batch_proofs(path)
     }
 
-    pub fn load_shuffle_proofs(path: &str) -> Vec<VerificationVariables> {
-        Self::load_batch_proofs(path)
+    /// Shuffle proofs of every seat by the round they were made for
+    pub fn load_shuffle_proofs(path: &str) -> BTreeMap<u64, Vec<VerificationVariables>> {
+        let parsed: BTreeMap<u64, Vec<BatchProofEntry>> = Self::read_json(path);
+
+        parsed
+            .into_iter()
+            .map(|(round, entries)| {
+                let proofs = entries
+                    .into_iter()
+                    .map(|entry| Self::construct_proof(&entry.proof, &entry.public_signals))
+                    .collect();
+                (round, proofs)
+            })
+            .collect()
     }
 
     pub fn load_table_cards_proofs(
@@ -204,7 +216,9 @@ impl ZkLoaderData {
             );
         }
 
-        (0..DECK_SIZE)
+        // one column per card, a short deck has fewer of them
+        let deck_size = json[0].len();
+        (0..deck_size)
             .map(|i| EncryptedCard {
                 c0: [
                     decimal_string_to_bytes(&json[0][i]),
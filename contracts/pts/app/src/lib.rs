@@ -18,6 +18,17 @@ struct Storage {
 
 static mut STORAGE: Option<Storage> = None;
 
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum PtsError {
+    TimeHasNotExpired,
+    AccessDenied,
+    TransferToSelf,
+    AccountNotFound,
+    LowBalance,
+}
+
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
 #[codec(crate = sails_rs::scale_codec)]
 #[scale_info(crate = sails_rs::scale_info)]
@@ -68,6 +79,12 @@ impl PtsService {
     fn get(&self) -> &'static Storage {
         unsafe { STORAGE.as_ref().expect("Storage is not initialized") }
     }
+    fn check_admin(&self) -> Result<(), PtsError> {
+        if !self.get().admins.contains(&msg::source()) {
+            return Err(PtsError::AccessDenied);
+        }
+        Ok(())
+    }
 }
 
 #[sails_rs::service(events = Event)]
@@ -76,13 +93,13 @@ impl PtsService {
         Self(())
     }
 
-    pub fn get_accural(&mut self) {
+    pub fn get_accural(&mut self) -> Result<(), PtsError> {
         let storage = self.get_mut();
         let msg_src = msg::source();
         let (balance, last_time) = storage.balances.entry(msg_src).or_insert((0, 0));
         let current_time = exec::block_timestamp();
         if current_time - *last_time < storage.time_ms_between_balance_receipt {
-            panic!("Time has not yet expired");
+            return Err(PtsError::TimeHasNotExpired);
         }
         *balance += storage.accrual;
         *last_time = current_time;
@@ -91,45 +108,59 @@ impl PtsService {
             accrual: storage.accrual,
         })
         .expect("Notification Error");
+        Ok(())
     }
 
-    pub fn transfer(&mut self, from: ActorId, to: ActorId, amount: u128) {
+    pub fn transfer(&mut self, from: ActorId, to: ActorId, amount: u128) -> Result<(), PtsError> {
         let storage = self.get_mut();
         let msg_src = msg::source();
         if !storage.admins.contains(&msg_src) && from != msg_src {
-            panic!("Access denied");
+            return Err(PtsError::AccessDenied);
         }
         if from == to {
-            panic!("Cannot transfer to self");
+            return Err(PtsError::TransferToSelf);
         }
         let (from_balance, _last_time) = storage
             .balances
             .get_mut(&from)
-            .expect("Actor id must be exist");
+            .ok_or(PtsError::AccountNotFound)?;
 
-        *from_balance = from_balance.checked_sub(amount).expect("Low balance");
+        *from_balance = from_balance
+            .checked_sub(amount)
+            .ok_or(PtsError::LowBalance)?;
 
         let (to_balance, _last_time) = storage.balances.entry(to).or_insert((0, 0));
         *to_balance = to_balance.checked_add(amount).unwrap_or(u128::MAX);
 
         self.emit_event(Event::Transfered { from, to, amount })
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub fn batch_transfer(&mut self, from: ActorId, to_ids: Vec<ActorId>, amounts: Vec<u128>) {
+    pub fn batch_transfer(
+        &mut self,
+        from: ActorId,
+        to_ids: Vec<ActorId>,
+        amounts: Vec<u128>,
+    ) -> Result<(), PtsError> {
         let storage = self.get_mut();
         let msg_src = msg::source();
         if !storage.admins.contains(&msg_src) && from != msg_src {
-            panic!("Access denied");
+            return Err(PtsError::AccessDenied);
         }
 
         let (from_balance, _last_time) = storage
             .balances
             .get_mut(&from)
-            .expect("Actor id must be exist");
+            .ok_or(PtsError::AccountNotFound)?;
 
-        let total_amount = amounts.iter().sum();
-        *from_balance = from_balance.checked_sub(total_amount).expect("Low balance");
+        let total_amount = amounts
+            .iter()
+            .try_fold(0u128, |sum, amount| sum.checked_add(*amount))
+            .ok_or(PtsError::LowBalance)?;
+        *from_balance = from_balance
+            .checked_sub(total_amount)
+            .ok_or(PtsError::LowBalance)?;
 
         for (id, amount) in to_ids.clone().into_iter().zip(amounts.clone()) {
             let (to_balance, _last_time) = storage.balances.entry(id).or_insert((0, 0));
@@ -142,48 +173,48 @@ impl PtsService {
             amounts,
         })
         .expect("Notification Error");
+        Ok(())
     }
 
-    pub fn add_admin(&mut self, new_admin: ActorId) {
+    pub fn add_admin(&mut self, new_admin: ActorId) -> Result<(), PtsError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        if !storage.admins.contains(&msg::source()) {
-            panic!("Access denied");
-        }
         storage.admins.insert(new_admin);
         self.emit_event(Event::NewAdminAdded(new_admin))
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub fn delete_admin(&mut self, admin: ActorId) {
+    pub fn delete_admin(&mut self, admin: ActorId) -> Result<(), PtsError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        if !storage.admins.contains(&msg::source()) {
-            panic!("Access denied");
-        }
         storage.admins.remove(&admin);
         self.emit_event(Event::AdminDeleted(admin))
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub fn change_accrual(&mut self, new_accrual: u128) {
+    pub fn change_accrual(&mut self, new_accrual: u128) -> Result<(), PtsError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        if !storage.admins.contains(&msg::source()) {
-            panic!("Access denied");
-        }
         storage.accrual = new_accrual;
         self.emit_event(Event::AccrualChanged(new_accrual))
             .expect("Notification Error");
+        Ok(())
     }
 
-    pub fn change_time_between_balance_receipt(&mut self, new_time_between_balance_receipt: u64) {
+    pub fn change_time_between_balance_receipt(
+        &mut self,
+        new_time_between_balance_receipt: u64,
+    ) -> Result<(), PtsError> {
+        self.check_admin()?;
         let storage = self.get_mut();
-        if !storage.admins.contains(&msg::source()) {
-            panic!("Access denied");
-        }
         storage.time_ms_between_balance_receipt = new_time_between_balance_receipt;
         self.emit_event(Event::TimeBetweenBalanceReceiptChanged(
             new_time_between_balance_receipt,
         ))
         .expect("Notification Error");
+        Ok(())
     }
 
     pub fn admins(&self) -> Vec<ActorId> {
@@ -196,20 +227,20 @@ impl PtsService {
         self.get().time_ms_between_balance_receipt
     }
 
-    pub fn get_balance(&self, id: ActorId) -> u128 {
+    pub fn get_balance(&self, id: ActorId) -> Result<u128, PtsError> {
         let (balance, _) = self
             .get()
             .balances
             .get(&id)
-            .expect("Actor id must be exist");
-        *balance
+            .ok_or(PtsError::AccountNotFound)?;
+        Ok(*balance)
     }
-    pub fn get_remaining_time_ms(&self, id: ActorId) -> Option<u64> {
+    pub fn get_remaining_time_ms(&self, id: ActorId) -> Result<Option<u64>, PtsError> {
         let storage = self.get();
-        let (_, last_time) = storage.balances.get(&id).expect("Actor id must be exist");
-        storage
+        let (_, last_time) = storage.balances.get(&id).ok_or(PtsError::AccountNotFound)?;
+        Ok(storage
             .time_ms_between_balance_receipt
-            .checked_sub(exec::block_timestamp() - last_time)
+            .checked_sub(exec::block_timestamp() - last_time))
     }
 }
 
@@ -34,12 +34,14 @@ async fn test_transfer() {
         .with_args(|args| args.with_actor_id(USER_ID.into()))
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     let balance = service_client
         .get_balance(USER_ID.into())
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     assert_eq!(balance, 1_000);
 
@@ -47,18 +49,21 @@ async fn test_transfer() {
         .transfer(USER_ID.into(), ACTOR_ID.into(), 1_000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     let balance = service_client
         .get_balance(USER_ID.into())
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     assert_eq!(balance, 0);
     let balance = service_client
         .get_balance(ACTOR_ID.into())
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     assert_eq!(balance, 1_000);
 
@@ -66,18 +71,21 @@ async fn test_transfer() {
         .transfer(ACTOR_ID.into(), USER_ID.into(), 1_000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     let balance = service_client
         .get_balance(ACTOR_ID.into())
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     assert_eq!(balance, 0);
     let balance = service_client
         .get_balance(USER_ID.into())
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     assert_eq!(balance, 1_000);
 }
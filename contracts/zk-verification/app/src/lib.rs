@@ -7,8 +7,11 @@ pub struct ZkVerificationProgram(());
 #[sails_rs::program]
 impl ZkVerificationProgram {
     // Program's constructor
-    pub fn new(vk_shuffle_bytes: VerifyingKeyBytes) -> Self {
-        ZkVerificationService::init(vk_shuffle_bytes);
+    pub fn new(
+        vk_shuffle_bytes: VerifyingKeyBytes,
+        vk_short_deck_shuffle_bytes: Option<VerifyingKeyBytes>,
+    ) -> Self {
+        ZkVerificationService::init(vk_shuffle_bytes, vk_short_deck_shuffle_bytes);
         Self(())
     }
 
@@ -1,10 +1,11 @@
 #![allow(static_mut_refs)]
+use blake2::{Blake2b512, Digest};
 use core::ops::AddAssign;
 use gbuiltin_bls381::{
     Request, Response,
     ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective as G1, G2Affine},
     ark_ec::{AffineRepr, Group, pairing::Pairing},
-    ark_ff::Field,
+    ark_ff::{Field, PrimeField, Zero},
     ark_scale,
     ark_scale::hazmat::ArkScaleProjective,
     ark_serialize::CanonicalDeserialize,
@@ -19,6 +20,8 @@ static mut STORAGE: Option<Storage> = None;
 #[derive(Debug)]
 struct Storage {
     shuffle_verification_context: BatchVerificationContext,
+    // shuffles of the 36-card short deck are proven by a circuit of their own
+    short_deck_shuffle_verification_context: Option<BatchVerificationContext>,
 }
 pub struct ZkVerificationService(());
 
@@ -34,24 +37,54 @@ impl ZkVerificationService {
         Self(())
     }
 
-    pub fn init(vk_shuffle_bytes: VerifyingKeyBytes) -> Self {
+    pub fn init(
+        vk_shuffle_bytes: VerifyingKeyBytes,
+        vk_short_deck_shuffle_bytes: Option<VerifyingKeyBytes>,
+    ) -> Self {
         unsafe {
             STORAGE = Some(Storage {
                 shuffle_verification_context: BatchVerificationContext::new(
                     &vk_shuffle_bytes,
                     ActorId::from(ACTOR_ID),
                 ),
+                short_deck_shuffle_verification_context: vk_short_deck_shuffle_bytes.map(
+                    |vk_bytes| BatchVerificationContext::new(&vk_bytes, ActorId::from(ACTOR_ID)),
+                ),
             });
         }
         Self(())
     }
 
-    pub async fn verify_shuffle(&mut self, instances: Vec<VerificationVariables>) {
+    /// Verifies a batch of shuffle proofs.
+    ///
+    /// The verifying key is chosen for every instance by the length of its public
+    /// input, a short-deck shuffle has fewer cards in it than a standard one. The
+    /// instances of each key are checked as a batch of their own.
+    pub async fn verify_shuffle(
+        &mut self,
+        instances: Vec<VerificationVariables>,
+    ) -> Result<(), ZkVerificationError> {
+        if instances.is_empty() {
+            return Err(ZkVerificationError::EmptyBatch);
+        }
+
         let storage = self.get();
-        storage
-            .shuffle_verification_context
-            .verify_batch(instances)
-            .await;
+        let short_deck_context = storage.short_deck_shuffle_verification_context.as_ref();
+        let (short_deck, standard): (Vec<_>, Vec<_>) =
+            instances.into_iter().partition(|instance| {
+                short_deck_context.is_some_and(|context| context.accepts(instance))
+            });
+
+        if let Some(context) = short_deck_context.filter(|_| !short_deck.is_empty()) {
+            context.verify_batch(short_deck).await?;
+        }
+        if !standard.is_empty() {
+            storage
+                .shuffle_verification_context
+                .verify_batch(standard)
+                .await?;
+        }
+        Ok(())
     }
 }
 
@@ -62,10 +95,25 @@ impl ZkVerificationService {
 type ArkScale<T> = ark_scale::ArkScale<T, { ark_scale::HOST_CALL }>;
 type Gt = <Bls12_381 as Pairing>::TargetField;
 
+/// Domain tag of the Fiat-Shamir transcript deriving batch weights
+const BATCH_WEIGHTS_DOMAIN: &[u8] = b"zk-mental-poker/groth16-batch/v1";
+
 // ================================================================================================
 // Core Data Structures
 // ================================================================================================
 
+/// Reasons a batch of proofs is rejected
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = scale_info)]
+pub enum ZkVerificationError {
+    EmptyBatch,
+    InvalidProofLength,
+    InvalidProofEncoding,
+    InvalidPublicInput,
+    VerificationFailed,
+}
+
 /// Serialized verifying key for zk-SNARK verification
 #[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
@@ -101,7 +149,9 @@ pub struct ProofBytes {
 struct ProofPoints {
     a_points: Vec<G1Affine>,
     b_points: Vec<G2Affine>,
-    batch_size: usize,
+    c_points: Vec<G1Affine>,
+    prepared_inputs: Vec<G1Affine>,
+    weights: Vec<Fr>,
 }
 
 /// Deserialized proof components
@@ -113,12 +163,19 @@ struct ProofComponents {
 }
 
 impl ProofComponents {
-    fn from_bytes(proof_bytes: &ProofBytes) -> Self {
-        Self {
-            a: CurvePointDeserializer::deserialize_g1(&proof_bytes.a),
-            b: CurvePointDeserializer::deserialize_g2(&proof_bytes.b),
-            c: CurvePointDeserializer::deserialize_g1(&proof_bytes.c),
-        }
+    fn from_bytes(proof_bytes: &ProofBytes) -> Result<Self, ZkVerificationError> {
+        let g1 = |bytes: &[u8]| {
+            G1Affine::deserialize_uncompressed_unchecked(bytes)
+                .map_err(|_| ZkVerificationError::InvalidProofEncoding)
+        };
+        let b = G2Affine::deserialize_uncompressed_unchecked(&*proof_bytes.b)
+            .map_err(|_| ZkVerificationError::InvalidProofEncoding)?;
+
+        Ok(Self {
+            a: g1(&proof_bytes.a)?,
+            b,
+            c: g1(&proof_bytes.c)?,
+        })
     }
 }
 
@@ -168,6 +225,11 @@ impl BatchVerificationContext {
         }
     }
 
+    /// Whether the public input of the instance matches the verifying key
+    fn accepts(&self, instance: &VerificationVariables) -> bool {
+        instance.public_input.len() + 1 == self.verifying_key.ic.len()
+    }
+
     pub async fn verify(&self, c0: Vec<u8>, delta: Vec<u8>, pk: Vec<u8>, g2: Vec<u8>) {
         let delta = CurvePointDeserializer::deserialize_g1(&delta);
         let c0 = CurvePointDeserializer::deserialize_g1(&c0);
@@ -189,61 +251,128 @@ impl BatchVerificationContext {
     }
 
     /// Performs batch verification of multiple zk-SNARK proofs
-    pub async fn verify_batch(&self, instances: Vec<VerificationVariables>) {
-        let proof_points = self.prepare_proof_points(instances).await;
+    ///
+    /// Each instance is weighted by a scalar derived from the whole batch, so
+    /// invalid proofs cannot cancel each other out in the pairing product:
+    /// `prod e(r_i * A_i, B_i) * e(sum r_i * IC_i, -gamma) * e(sum r_i * C_i, -delta)
+    /// == e(alpha, beta)^(sum r_i)`
+    pub async fn verify_batch(
+        &self,
+        instances: Vec<VerificationVariables>,
+    ) -> Result<(), ZkVerificationError> {
+        if instances.is_empty() {
+            return Err(ZkVerificationError::EmptyBatch);
+        }
+
+        let proof_points = self.prepare_proof_points(instances).await?;
         let is_valid = self.execute_pairing_check(&proof_points).await;
 
         if !is_valid {
-            panic!("Batch verification failed");
+            return Err(ZkVerificationError::VerificationFailed);
         }
+        Ok(())
     }
 
     /// Prepares proof points for batch verification
-    async fn prepare_proof_points(&self, instances: Vec<VerificationVariables>) -> ProofPoints {
+    async fn prepare_proof_points(
+        &self,
+        instances: Vec<VerificationVariables>,
+    ) -> Result<ProofPoints, ZkVerificationError> {
         let len = instances.len();
-        let mut a_points = Vec::with_capacity(3 * len);
-        let mut b_points = Vec::with_capacity(3 * len);
+        let weights = BatchWeights::derive(&instances);
+
+        // every instance is decoded before the first builtin call
+        let decoded = instances
+            .iter()
+            .map(|instance| {
+                let public_inputs = PublicInputProcessor::parse_inputs(
+                    &instance.public_input,
+                    &self.verifying_key.ic,
+                )?;
+                let proof_components = ProofComponents::from_bytes(&instance.proof_bytes)?;
+                Ok((public_inputs, proof_components))
+            })
+            .collect::<Result<Vec<_>, ZkVerificationError>>()?;
+
+        let mut a_points = Vec::with_capacity(len);
+        let mut b_points = Vec::with_capacity(len);
+        let mut c_points = Vec::with_capacity(len);
+        let mut prepared_inputs = Vec::with_capacity(len);
 
-        for instance in instances.into_iter() {
-            let prepared_inputs = PublicInputProcessor::prepare_inputs_bytes(
-                &instance.public_input,
+        for (public_inputs, proof_components) in decoded {
+            let prepared = PublicInputProcessor::prepare_inputs(
                 &self.verifying_key.ic,
+                &public_inputs,
                 self.builtin_address,
             )
             .await;
 
-            let proof_components = ProofComponents::from_bytes(&instance.proof_bytes);
-
-            a_points.extend([proof_components.a, prepared_inputs, proof_components.c]);
-            b_points.extend([
-                proof_components.b,
-                self.verifying_key.gamma_g2_neg_pc,
-                self.verifying_key.delta_g2_neg_pc,
-            ]);
+            a_points.push(proof_components.a);
+            b_points.push(proof_components.b);
+            c_points.push(proof_components.c);
+            prepared_inputs.push(prepared);
         }
 
-        ProofPoints {
+        Ok(ProofPoints {
             a_points,
             b_points,
-            batch_size: len,
-        }
+            c_points,
+            prepared_inputs,
+            weights,
+        })
     }
 
     /// Executes the pairing check for batch verification
     async fn execute_pairing_check(&self, proof_points: &ProofPoints) -> bool {
-        let a: ArkScale<Vec<G1Affine>> = proof_points.a_points.clone().into();
-        let b: ArkScale<Vec<G2Affine>> = proof_points.b_points.clone().into();
+        let len = proof_points.weights.len();
+        let mut g1_points = Vec::with_capacity(len + 2);
+        let mut g2_points = Vec::with_capacity(len + 2);
+
+        for ((a, b), weight) in proof_points
+            .a_points
+            .iter()
+            .zip(&proof_points.b_points)
+            .zip(&proof_points.weights)
+        {
+            let weighted_a =
+                PairingOperations::msm_g1_affine(&[*a], &[*weight], self.builtin_address).await;
+            g1_points.push(weighted_a);
+            g2_points.push(*b);
+        }
+
+        let weighted_inputs = PairingOperations::msm_g1_affine(
+            &proof_points.prepared_inputs,
+            &proof_points.weights,
+            self.builtin_address,
+        )
+        .await;
+        let weighted_c = PairingOperations::msm_g1_affine(
+            &proof_points.c_points,
+            &proof_points.weights,
+            self.builtin_address,
+        )
+        .await;
+
+        g1_points.extend([weighted_inputs, weighted_c]);
+        g2_points.extend([
+            self.verifying_key.gamma_g2_neg_pc,
+            self.verifying_key.delta_g2_neg_pc,
+        ]);
+
+        let a: ArkScale<Vec<G1Affine>> = g1_points.into();
+        let b: ArkScale<Vec<G2Affine>> = g2_points.into();
 
         let miller_out =
             PairingOperations::multi_miller_loop(a.encode(), b.encode(), self.builtin_address)
                 .await;
 
         let exp = PairingOperations::final_exponentiation(miller_out, self.builtin_address).await;
+        let weights_sum: Fr = proof_points.weights.iter().sum();
         let expected = self
             .verifying_key
             .alpha_g1_beta_g2
             .0
-            .pow([proof_points.batch_size as u64]);
+            .pow(weights_sum.into_bigint());
 
         exp.0 == expected
     }
@@ -270,6 +399,24 @@ impl PairingOperations {
         }
     }
 
+    /// Computes `sum scalars[i] * bases[i]` and returns it in affine form
+    async fn msm_g1_affine(
+        bases: &[G1Affine],
+        scalars: &[Fr],
+        builtin_address: ActorId,
+    ) -> G1Affine {
+        let bases: ArkScale<Vec<G1Affine>> = bases.to_vec().into();
+        let scalars: ArkScale<Vec<<G1 as Group>::ScalarField>> = scalars.to_vec().into();
+
+        let msm_result_bytes =
+            Self::multi_scalar_mul_g1(bases.encode(), scalars.encode(), builtin_address).await;
+
+        ArkScaleProjective::<G1>::decode(&mut msm_result_bytes.as_slice())
+            .expect("Deserialization failed: MSM result")
+            .0
+            .into()
+    }
+
     async fn multi_miller_loop(g1: Vec<u8>, g2: Vec<u8>, builtin_address: ActorId) -> Vec<u8> {
         match PairingOperations::send_request_and_extract(
             Request::MultiMillerLoop { a: g1, b: g2 },
@@ -316,21 +463,22 @@ impl PairingOperations {
 struct PublicInputProcessor;
 
 impl PublicInputProcessor {
-    /// Prepares public inputs for verification from byte representation
-    async fn prepare_inputs_bytes(
+    /// Decodes public inputs and checks their count against the verifying key
+    fn parse_inputs(
         public_input: &[Vec<u8>],
-        ic: &[G1Affine],
-        builtin_address: ActorId,
-    ) -> G1Affine {
-        let public_inputs: Vec<Fr> = public_input
+        gamma_abc_g1: &[G1Affine],
+    ) -> Result<Vec<Fr>, ZkVerificationError> {
+        if (public_input.len() + 1) != gamma_abc_g1.len() {
+            return Err(ZkVerificationError::InvalidProofLength);
+        }
+
+        public_input
             .iter()
             .map(|bytes| {
                 Fr::deserialize_uncompressed_unchecked(&**bytes)
-                    .expect("Deserialization failed: public input")
+                    .map_err(|_| ZkVerificationError::InvalidPublicInput)
             })
-            .collect();
-
-        Self::prepare_inputs(ic, &public_inputs, builtin_address).await
+            .collect()
     }
 
     /// Prepares verification inputs using multi-scalar multiplication
@@ -339,25 +487,11 @@ impl PublicInputProcessor {
         public_inputs: &[Fr],
         builtin_address: ActorId,
     ) -> G1Affine {
-        if (public_inputs.len() + 1) != gamma_abc_g1.len() {
-            panic!("Invalid proof length");
-        }
-
         let mut g_ic = gamma_abc_g1[0].into_group();
 
-        let bases: ArkScale<Vec<G1Affine>> = gamma_abc_g1[1..].to_vec().into();
-        let scalars: ArkScale<Vec<<G1 as Group>::ScalarField>> = public_inputs.to_vec().into();
-
-        let msm_result_bytes = PairingOperations::multi_scalar_mul_g1(
-            bases.encode(),
-            scalars.encode(),
-            builtin_address,
-        )
-        .await;
-
-        let msm_result_affine = ArkScaleProjective::<G1>::decode(&mut msm_result_bytes.as_slice())
-            .expect("Deserialization failed: MSM result")
-            .0;
+        let msm_result_affine =
+            PairingOperations::msm_g1_affine(&gamma_abc_g1[1..], public_inputs, builtin_address)
+                .await;
 
         g_ic.add_assign(msm_result_affine);
         g_ic.into()
@@ -368,6 +502,50 @@ impl PublicInputProcessor {
 // Utility Modules
 // ================================================================================================
 
+/// Derives per-instance batch weights via Fiat-Shamir
+struct BatchWeights;
+
+impl BatchWeights {
+    /// Derives one 128-bit non-zero weight per instance.
+    ///
+    /// The transcript covers every proof and public input of the batch, so a
+    /// prover cannot pick proofs after learning the weights.
+    fn derive(instances: &[VerificationVariables]) -> Vec<Fr> {
+        let mut transcript = Blake2b512::new();
+        transcript.update(BATCH_WEIGHTS_DOMAIN);
+        transcript.update((instances.len() as u64).to_le_bytes());
+
+        for instance in instances {
+            let proof = &instance.proof_bytes;
+            for bytes in [&proof.a, &proof.b, &proof.c] {
+                Self::absorb(&mut transcript, bytes);
+            }
+            transcript.update((instance.public_input.len() as u64).to_le_bytes());
+            for input in &instance.public_input {
+                Self::absorb(&mut transcript, input);
+            }
+        }
+
+        let seed = transcript.finalize();
+
+        (0..instances.len() as u64)
+            .map(|index| {
+                let mut hasher = Blake2b512::new();
+                hasher.update(seed);
+                hasher.update(index.to_le_bytes());
+                let weight = Fr::from_le_bytes_mod_order(&hasher.finalize()[..16]);
+
+                if weight.is_zero() { Fr::ONE } else { weight }
+            })
+            .collect()
+    }
+
+    fn absorb(transcript: &mut Blake2b512, bytes: &[u8]) {
+        transcript.update((bytes.len() as u64).to_le_bytes());
+        transcript.update(bytes);
+    }
+}
+
 /// Handles curve point deserialization
 struct CurvePointDeserializer;
 
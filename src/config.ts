@@ -1,5 +1,7 @@
 export const SUITS = ["hearts", "diamonds", "clubs", "spades"];
 export const RANKS = ["2","3","4","5","6","7","8","9","10","J","Q","K","A"];
+// short-deck (6+) lobbies deal from the 36 cards of six and up
+export const SHORT_DECK_RANKS = RANKS.slice(4);
 
 export const q = BigInt("52435875175126190479447740508185965837690552500527637822603658699938581184513");
 export const a = -5n;
@@ -2,22 +2,27 @@ import { CipherCard, ECPoint } from "../types.js";
 import { ecPointToHexLE } from "../utils/ec.js";
 import { cpProofToBytes } from "../utils/proof.js";
 import { toCipherCards, findCardByPoint } from "../utils/cards.js";
-import { scalarMul, cpProve, cpVerify, projectiveAdd } from "zk-shuffle-proof";
+import { hexToBytes } from "../utils/bytes.js";
+import { scalarMul, cpProve, cpVerify, projectiveAdd, decryptionTranscript } from "zk-shuffle-proof";
 import { decodeAddress } from "@gear-js/api";
 
 export async function partialDecrypt({
   program, players, playerHands, F, a, d, base, keyrings
 }: any) {
+  const lobbyId = hexToBytes(program.programId);
+  const round: bigint = await program.poker.round();
   for (let i=0;i<players.length;i++) {
     const partialDecs: any[] = [];
     for (let j=0;j<players.length;j++) {
       if (i===j) continue;
-      for (let k=0;k<2;k++) {
+      const owner = hexToBytes(decodeAddress(keyrings[j].address));
+      for (let k=0;k<playerHands[j].length;k++) {
         const c0 = playerHands[j][k].c0;
         const skC0 = scalarMul(F, a, d, c0, players[i].sk);
         const delta: ECPoint = { X: F.neg(skC0.X), Y: skC0.Y, Z: skC0.Z };
-        const proof = cpProve(F, a, d, base, players[i].pk, c0, skC0, players[i].sk);
-        if (!cpVerify(F, a, d, base, players[i].pk, c0, skC0, proof)) throw new Error("Invalid CP proof");
+        const transcript = decryptionTranscript(lobbyId, BigInt(round), { Hole: { owner, index: k } });
+        const proof = cpProve(F, a, d, base, players[i].pk, c0, skC0, players[i].sk, transcript);
+        if (!cpVerify(F, a, d, base, players[i].pk, c0, skC0, proof, transcript)) throw new Error("Invalid CP proof");
         partialDecs.push({ c0: ecPointToHexLE(c0), delta_c0: ecPointToHexLE(delta), proof: cpProofToBytes(proof) });
       }
     }
@@ -34,7 +39,7 @@ export async function finalDecryptAndShow({
   for (let i=0;i<players.length;i++) {
     const raw = await program.poker.playerCards(decodeAddress(keyrings[i].address));
     const cards: CipherCard[] = toCipherCards(raw);
-    for (let k=0;k<2;k++) {
+    for (let k=0;k<cards.length;k++) {
       const c0 = cards[k].c0, c1 = cards[k].c1;
       const skC0 = scalarMul(F, a, d, c0, players[i].sk);
       const delta: ECPoint = { X: F.neg(skC0.X), Y: skC0.Y, Z: skC0.Z };
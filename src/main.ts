@@ -10,11 +10,13 @@ import { F1Field } from "ffjavascript";
 import * as snarkjs from "snarkjs";
 import { groth16 } from "snarkjs";
 
-import { initDeck, keyGen, projectiveAdd } from "zk-shuffle-proof";
+import { initDeck, keyGen, projectiveAdd, skProve } from "zk-shuffle-proof";
 import { shuffleDeckWithProofs } from "./game/shuffle.js";
 import { dealHands } from "./game/deal.js";
 import { partialDecrypt, finalDecryptAndShow } from "./game/decrypt.js";
 import { ecPointToNumberArrays } from "./utils/ec.js";
+import { skProofToBytes } from "./utils/proof.js";
+import { hexToBytes } from "./utils/bytes.js";
 import { q, a, d, basePoint } from "./config.js";
 
 
@@ -59,7 +61,15 @@ async function main() {
     small_blind: 10,
     big_blind: 100,
     starting_bank: 100,
-    time_per_move_ms: 1_000_000
+    time_per_move_ms: 1_000_000,
+    betting_structure: null,
+    game_mode: { Cash: null },
+    ante: 0,
+    allow_straddle: false,
+    max_players: 9,
+    min_players_to_start: 2,
+    game_variant: { Holdem: null },
+    hi_lo: false
   };
   const sessionConfig = {
     gas_to_delete_session: 20_000_000_000,
@@ -68,8 +78,11 @@ async function main() {
   };
   const zkProgramId = "0x7e2826b2b6747324efc1b2b63ae8ba144f74b6af0d1d8dbaa65e3a1e0b4f0d5d"; // твой ID
   const pokerCode = readFileSync(resolve("./contracts/target/wasm32-gear/release/poker.opt.wasm"));
+  // the admin deploys the lobby, so the key proof is bound to the admin's address twice
+  const adminId = hexToBytes(decodeAddress(keyrings[0].address));
+  const adminPkProof = skProofToBytes(skProve(F, a, d, basePoint, players[0].pk, players[0].sk, adminId, adminId));
   const pokerCtor = await pokerProgram
-    .newCtorFromCode(pokerCode, gameConfig, sessionConfig, ptsProgram.programId, playerPks[0], null, zkProgramId)
+    .newCtorFromCode(pokerCode, gameConfig, sessionConfig, ptsProgram.programId, playerPks[0], adminPkProof, null, zkProgramId)
     .withAccount(keyrings[0]).calculateGas();
   await pokerCtor.withGas(200000000000n).signAndSend();
   console.log(`\nPoker deployed: ${pokerProgram.programId}`);
@@ -85,7 +98,11 @@ async function main() {
     console.log(`\nGet accural message sent.`);
     console.log(`\nPTS replied: \n\t${JSON.stringify(await accuralR())}`);
 
-    const regB = await pokerProgram.poker.register("Player", playerPks[i], null).withAccount(keyrings[i]).calculateGas();
+    const pkProof = skProofToBytes(skProve(
+      F, a, d, basePoint, players[i].pk, players[i].sk,
+      hexToBytes(decodeAddress(keyrings[i].address)), hexToBytes(pokerProgram.programId)
+    ));
+    const regB = await pokerProgram.poker.register("Player", playerPks[i], pkProof, null, null).withAccount(keyrings[i]).calculateGas();
     const regR = (await regB.withGas(200000000000n).signAndSend()).response;
     console.log(`\nRegister message sent.`);
     console.log(`\nPoker replied: \n\t${JSON.stringify(await regR())}`);
@@ -107,7 +124,7 @@ async function main() {
   const { deck: shuffledDeck, cardMap } = await shuffleDeckWithProofs({
     players, numCards, F, a, d, base: basePoint, aggKey, deck,
     encryptWasmFile, encryptZkeyFile, encryptVkey,
-    groth16, snarkjs, program: pokerProgram, adminKeyring: keyrings[0]
+    groth16, snarkjs, program: pokerProgram, keyrings
   });
 
   const hands = dealHands(shuffledDeck, numPlayers);
@@ -9,8 +9,46 @@ declare global {
     big_blind: number | string | bigint;
     starting_bank: number | string | bigint;
     time_per_move_ms: number | string | bigint;
+    betting_structure: BettingStructure | null;
+    game_mode: GameMode;
+    ante: number | string | bigint;
+    allow_straddle: boolean;
+    max_players: number;
+    min_players_to_start: number;
+    game_variant: GameVariant;
+    hi_lo: boolean;
   }
 
+  export type BettingStructure = 
+    | { NoLimit: null }
+    | { PotLimit: null }
+    | { FixedLimit: { small_bet: number | string | bigint; big_bet: number | string | bigint; max_raises: number } };
+
+  export type GameMode = 
+    | { Cash: null }
+    | { SitAndGo: TournamentConfig };
+
+  export interface TournamentConfig {
+    blind_levels: Array<BlindLevel>;
+    level_duration: LevelDuration;
+    payouts: `0x${string}`;
+  }
+
+  export interface BlindLevel {
+    small_blind: number | string | bigint;
+    big_blind: number | string | bigint;
+  }
+
+  export type LevelDuration = 
+    | { Hands: number }
+    | { Millis: number | string | bigint };
+
+  export type GameVariant = 
+    | { Holdem: null }
+    | { Omaha: null }
+    | { ShortDeck: null }
+    | { FiveCardDraw: { max_discards: number } };
+
   export interface SessionConfig {
     gas_to_delete_session: number | string | bigint;
     minimum_session_duration_ms: number | string | bigint;
@@ -23,6 +61,11 @@ declare global {
     z: Array<number>;
   }
 
+  export interface SchnorrProofBytes {
+    a: Array<`0x${string}`>;
+    z: `0x${string}`;
+  }
+
   export interface SignatureInfo {
     signature_data: SignatureData;
     signature: `0x${string}` | null;
@@ -80,7 +123,6 @@ declare global {
   export interface TurnManagerForActorId {
     active_ids: Array<ActorId>;
     turn_index: number | string | bigint;
-    first_index: number;
   }
 
   export interface BettingStage {
@@ -88,12 +130,23 @@ declare global {
     last_active_time: number | string | bigint | null;
     current_bet: number | string | bigint;
     acted_players: Array<ActorId>;
+    last_raise: number | string | bigint;
+    raise_closed: Array<ActorId>;
+    raises: number;
+    last_aggressor: ActorId | null;
   }
 
   export interface Participant {
     name: string;
     balance: number | string | bigint;
     pk: ZkPublicKey;
+    seat: number;
+  }
+
+  export interface Positions {
+    button: number;
+    small_blind: number;
+    big_blind: number;
   }
 
   export interface Card {
@@ -105,15 +158,28 @@ declare global {
 
   export type Status = 
     | { Registration: null }
-    | { WaitingShuffleVerification: null }
+    | { WaitingShuffle: { turn: ActorId } }
     | { WaitingStart: null }
     | { WaitingPartialDecryptionsForPlayersCards: null }
     | { Play: { stage: Stage } }
     | { WaitingForCardsToBeDisclosed: null }
     | { WaitingForAllTableCardsToBeDisclosed: null }
-    | { Finished: { pots: Array<[number | string | bigint, Array<ActorId>]> } };
+    | { Finished: { pots: Array<[number | string | bigint, Array<ActorId>, PotShare]> } };
 
-  export type Stage = "PreFlop" | "WaitingTableCardsAfterPreFlop" | "Flop" | "WaitingTableCardsAfterFlop" | "Turn" | "WaitingTableCardsAfterTurn" | "River";
+  export type Stage = "PreFlop" | "WaitingTableCardsAfterPreFlop" | "Flop" | "WaitingTableCardsAfterFlop" | "Turn" | "WaitingTableCardsAfterTurn" | "River" | "PreDraw" | "Draw" | "WaitingDrawnCards" | "PostDraw";
+
+  export type PotShare = "Whole" | "High" | "Low";
+
+  export interface TournamentState {
+    entrants: number;
+    prize_pool: number | string | bigint;
+    level: number;
+    hands_at_level: number;
+    level_started_at: number | string | bigint;
+    eliminated: Array<ActorId>;
+    standings: Array<[ActorId, number | string | bigint]>;
+    prizes_paid: boolean;
+  }
 
   export interface SessionData {
     key: ActorId;
@@ -121,4 +187,8 @@ declare global {
     allowed_actions: Array<ActionsForSession>;
     expires_at_block: number;
   }
+
+  export type PokerError = "NoValidSession" | "SessionExpired" | "SessionNotApproved" | "AccessDenied" | "WrongStatus" | "WrongStage" | "AlreadyRegistered" | "NotRegistered" | "LobbyIsFull" | "InvalidSeat" | "SeatTaken" | "NotEnoughParticipants" | "NoSuchPlayer" | "InvalidSecretKeyProof" | "DeadlineNotReached" | "InvalidConfig" | "InvalidTournament" | "NotYourTurn" | "WrongAction" | "CannotCheck" | "RaiseTooLow" | "RaiseNotAllowed" | "RaiseTooHigh" | "RaiseCapReached" | "StraddleNotAllowed" | "InvalidDiscard" | "InvalidPublicInput" | "PublicKeyMismatch" | "ShuffleNonceMismatch" | "InitialDeckMismatch" | "ShuffleChainDiscontinuity" | "AlreadySubmitted" | "WrongNumberOfDecryptions" | "CardNotFound" | "DecryptionVerificationFailed" | "CardDecryptionFailed" | "NotEnoughCards" | "PtsTransferFailed" | "ZkVerificationFailed" | "FactoryCallFailed";
+
+  export type PtsError = "TimeHasNotExpired" | "AccessDenied" | "TransferToSelf" | "AccountNotFound" | "LowBalance";
 };
\ No newline at end of file
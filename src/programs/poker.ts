@@ -10,26 +10,37 @@ export class SailsProgram {
 
   constructor(public api: GearApi, programId?: `0x${string}`) {
     const types: Record<string, any> = {
-      GameConfig: {"admin_id":"[u8;32]","admin_name":"String","lobby_name":"String","small_blind":"u128","big_blind":"u128","starting_bank":"u128","time_per_move_ms":"u64"},
+      GameConfig: {"admin_id":"[u8;32]","admin_name":"String","lobby_name":"String","small_blind":"u128","big_blind":"u128","starting_bank":"u128","time_per_move_ms":"u64","betting_structure":"Option<BettingStructure>","game_mode":"GameMode","ante":"u128","allow_straddle":"bool","max_players":"u8","min_players_to_start":"u8","game_variant":"GameVariant","hi_lo":"bool"},
+      BettingStructure: {"_enum":{"NoLimit":"Null","PotLimit":"Null","FixedLimit":{"small_bet":"u128","big_bet":"u128","max_raises":"u32"}}},
+      GameMode: {"_enum":{"Cash":"Null","SitAndGo":"TournamentConfig"}},
+      TournamentConfig: {"blind_levels":"Vec<BlindLevel>","level_duration":"LevelDuration","payouts":"Vec<u8>"},
+      BlindLevel: {"small_blind":"u128","big_blind":"u128"},
+      LevelDuration: {"_enum":{"Hands":"u32","Millis":"u64"}},
+      GameVariant: {"_enum":{"Holdem":"Null","Omaha":"Null","ShortDeck":"Null","FiveCardDraw":{"max_discards":"u8"}}},
       SessionConfig: {"gas_to_delete_session":"u64","minimum_session_duration_ms":"u64","ms_per_block":"u64"},
       ZkPublicKey: {"x":"[u8; 32]","y":"[u8; 32]","z":"[u8; 32]"},
       SignatureInfo: {"signature_data":"SignatureData","signature":"Option<Vec<u8>>"},
       SignatureData: {"key":"[u8;32]","duration":"u64","allowed_actions":"Vec<ActionsForSession>"},
       ActionsForSession: {"_enum":["AllActions"]},
       PartialDec: {"c0":"[Vec<u8>; 3]","delta_c0":"[Vec<u8>; 3]","proof":"ChaumPedersenProofBytes"},
+      SchnorrProofBytes: {"a":"[Vec<u8>; 3]","z":"Vec<u8>"},
       ChaumPedersenProofBytes: {"a":"[Vec<u8>; 3]","b":"[Vec<u8>; 3]","z":"Vec<u8>"},
       EncryptedCard: {"c0":"[Vec<u8>; 3]","c1":"[Vec<u8>; 3]"},
       VerificationVariables: {"proof_bytes":"ProofBytes","public_input":"Vec<Vec<u8>>"},
       ProofBytes: {"a":"Vec<u8>","b":"Vec<u8>","c":"Vec<u8>"},
       Action: {"_enum":{"Fold":"Null","Call":"Null","Raise":{"bet":"u128"},"Check":"Null","AllIn":"Null"}},
-      TurnManagerForActorId: {"active_ids":"Vec<[u8;32]>","turn_index":"u64","first_index":"u16"},
-      BettingStage: {"turn":"[u8;32]","last_active_time":"Option<u64>","current_bet":"u128","acted_players":"Vec<[u8;32]>"},
-      Participant: {"name":"String","balance":"u128","pk":"ZkPublicKey"},
+      TurnManagerForActorId: {"active_ids":"Vec<[u8;32]>","turn_index":"u64"},
+      BettingStage: {"turn":"[u8;32]","last_active_time":"Option<u64>","current_bet":"u128","acted_players":"Vec<[u8;32]>","last_raise":"u128","raise_closed":"Vec<[u8;32]>","raises":"u32","last_aggressor":"Option<[u8;32]>"},
+      Participant: {"name":"String","balance":"u128","pk":"ZkPublicKey","seat":"u8"},
+      Positions: {"button":"u8","small_blind":"u8","big_blind":"u8"},
       Card: {"value":"u8","suit":"Suit"},
       Suit: {"_enum":["Spades","Hearts","Diamonds","Clubs"]},
-      Status: {"_enum":{"Registration":"Null","WaitingShuffleVerification":"Null","WaitingStart":"Null","WaitingPartialDecryptionsForPlayersCards":"Null","Play":{"stage":"Stage"},"WaitingForCardsToBeDisclosed":"Null","WaitingForAllTableCardsToBeDisclosed":"Null","Finished":{"pots":"Vec<(u128, Vec<[u8;32]>)>"}}},
-      Stage: {"_enum":["PreFlop","WaitingTableCardsAfterPreFlop","Flop","WaitingTableCardsAfterFlop","Turn","WaitingTableCardsAfterTurn","River"]},
+      Status: {"_enum":{"Registration":"Null","WaitingShuffle":{"turn":"[u8;32]"},"WaitingStart":"Null","WaitingPartialDecryptionsForPlayersCards":"Null","Play":{"stage":"Stage"},"WaitingForCardsToBeDisclosed":"Null","WaitingForAllTableCardsToBeDisclosed":"Null","Finished":{"pots":"Vec<(u128, Vec<[u8;32]>, PotShare)>"}}},
+      Stage: {"_enum":["PreFlop","WaitingTableCardsAfterPreFlop","Flop","WaitingTableCardsAfterFlop","Turn","WaitingTableCardsAfterTurn","River","PreDraw","Draw","WaitingDrawnCards","PostDraw"]},
+      PotShare: {"_enum":["Whole","High","Low"]},
+      TournamentState: {"entrants":"u32","prize_pool":"u128","level":"u32","hands_at_level":"u32","level_started_at":"u64","eliminated":"Vec<[u8;32]>","standings":"Vec<([u8;32], u128)>","prizes_paid":"bool"},
       SessionData: {"key":"[u8;32]","expires":"u64","allowed_actions":"Vec<ActionsForSession>","expires_at_block":"u32"},
+      PokerError: {"_enum":["NoValidSession","SessionExpired","SessionNotApproved","AccessDenied","WrongStatus","WrongStage","AlreadyRegistered","NotRegistered","LobbyIsFull","InvalidSeat","SeatTaken","NotEnoughParticipants","NoSuchPlayer","InvalidSecretKeyProof","DeadlineNotReached","InvalidConfig","InvalidTournament","NotYourTurn","WrongAction","CannotCheck","RaiseTooLow","RaiseNotAllowed","RaiseTooHigh","RaiseCapReached","StraddleNotAllowed","InvalidDiscard","InvalidPublicInput","PublicKeyMismatch","ShuffleNonceMismatch","InitialDeckMismatch","ShuffleChainDiscontinuity","AlreadySubmitted","WrongNumberOfDecryptions","CardNotFound","DecryptionVerificationFailed","CardDecryptionFailed","NotEnoughCards","PtsTransferFailed","ZkVerificationFailed","FactoryCallFailed"]},
     }
 
     this.registry = new TypeRegistry();
@@ -48,15 +59,15 @@ export class SailsProgram {
     return this._program.id;
   }
 
-  newCtorFromCode(code: Uint8Array | Buffer | HexString, config: GameConfig, session_config: SessionConfig, pts_actor_id: ActorId, pk: ZkPublicKey, session_for_admin: SignatureInfo | null, zk_verification_id: ActorId): TransactionBuilder<null> {
+  newCtorFromCode(code: Uint8Array | Buffer | HexString, config: GameConfig, session_config: SessionConfig, pts_actor_id: ActorId, pk: ZkPublicKey, pk_proof: SchnorrProofBytes, session_for_admin: SignatureInfo | null, zk_verification_id: ActorId): TransactionBuilder<null> {
     const builder = new TransactionBuilder<null>(
       this.api,
       this.registry,
       'upload_program',
       undefined,
       'New',
-      [config, session_config, pts_actor_id, pk, session_for_admin, zk_verification_id],
-      '(GameConfig, SessionConfig, [u8;32], ZkPublicKey, Option<SignatureInfo>, [u8;32])',
+      [config, session_config, pts_actor_id, pk, pk_proof, session_for_admin, zk_verification_id],
+      '(GameConfig, SessionConfig, [u8;32], ZkPublicKey, SchnorrProofBytes, Option<SignatureInfo>, [u8;32])',
       'String',
       code,
       async (programId) =>  {
@@ -66,15 +77,15 @@ export class SailsProgram {
     return builder;
   }
 
-  newCtorFromCodeId(codeId: `0x${string}`, config: GameConfig, session_config: SessionConfig, pts_actor_id: ActorId, pk: ZkPublicKey, session_for_admin: SignatureInfo | null, zk_verification_id: ActorId) {
+  newCtorFromCodeId(codeId: `0x${string}`, config: GameConfig, session_config: SessionConfig, pts_actor_id: ActorId, pk: ZkPublicKey, pk_proof: SchnorrProofBytes, session_for_admin: SignatureInfo | null, zk_verification_id: ActorId) {
     const builder = new TransactionBuilder<null>(
       this.api,
       this.registry,
       'create_program',
       undefined,
       'New',
-      [config, session_config, pts_actor_id, pk, session_for_admin, zk_verification_id],
-      '(GameConfig, SessionConfig, [u8;32], ZkPublicKey, Option<SignatureInfo>, [u8;32])',
+      [config, session_config, pts_actor_id, pk, pk_proof, session_for_admin, zk_verification_id],
+      '(GameConfig, SessionConfig, [u8;32], ZkPublicKey, SchnorrProofBytes, Option<SignatureInfo>, [u8;32])',
       'String',
       codeId,
       async (programId) =>  {
@@ -88,9 +99,9 @@ export class SailsProgram {
 export class Poker {
   constructor(private _program: SailsProgram) {}
 
-  public cancelGame(session_for_account: ActorId | null): TransactionBuilder<null> {
+  public cancelGame(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -98,7 +109,7 @@ export class Poker {
       'CancelGame',
       session_for_account,
       'Option<[u8;32]>',
-      'Null',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -106,16 +117,16 @@ export class Poker {
   /**
    * Cancels player registration and refunds their balance via PTS contract.
    * 
-   * Panics if:
-   * - current status is invalid for cancellation;
+   * Fails if:
+   * - current status is invalid for cancellation or the sit-and-go has started;
    * - caller is not a registered player.
    * 
    * Sends a transfer request to PTS contract to return points to the player.
    * Removes player data and emits `RegistrationCanceled` event on success.
   */
-  public cancelRegistration(session_for_account: ActorId | null): TransactionBuilder<null> {
+  public cancelRegistration(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -123,14 +134,17 @@ export class Poker {
       'CancelRegistration',
       session_for_account,
       'Option<[u8;32]>',
-      'Null',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
 
-  public cardDisclosure(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<null> {
+  /**
+   * Shows the player's hole cards at the showdown, when it's their turn in the showdown order.
+  */
+  public cardDisclosure(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -138,7 +152,41 @@ export class Poker {
       'CardDisclosure',
       [player_decryptions, session_for_account],
       '(Vec<PartialDec>, Option<[u8;32]>)',
-      'Null',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Ends a hand stalled in the shuffle, decryption or disclosure phase.
+   * 
+   * Can be called by anyone once the phase deadline has passed. The players who
+   * haven't made their submission forfeit their stake (balance and bets in the hand)
+   * to the remaining participants and leave the table. Bets of the other players
+   * are refunded and the game returns to Registration. A sit-and-go knocks the
+   * stalled players out and goes on with the next hand.
+   * 
+   * Fails if:
+   * - wrong status (not a shuffle, decryption or disclosure phase)
+   * - the phase deadline hasn't passed yet
+   * 
+   * At the showdown the player who hasn't shown their cards in time mucks them instead,
+   * and the next one gets a new deadline.
+   * 
+   * Emits PlayerTimedOut for every stalled player, then GameCanceled
+   * (CardsMucked at the showdown).
+  */
+  public claimTimeout(): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'ClaimTimeout',
+      undefined,
+      undefined,
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -146,9 +194,10 @@ export class Poker {
   /**
    * Admin-only function to forcibly remove a player and refund their balance.
    * 
-   * Panics if:
+   * Fails if:
    * - caller is not admin or tries to delete themselves
-   * - wrong game status (not Registration/WaitingShuffleVerification)
+   * - wrong game status (not Registration/WaitingShuffle/WaitingStart)
+   * or the sit-and-go has started
    * - player doesn't exist
    * 
    * Performs:
@@ -157,9 +206,9 @@ export class Poker {
    * 3. Resets status to Registration
    * 4. Emits PlayerDeleted event
   */
-  public deletePlayer(player_id: ActorId, session_for_account: ActorId | null): TransactionBuilder<null> {
+  public deletePlayer(player_id: ActorId, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -167,7 +216,43 @@ export class Poker {
       'DeletePlayer',
       [player_id, session_for_account],
       '([u8;32], Option<[u8;32]>)',
-      'Null',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Discards the cards at the given positions of the caller's hand in a draw game.
+   * 
+   * The cards are discarded without being decrypted, so their values stay hidden,
+   * and they are replaced from the deck once everyone has drawn. An empty list
+   * stands pat. The players draw in turn from the first seat after the button.
+   * 
+   * Only the values are hidden: the positions are sent in the clear and kept in
+   * the state until the hand ends, and `CardsDiscarded` announces the count. The
+   * others learn how many cards were drawn and which hole card slots they went
+   * to, so the cards kept can be told from the drawn ones at the showdown.
+   * 
+   * Fails if:
+   * - the hand is not at the draw;
+   * - it's not the player's turn to draw;
+   * - a position is repeated or out of the hand, or too many cards are discarded;
+   * - the deck has not enough cards left to replace the discards.
+   * 
+   * Emits `CardsDiscarded`, and `CardsDealtToPlayers` with the replacements once
+   * everyone has drawn.
+  */
+  public draw(discards: Array<number>, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'Draw',
+      [discards, session_for_account],
+      '(Vec<u8>, Option<[u8;32]>)',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -175,9 +260,10 @@ export class Poker {
   /**
    * Admin-only function to terminate the lobby and refund all players.
    * 
-   * Panics if:
+   * Fails if:
    * - caller is not admin
-   * - wrong game status (not Registration/WaitingShuffleVerification/Finished/WaitingStart)
+   * - wrong game status (not Registration/WaitingShuffle/Finished/WaitingStart)
+   * - the sit-and-go is over, but the prizes aren't paid yet
    * 
    * Performs:
    * 1. Batch transfer of all player balances via PTS contract
@@ -186,9 +272,9 @@ export class Poker {
    * 
    * WARNING: Irreversible operation
   */
-  public kill(session_for_account: ActorId | null): TransactionBuilder<null> {
+  public kill(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -196,7 +282,119 @@ export class Poker {
       'Kill',
       session_for_account,
       'Option<[u8;32]>',
-      'Null',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Gives up the player's claim on all pots without showing the cards.
+   * 
+   * Fails if:
+   * - status is not `WaitingForCardsToBeDisclosed`;
+   * - it's not the player's turn to show or muck.
+   * 
+   * Emits `CardsMucked`, and `Finished` if the hand is over.
+  */
+  public muck(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'Muck',
+      session_for_account,
+      'Option<[u8;32]>',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Pays the prize pool of a finished sit-and-go by the payout table.
+   * 
+   * The lobby sends it to itself when the tournament is over; anyone can
+   * call it again if the transfer has failed.
+   * 
+   * Fails if:
+   * - the lobby doesn't run a sit-and-go or it isn't over yet
+   * - the prizes are already paid
+   * - the PTS transfer fails
+   * 
+   * Emits PrizesPaid
+  */
+  public payPrizes(): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'PayPrizes',
+      undefined,
+      undefined,
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Posts a straddle of two big blinds before the cards are dealt.
+   * 
+   * Only the player after the big blind can straddle, if the lobby allows it.
+   * The straddle is a raise: preflop action starts with the next player
+   * and the straddler acts last.
+   * 
+   * Fails if:
+   * - wrong status (the hand hasn't started or the preflop betting is already on)
+   * - it's not the caller's turn after the blinds
+   * - straddles are disabled, the caller has posted a blind,
+   * already straddled or can't cover the straddle
+   * 
+   * Emits StraddleIsSet
+  */
+  public postStraddle(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'PostStraddle',
+      session_for_account,
+      'Option<[u8;32]>',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Reveals the rest of the board after a hand that ended before the river.
+   * 
+   * Every player submits the decryption shares of the undealt table cards, as in
+   * `submit_table_partial_decryptions`. The cards are added to the revealed table cards
+   * and don't change the payouts. In a sit-and-go the next hand is dealt right away,
+   * so there's no rabbit hunt there.
+   * 
+   * Fails if:
+   * - status is not `Finished`, or the whole board is revealed;
+   * - the player is not registered or has already submitted;
+   * - a decryption share doesn't verify.
+   * 
+   * Emits `TablePartialDecryptionsSubmited`, and `RabbitHunted` once the cards are revealed.
+  */
+  public rabbitHunt(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'RabbitHunt',
+      [player_decryptions, session_for_account],
+      '(Vec<PartialDec>, Option<[u8;32]>)',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -204,37 +402,43 @@ export class Poker {
   /**
    * Registers a player by sending a transfer request to the PTS contract (starting_bank points).
    * 
-   * Panics if:
+   * The player takes `seat`, or the first free one if it's `None`.
+   * 
+   * Fails if:
    * - status is not `Registration`;
-   * - player is already registered.
+   * - player is already registered;
+   * - the sit-and-go has already started;
+   * - the seat doesn't exist or is taken, or there are no free seats;
+   * - `pk_proof` does not prove knowledge of the secret key for `pk`
+   * (bound to the player and this lobby).
    * 
    * Sends a message to the PTS contract (pts_actor_id) to transfer points to this contract.
    * On success, updates participant data and emits a `Registered` event.
   */
-  public register(player_name: string, pk: ZkPublicKey, session_for_account: ActorId | null): TransactionBuilder<null> {
+  public register(player_name: string, pk: ZkPublicKey, pk_proof: SchnorrProofBytes, seat: number | null, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
       'Poker',
       'Register',
-      [player_name, pk, session_for_account],
-      '(String, ZkPublicKey, Option<[u8;32]>)',
-      'Null',
+      [player_name, pk, pk_proof, seat, session_for_account],
+      '(String, ZkPublicKey, SchnorrProofBytes, Option<u8>, Option<[u8;32]>)',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
 
   /**
    * Restarts the game, resetting status and refunding bets (if not Finished).
-   * Panics if caller is not admin.
-   * Resets game to WaitingShuffleVerification (if full) or Registration status.
+   * Fails if caller is not admin or the lobby runs a sit-and-go, which restarts on its own.
+   * Resets game to Registration status.
    * Emits GameRestarted event with new status.
   */
-  public restartGame(session_for_account: ActorId | null): TransactionBuilder<null> {
+  public restartGame(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -242,22 +446,94 @@ export class Poker {
       'RestartGame',
       session_for_account,
       'Option<[u8;32]>',
-      'Null',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
 
-  public shuffleDeck(encrypted_deck: Array<EncryptedCard>, instances: Array<VerificationVariables>): TransactionBuilder<null> {
+  /**
+   * Agrees to run the rest of the board twice when everyone is all-in.
+   * 
+   * Once every player left in the hand has agreed, a second board is dealt from the rest
+   * of the deck after the first one is revealed, and each board wins half of every pot.
+   * 
+   * Fails if:
+   * - status is not `WaitingForAllTableCardsToBeDisclosed`, or the first board is revealed;
+   * - the player has folded;
+   * - the player has already agreed;
+   * - there are not enough cards left in the deck.
+   * 
+   * Emits `RunItTwiceAgreed`.
+  */
+  public runItTwice(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
       'Poker',
-      'ShuffleDeck',
-      [encrypted_deck, instances],
-      '(Vec<EncryptedCard>, Vec<VerificationVariables>)',
-      'Null',
+      'RunItTwice',
+      session_for_account,
+      'Option<[u8;32]>',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Shows the player's hole cards after the hand, e.g. when everyone else has folded.
+   * 
+   * Doesn't change the payouts. In a sit-and-go the next hand is dealt right away,
+   * so there's nothing to show there.
+   * 
+   * Fails if:
+   * - status is not `Finished`;
+   * - the player wasn't dealt cards in the hand;
+   * - a decryption share doesn't verify.
+   * 
+   * Emits `CardsShown`.
+  */
+  public showCards(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'ShowCards',
+      [player_decryptions, session_for_account],
+      '(Vec<PartialDec>, Option<[u8;32]>)',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Submits the caller's shuffle of the deck.
+   * 
+   * Every participant shuffles exactly once, in seat order, each starting from
+   * the deck produced by the previous one, so the final order stays hidden
+   * unless all players collude.
+   * 
+   * Fails if:
+   * - wrong status (not WaitingShuffle) or it's not the caller's turn to shuffle
+   * - the shuffle doesn't continue the chain (wrong key, nonce or input deck)
+   * - the proof is rejected by the zk verification program
+   * 
+   * Emits DeckShuffled, and DeckShuffleComplete once the last player has shuffled
+   * and the cards are dealt.
+  */
+  public submitShuffle(instance: VerificationVariables, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'SubmitShuffle',
+      [instance, session_for_account],
+      '(VerificationVariables, Option<[u8;32]>)',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -265,20 +541,22 @@ export class Poker {
   /**
    * Admin-only function to start the poker game after setup.
    * 
-   * Panics if:
+   * Fails if:
    * - caller is not admin
    * - wrong status (not WaitingStart)
+   * - the sit-and-go is already over
    * 
    * Performs:
-   * 1. Processes small/big blinds (handles all-in cases)
-   * 2. Initializes betting stage
-   * 3. Updates game status and emits GameStarted event
+   * 1. Starts the sit-and-go with the registered players, if the lobby runs one
+   * 2. Processes antes and small/big blinds (handles all-in cases)
+   * 3. Initializes betting stage
+   * 4. Updates game status and emits GameStarted event
    * 
    * Note: Handles edge cases where players can't cover blinds
   */
-  public startGame(session_for_account: ActorId | null): TransactionBuilder<null> {
+  public startGame(session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -286,14 +564,18 @@ export class Poker {
       'StartGame',
       session_for_account,
       'Option<[u8;32]>',
-      'Null',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
 
-  public submitPartialDecryptions(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<null> {
+  /**
+   * Submits the caller's decryption shares of the other players' cards: every dealt card
+   * before the first betting round, and the replacement cards after the draw.
+  */
+  public submitPartialDecryptions(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -301,14 +583,14 @@ export class Poker {
       'SubmitPartialDecryptions',
       [player_decryptions, session_for_account],
       '(Vec<PartialDec>, Option<[u8;32]>)',
-      'Null',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
 
-  public submitTablePartialDecryptions(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<null> {
+  public submitTablePartialDecryptions(player_decryptions: Array<PartialDec>, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -316,7 +598,7 @@ export class Poker {
       'SubmitTablePartialDecryptions',
       [player_decryptions, session_for_account],
       '(Vec<PartialDec>, Option<[u8;32]>)',
-      'Null',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -324,22 +606,23 @@ export class Poker {
   /**
    * Processes player actions during betting rounds.
    * 
-   * Panics if:
+   * Fails if:
    * - Wrong game status
    * - Not player's turn
    * - Invalid action (e.g. check when bet exists)
+   * - Raise smaller than the last bet or raise (the big blind when opening)
+   * - Raise by a player who already acted before a short all-in
    * 
    * Handles:
    * - Fold/Call/Check/Raise/AllIn actions
-   * - Turn timers and skips
    * - Game end conditions (single player left)
    * - Stage transitions
    * 
    * Emits TurnIsMade and NextStage events
   */
-  public turn(action: Action, session_for_account: ActorId | null): TransactionBuilder<null> {
+  public turn(action: Action, session_for_account: ActorId | null): TransactionBuilder<{ ok: null } | { err: PokerError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -347,7 +630,32 @@ export class Poker {
       'Turn',
       [action, session_for_account],
       '(Action, Option<[u8;32]>)',
-      'Null',
+      'Result<Null, PokerError>',
+      this._program.programId
+    );
+  }
+
+  /**
+   * Plays the turn of a player who didn't act in time: checks if possible, folds otherwise.
+   * 
+   * The lobby sends it to itself as a delayed message when each turn starts.
+   * Timers of the turns that are already over carry an old nonce and are ignored.
+   * 
+   * Fails if the caller is not the lobby itself.
+   * 
+   * Emits TurnTimedOut, then the same events as `turn`
+  */
+  public turnTimeout(turn_nonce: number | string | bigint): TransactionBuilder<{ ok: null } | { err: PokerError }> {
+    if (!this._program.programId) throw new Error('Program ID is not set');
+    return new TransactionBuilder<{ ok: null } | { err: PokerError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      'Poker',
+      'TurnTimeout',
+      turn_nonce,
+      'u64',
+      'Result<Null, PokerError>',
       this._program.programId
     );
   }
@@ -457,6 +765,36 @@ export class Poker {
     return result[2].toJSON() as unknown as GameConfig;
   }
 
+  public async decryptionProofVersion(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'DecryptionProofVersion']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, u32)', reply.payload);
+    return result[2].toNumber() as unknown as number;
+  }
+
+  public async drawing(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<ActorId>> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'Drawing']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Vec<[u8;32]>)', reply.payload);
+    return result[2].toJSON() as unknown as Array<ActorId>;
+  }
+
   public async encryptedTableCards(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<EncryptedCard>> {
     const payload = this._program.registry.createType('(String, String)', ['Poker', 'EncryptedTableCards']).toHex();
     const reply = await this._program.api.message.calculateReply({
@@ -502,6 +840,21 @@ export class Poker {
     return result[2].toJSON() as unknown as Array<[ActorId, Participant]>;
   }
 
+  public async phaseDeadline(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number | string | bigint | null> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'PhaseDeadline']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Option<u64>)', reply.payload);
+    return result[2].toJSON() as unknown as number | string | bigint | null;
+  }
+
   public async playerCards(player_id: ActorId, originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<EncryptedCard> | null> {
     const payload = this._program.registry.createType('(String, String, [u8;32])', ['Poker', 'PlayerCards', player_id]).toHex();
     const reply = await this._program.api.message.calculateReply({
@@ -513,10 +866,25 @@ export class Poker {
       at: atBlock,
     });
     throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
-    const result = this._program.registry.createType('(String, String, Option<[EncryptedCard; 2]>)', reply.payload);
+    const result = this._program.registry.createType('(String, String, Option<Vec<EncryptedCard>>)', reply.payload);
     return result[2].toJSON() as unknown as Array<EncryptedCard> | null;
   }
 
+  public async positions(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Positions | null> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'Positions']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Option<Positions>)', reply.payload);
+    return result[2].toJSON() as unknown as Positions | null;
+  }
+
   public async ptsActorId(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<ActorId> {
     const payload = this._program.registry.createType('(String, String)', ['Poker', 'PtsActorId']).toHex();
     const reply = await this._program.api.message.calculateReply({
@@ -532,7 +900,7 @@ export class Poker {
     return result[2].toJSON() as unknown as ActorId;
   }
 
-  public async revealedPlayers(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, [Card, Card]]>> {
+  public async revealedPlayers(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, Array<Card>]>> {
     const payload = this._program.registry.createType('(String, String)', ['Poker', 'RevealedPlayers']).toHex();
     const reply = await this._program.api.message.calculateReply({
       destination: this._program.programId,
@@ -543,8 +911,8 @@ export class Poker {
       at: atBlock,
     });
     throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
-    const result = this._program.registry.createType('(String, String, Vec<([u8;32], (Card, Card))>)', reply.payload);
-    return result[2].toJSON() as unknown as Array<[ActorId, [Card, Card]]>;
+    const result = this._program.registry.createType('(String, String, Vec<([u8;32], Vec<Card>)>)', reply.payload);
+    return result[2].toJSON() as unknown as Array<[ActorId, Array<Card>]>;
   }
 
   public async revealedTableCards(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<Card>> {
@@ -577,6 +945,81 @@ export class Poker {
     return result[2].toBigInt() as unknown as bigint;
   }
 
+  public async runItTwiceAgreed(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<ActorId>> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'RunItTwiceAgreed']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Vec<[u8;32]>)', reply.payload);
+    return result[2].toJSON() as unknown as Array<ActorId>;
+  }
+
+  public async seats(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[number, ActorId]>> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'Seats']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Vec<(u8, [u8;32])>)', reply.payload);
+    return result[2].toJSON() as unknown as Array<[number, ActorId]>;
+  }
+
+  public async showdownOrder(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<ActorId>> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'ShowdownOrder']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Vec<[u8;32]>)', reply.payload);
+    return result[2].toJSON() as unknown as Array<ActorId>;
+  }
+
+  public async shuffleNonce(player_id: ActorId, originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<`0x${string}`> {
+    const payload = this._program.registry.createType('(String, String, [u8;32])', ['Poker', 'ShuffleNonce', player_id]).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Vec<u8>)', reply.payload);
+    return result[2].toJSON() as unknown as `0x${string}`;
+  }
+
+  public async standings(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, number | string | bigint]>> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'Standings']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Vec<([u8;32], u128)>)', reply.payload);
+    return result[2].toJSON() as unknown as Array<[ActorId, number | string | bigint]>;
+  }
+
   public async status(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Status> {
     const payload = this._program.registry.createType('(String, String)', ['Poker', 'Status']).toHex();
     const reply = await this._program.api.message.calculateReply({
@@ -607,6 +1050,21 @@ export class Poker {
     return result[2].toJSON() as unknown as Array<EncryptedCard>;
   }
 
+  public async tournament(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<TournamentState | null> {
+    const payload = this._program.registry.createType('(String, String)', ['Poker', 'Tournament']).toHex();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: originAddress ? decodeAddress(originAddress) : ZERO_ADDRESS,
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock,
+    });
+    throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
+    const result = this._program.registry.createType('(String, String, Option<TournamentState>)', reply.payload);
+    return result[2].toJSON() as unknown as TournamentState | null;
+  }
+
   public async waitingParticipants(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, Participant]>> {
     const payload = this._program.registry.createType('(String, String)', ['Poker', 'WaitingParticipants']).toHex();
     const reply = await this._program.api.message.calculateReply({
@@ -622,7 +1080,7 @@ export class Poker {
     return result[2].toJSON() as unknown as Array<[ActorId, Participant]>;
   }
 
-  public subscribeToRegisteredEvent(callback: (data: { participant_id: ActorId; pk: ZkPublicKey }) => void | Promise<void>): Promise<() => void> {
+  public subscribeToRegisteredEvent(callback: (data: { participant_id: ActorId; pk: ZkPublicKey; seat: number }) => void | Promise<void>): Promise<() => void> {
     return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
       if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
         return;
@@ -630,7 +1088,7 @@ export class Poker {
 
       const payload = message.payload.toHex();
       if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'Registered') {
-        callback(this._program.registry.createType('(String, String, {"participant_id":"[u8;32]","pk":"ZkPublicKey"})', message.payload)[2].toJSON() as unknown as { participant_id: ActorId; pk: ZkPublicKey });
+        callback(this._program.registry.createType('(String, String, {"participant_id":"[u8;32]","pk":"ZkPublicKey","seat":"u8"})', message.payload)[2].toJSON() as unknown as { participant_id: ActorId; pk: ZkPublicKey; seat: number });
       }
     });
   }
@@ -661,6 +1119,19 @@ export class Poker {
     });
   }
 
+  public subscribeToDeckShuffledEvent(callback: (data: { player_id: ActorId }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'DeckShuffled') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId });
+      }
+    });
+  }
+
   public subscribeToDeckShuffleCompleteEvent(callback: (data: null) => void | Promise<void>): Promise<() => void> {
     return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
       if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
@@ -695,7 +1166,7 @@ export class Poker {
 
       const payload = message.payload.toHex();
       if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'CardsDealtToPlayers') {
-        callback(this._program.registry.createType('(String, String, Vec<([u8;32], [EncryptedCard; 2])>)', message.payload)[2].toJSON() as unknown as Array<[ActorId, Array<EncryptedCard>]>);
+        callback(this._program.registry.createType('(String, String, Vec<([u8;32], Vec<EncryptedCard>)>)', message.payload)[2].toJSON() as unknown as Array<[ActorId, Array<EncryptedCard>]>);
       }
     });
   }
@@ -765,6 +1236,19 @@ export class Poker {
     });
   }
 
+  public subscribeToTurnTimedOutEvent(callback: (data: { player_id: ActorId; action: Action }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'TurnTimedOut') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]","action":"Action"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId; action: Action });
+      }
+    });
+  }
+
   public subscribeToNextStageEvent(callback: (data: Stage) => void | Promise<void>): Promise<() => void> {
     return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
       if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
@@ -778,7 +1262,7 @@ export class Poker {
     });
   }
 
-  public subscribeToFinishedEvent(callback: (data: { pots: Array<[number | string | bigint, Array<ActorId>]> }) => void | Promise<void>): Promise<() => void> {
+  public subscribeToFinishedEvent(callback: (data: { pots: Array<[number | string | bigint, Array<ActorId>, PotShare]> }) => void | Promise<void>): Promise<() => void> {
     return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
       if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
         return;
@@ -786,7 +1270,7 @@ export class Poker {
 
       const payload = message.payload.toHex();
       if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'Finished') {
-        callback(this._program.registry.createType('(String, String, {"pots":"Vec<(u128, Vec<[u8;32]>)>"})', message.payload)[2].toJSON() as unknown as { pots: Array<[number | string | bigint, Array<ActorId>]> });
+        callback(this._program.registry.createType('(String, String, {"pots":"Vec<(u128, Vec<[u8;32]>, PotShare)>"})', message.payload)[2].toJSON() as unknown as { pots: Array<[number | string | bigint, Array<ActorId>, PotShare]> });
       }
     });
   }
@@ -843,6 +1327,71 @@ export class Poker {
     });
   }
 
+  public subscribeToCardsMuckedEvent(callback: (data: { player_id: ActorId }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'CardsMucked') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId });
+      }
+    });
+  }
+
+  public subscribeToCardsShownEvent(callback: (data: { player_id: ActorId; cards: Array<Card> }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'CardsShown') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]","cards":"Vec<Card>"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId; cards: Array<Card> });
+      }
+    });
+  }
+
+  public subscribeToRunItTwiceAgreedEvent(callback: (data: { player_id: ActorId }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'RunItTwiceAgreed') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId });
+      }
+    });
+  }
+
+  public subscribeToRabbitHuntedEvent(callback: (data: { cards: Array<Card> }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'RabbitHunted') {
+        callback(this._program.registry.createType('(String, String, {"cards":"Vec<Card>"})', message.payload)[2].toJSON() as unknown as { cards: Array<Card> });
+      }
+    });
+  }
+
+  public subscribeToCardsDiscardedEvent(callback: (data: { player_id: ActorId; count: number }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'CardsDiscarded') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]","count":"u8"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId; count: number });
+      }
+    });
+  }
+
   public subscribeToGameCanceledEvent(callback: (data: null) => void | Promise<void>): Promise<() => void> {
     return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
       if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
@@ -882,7 +1431,7 @@ export class Poker {
     });
   }
 
-  public subscribeToRegisteredToTheNextRoundEvent(callback: (data: { participant_id: ActorId; pk: ZkPublicKey }) => void | Promise<void>): Promise<() => void> {
+  public subscribeToRegisteredToTheNextRoundEvent(callback: (data: { participant_id: ActorId; pk: ZkPublicKey; seat: number }) => void | Promise<void>): Promise<() => void> {
     return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
       if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
         return;
@@ -890,7 +1439,85 @@ export class Poker {
 
       const payload = message.payload.toHex();
       if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'RegisteredToTheNextRound') {
-        callback(this._program.registry.createType('(String, String, {"participant_id":"[u8;32]","pk":"ZkPublicKey"})', message.payload)[2].toJSON() as unknown as { participant_id: ActorId; pk: ZkPublicKey });
+        callback(this._program.registry.createType('(String, String, {"participant_id":"[u8;32]","pk":"ZkPublicKey","seat":"u8"})', message.payload)[2].toJSON() as unknown as { participant_id: ActorId; pk: ZkPublicKey; seat: number });
+      }
+    });
+  }
+
+  public subscribeToPlayerTimedOutEvent(callback: (data: { player_id: ActorId; forfeited: number | string | bigint }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'PlayerTimedOut') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]","forfeited":"u128"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId; forfeited: number | string | bigint });
+      }
+    });
+  }
+
+  public subscribeToBlindsRaisedEvent(callback: (data: { level: number; small_blind: number | string | bigint; big_blind: number | string | bigint }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'BlindsRaised') {
+        callback(this._program.registry.createType('(String, String, {"level":"u32","small_blind":"u128","big_blind":"u128"})', message.payload)[2].toJSON() as unknown as { level: number; small_blind: number | string | bigint; big_blind: number | string | bigint });
+      }
+    });
+  }
+
+  public subscribeToPlayerEliminatedEvent(callback: (data: { player_id: ActorId; place: number }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'PlayerEliminated') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]","place":"u32"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId; place: number });
+      }
+    });
+  }
+
+  public subscribeToTournamentFinishedEvent(callback: (data: { standings: Array<[ActorId, number | string | bigint]> }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'TournamentFinished') {
+        callback(this._program.registry.createType('(String, String, {"standings":"Vec<([u8;32], u128)>"})', message.payload)[2].toJSON() as unknown as { standings: Array<[ActorId, number | string | bigint]> });
+      }
+    });
+  }
+
+  public subscribeToPrizesPaidEvent(callback: (data: null) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'PrizesPaid') {
+        callback(null);
+      }
+    });
+  }
+
+  public subscribeToStraddleIsSetEvent(callback: (data: { player_id: ActorId; amount: number | string | bigint }) => void | Promise<void>): Promise<() => void> {
+    return this._program.api.gearEvents.subscribeToGearEvent('UserMessageSent', ({ data: { message } }) => {;
+      if (!message.source.eq(this._program.programId) || !message.destination.eq(ZERO_ADDRESS)) {
+        return;
+      }
+
+      const payload = message.payload.toHex();
+      if (getServiceNamePrefix(payload) === 'Poker' && getFnNamePrefix(payload) === 'StraddleIsSet') {
+        callback(this._program.registry.createType('(String, String, {"player_id":"[u8;32]","amount":"u128"})', message.payload)[2].toJSON() as unknown as { player_id: ActorId; amount: number | string | bigint });
       }
     });
   }
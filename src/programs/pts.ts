@@ -9,6 +9,7 @@ export class PtsProgram {
 
   constructor(public api: GearApi, programId?: `0x${string}`) {
     const types: Record<string, any> = {
+      PtsError: {"_enum":["TimeHasNotExpired","AccessDenied","TransferToSelf","AccountNotFound","LowBalance"]},
     }
 
     this.registry = new TypeRegistry();
@@ -66,9 +67,9 @@ export class PtsProgram {
 export class Pts {
   constructor(private _program: PtsProgram) {}
 
-  public addAdmin(new_admin: ActorId): TransactionBuilder<null> {
+  public addAdmin(new_admin: ActorId): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -76,14 +77,14 @@ export class Pts {
       'AddAdmin',
       new_admin,
       '[u8;32]',
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
 
-  public batchTransfer(from: ActorId, to_ids: Array<ActorId>, amounts: Array<number | string | bigint>): TransactionBuilder<null> {
+  public batchTransfer(from: ActorId, to_ids: Array<ActorId>, amounts: Array<number | string | bigint>): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -91,14 +92,14 @@ export class Pts {
       'BatchTransfer',
       [from, to_ids, amounts],
       '([u8;32], Vec<[u8;32]>, Vec<u128>)',
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
 
-  public changeAccrual(new_accrual: number | string | bigint): TransactionBuilder<null> {
+  public changeAccrual(new_accrual: number | string | bigint): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -106,14 +107,14 @@ export class Pts {
       'ChangeAccrual',
       new_accrual,
       'u128',
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
 
-  public changeTimeBetweenBalanceReceipt(new_time_between_balance_receipt: number | string | bigint): TransactionBuilder<null> {
+  public changeTimeBetweenBalanceReceipt(new_time_between_balance_receipt: number | string | bigint): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -121,14 +122,14 @@ export class Pts {
       'ChangeTimeBetweenBalanceReceipt',
       new_time_between_balance_receipt,
       'u64',
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
 
-  public deleteAdmin(admin: ActorId): TransactionBuilder<null> {
+  public deleteAdmin(admin: ActorId): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -136,14 +137,14 @@ export class Pts {
       'DeleteAdmin',
       admin,
       '[u8;32]',
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
 
-  public getAccural(): TransactionBuilder<null> {
+  public getAccural(): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -151,14 +152,14 @@ export class Pts {
       'GetAccural',
       undefined,
       undefined,
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
 
-  public transfer(from: ActorId, to: ActorId, amount: number | string | bigint): TransactionBuilder<null> {
+  public transfer(from: ActorId, to: ActorId, amount: number | string | bigint): TransactionBuilder<{ ok: null } | { err: PtsError }> {
     if (!this._program.programId) throw new Error('Program ID is not set');
-    return new TransactionBuilder<null>(
+    return new TransactionBuilder<{ ok: null } | { err: PtsError }>(
       this._program.api,
       this._program.registry,
       'send_message',
@@ -166,7 +167,7 @@ export class Pts {
       'Transfer',
       [from, to, amount],
       '([u8;32], [u8;32], u128)',
-      'Null',
+      'Result<Null, PtsError>',
       this._program.programId
     );
   }
@@ -201,7 +202,7 @@ export class Pts {
     return result[2].toJSON() as unknown as Array<ActorId>;
   }
 
-  public async getBalance(id: ActorId, originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<bigint> {
+  public async getBalance(id: ActorId, originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<{ ok: number | string | bigint } | { err: PtsError }> {
     const payload = this._program.registry.createType('(String, String, [u8;32])', ['Pts', 'GetBalance', id]).toHex();
     const reply = await this._program.api.message.calculateReply({
       destination: this._program.programId,
@@ -212,11 +213,11 @@ export class Pts {
       at: atBlock,
     });
     throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
-    const result = this._program.registry.createType('(String, String, u128)', reply.payload);
-    return result[2].toBigInt() as unknown as bigint;
+    const result = this._program.registry.createType('(String, String, Result<u128, PtsError>)', reply.payload);
+    return result[2].toJSON() as unknown as { ok: number | string | bigint } | { err: PtsError };
   }
 
-  public async getRemainingTimeMs(id: ActorId, originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number | string | bigint | null> {
+  public async getRemainingTimeMs(id: ActorId, originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<{ ok: number | string | bigint | null } | { err: PtsError }> {
     const payload = this._program.registry.createType('(String, String, [u8;32])', ['Pts', 'GetRemainingTimeMs', id]).toHex();
     const reply = await this._program.api.message.calculateReply({
       destination: this._program.programId,
@@ -227,8 +228,8 @@ export class Pts {
       at: atBlock,
     });
     throwOnErrorReply(reply.code, reply.payload.toU8a(), this._program.api.specVersion, this._program.registry);
-    const result = this._program.registry.createType('(String, String, Option<u64>)', reply.payload);
-    return result[2].toJSON() as unknown as number | string | bigint | null;
+    const result = this._program.registry.createType('(String, String, Result<Option<u64>, PtsError>)', reply.payload);
+    return result[2].toJSON() as unknown as { ok: number | string | bigint | null } | { err: PtsError };
   }
 
   public async timeMsBetweenBalanceReceipt(originAddress?: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<bigint> {
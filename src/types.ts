@@ -17,6 +17,11 @@ export interface ChaumPedersenProofBytes {
   z: `0x${string}`;
 }
 
+export interface SchnorrProofBytes {
+  a: Array<`0x${string}`>;
+  z: `0x${string}`;
+}
+
 export interface ProofBytes {
   a: `0x${string}`;
   b: `0x${string}`;
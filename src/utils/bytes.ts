@@ -24,6 +24,10 @@ export function toHexString(bytes: Uint8Array): `0x${string}` {
   return ("0x" + [...bytes].map((b) => b.toString(16).padStart(2, "0")).join("")) as `0x${string}`;
 }
 
+export function hexToBytes(hex: `0x${string}`): Uint8Array {
+  return Uint8Array.from(Buffer.from(hex.slice(2), "hex"));
+}
+
 export function bigintToBytes48(x: string): Uint8Array {
   const hex = BigInt(x).toString(16).padStart(96, '0');
   return Uint8Array.from(Buffer.from(hex, 'hex'));
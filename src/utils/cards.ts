@@ -3,13 +3,13 @@ import { ECPoint, Card, CipherCard } from "../types.js";
 import { toAffine } from "./ec.js";
 import { littleEndianHexToBigInt } from "./bytes.js";
 
-export function buildCardMap(deck: bigint[][]): Card[] {
-  const n = SUITS.length * RANKS.length;
+export function buildCardMap(deck: bigint[][], ranks: string[] = RANKS): Card[] {
+  const n = SUITS.length * ranks.length;
   if (deck[0].length !== n) throw new Error(`Deck size mismatch: expected ${n}, got ${deck[0].length}`);
   const cards: Card[] = [];
-  for (let s=0;s<SUITS.length;s++) for (let r=0;r<RANKS.length;r++) {
-    const i = s*RANKS.length + r;
-    cards.push({ suit: SUITS[s], rank: RANKS[r], point: { X: deck[3][i], Y: deck[4][i], Z: deck[5][i] }});
+  for (let s=0;s<SUITS.length;s++) for (let r=0;r<ranks.length;r++) {
+    const i = s*ranks.length + r;
+    cards.push({ suit: SUITS[s], rank: ranks[r], point: { X: deck[3][i], Y: deck[4][i], Z: deck[5][i] }});
   }
   return cards;
 }
@@ -1,4 +1,4 @@
-import { ProofBytes, ChaumPedersenProofBytes } from "../types.js";
+import { ProofBytes, ChaumPedersenProofBytes, SchnorrProofBytes } from "../types.js";
 import { numberToLittleEndianBytes, toHexString, bigintToBytes48 } from "./bytes.js";
 
 export function cpProofToBytes(proof: {
@@ -13,6 +13,16 @@ export function cpProofToBytes(proof: {
   };
 }
 
+export function skProofToBytes(proof: {
+  A: { X: bigint; Y: bigint; Z: bigint };
+  z: bigint;
+}): SchnorrProofBytes {
+  return {
+    a: [numberToLittleEndianBytes(proof.A.X), numberToLittleEndianBytes(proof.A.Y), numberToLittleEndianBytes(proof.A.Z)],
+    z: numberToLittleEndianBytes(proof.z),
+  };
+}
+
 export function encodeProof(proof: {
   pi_a: string[];
   pi_b: string[][];
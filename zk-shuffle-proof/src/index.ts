@@ -16,6 +16,11 @@ export {
     hashToFr,
     cpProve, 
     cpVerify,
+    skProve,
+    decryptionTranscript,
+    shuffleNonce,
+    DECRYPTION_PROOF_VERSION,
+    CardSlot,
   } from './shuffle/utilities';
   
   export {
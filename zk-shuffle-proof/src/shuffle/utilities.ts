@@ -17,6 +17,21 @@ const d = 4502236312459181567250950091368687617548806382931946690077670179107461
 const FQ_BYTES = 32; 
 const FR_BYTES = 32;
 
+// Domain tag of the proof of knowledge of a player's secret key (must match the contract)
+const SK_POK_DOMAIN = utf8ToBytes('zk-mental-poker/sk-pok/v1');
+
+// Domain tag and version of the partial decryption proof transcript (must match the contract)
+const DECRYPTION_PROOF_DOMAIN = utf8ToBytes('zk-mental-poker/cp-dec');
+export const DECRYPTION_PROOF_VERSION = 1;
+
+// Domain tag of the nonce every shuffle proof carries as a public input (must match the contract)
+const SHUFFLE_NONCE_DOMAIN = utf8ToBytes('zk-mental-poker/shuffle-nonce/v1');
+
+// Position of a card whose partial decryption is proven
+export type CardSlot =
+  | { Hole: { owner: Uint8Array; index: number } }
+  | { Table: { index: number } };
+
 const base = {
   X: BigInt("0x29c132cc2c0b34c5743711777bbe42f32b79c022ad998465e1e71866a252ae18"),
   Y: BigInt("0x2a6c669eda123e0f157d8b50badcd586358cad81eee464605e3167b6cc974166"),
@@ -54,9 +69,10 @@ function bytesToBigIntLE(a: Uint8Array): bigint {
 
 export function hashToFr(
   F: any, 
-  points: { X: bigint, Y: bigint, Z: bigint }[]
+  points: { X: bigint, Y: bigint, Z: bigint }[],
+  prefix: Uint8Array[] = []
 ): bigint {
-  const chunks: Uint8Array[] = [];
+  const chunks: Uint8Array[] = [...prefix];
 
   for (const P of points) {
     const zInv = F.inv(P.Z);
@@ -71,20 +87,49 @@ export function hashToFr(
   return c;
 }
 
+// Transcript prefix a partial decryption proof is bound to:
+// tag || version (u32 LE) || lobbyId || round (u64 LE) || SCALE(slot)
+export function decryptionTranscript(
+  lobbyId: Uint8Array,
+  round: bigint,
+  slot: CardSlot
+): Uint8Array[] {
+  const version = new Uint8Array(4);
+  new DataView(version.buffer).setUint32(0, DECRYPTION_PROOF_VERSION, true);
+  const roundBytes = new Uint8Array(8);
+  new DataView(roundBytes.buffer).setBigUint64(0, round, true);
+  const slotBytes = 'Hole' in slot
+    ? concatBytes([Uint8Array.of(0), slot.Hole.owner, Uint8Array.of(slot.Hole.index)])
+    : Uint8Array.of(1, slot.Table.index);
+
+  return [DECRYPTION_PROOF_DOMAIN, version, lobbyId, roundBytes, slotBytes];
+}
+
+// Shuffle nonce of a player in a lobby's round, the same value the contract's ShuffleNonce query returns:
+// blake2b-512(tag || lobbyId || round (u64 LE) || playerId), first 32 bytes LE reduced mod q
+export function shuffleNonce(lobbyId: Uint8Array, round: bigint, playerId: Uint8Array): bigint {
+  const roundBytes = new Uint8Array(8);
+  new DataView(roundBytes.buffer).setBigUint64(0, round, true);
+
+  const digest = blake2b(concatBytes([SHUFFLE_NONCE_DOMAIN, lobbyId, roundBytes, playerId]), { dkLen: 64 });
+  return bytesToBigIntLE(digest.slice(0, FQ_BYTES)) % q;
+}
+
 export function cpProve(
   F: any, a: bigint, d: bigint,
   g: { X: bigint, Y: bigint, Z: bigint }, 
   pk: { X: bigint, Y: bigint, Z: bigint }, 
   c0: { X: bigint, Y: bigint, Z: bigint }, 
   dec: { X: bigint, Y: bigint, Z: bigint }, 
-  sk: bigint
+  sk: bigint,
+  transcript: Uint8Array[]
 ): { A: { X: bigint, Y: bigint, Z: bigint }; B: { X: bigint, Y: bigint, Z: bigint }; z: bigint } {
   const r = generateRandomScalar(50);
 
   const A = scalarMul(F, a, d, g,  r);   // r·g
   const B = scalarMul(F, a, d, c0, r);   // r·c1
 
-  const c = hashToFr(F, [g, pk, c0, dec, A, B]);
+  const c = hashToFr(F, [g, pk, c0, dec, A, B], transcript);
   const z = (r + c * sk) % scalarField;
   return { A, B, z };
 }
@@ -95,9 +140,10 @@ export function cpVerify(
   pk: { X: bigint, Y: bigint, Z: bigint }, 
   c0: { X: bigint, Y: bigint, Z: bigint }, 
   dec: { X: bigint, Y: bigint, Z: bigint },
-  proof: { A: { X: bigint, Y: bigint, Z: bigint }; B: { X: bigint, Y: bigint, Z: bigint }; z: bigint }
+  proof: { A: { X: bigint, Y: bigint, Z: bigint }; B: { X: bigint, Y: bigint, Z: bigint }; z: bigint },
+  transcript: Uint8Array[]
 ): boolean {
-  const c = hashToFr(F, [g, pk, c0, dec, proof.A, proof.B]);
+  const c = hashToFr(F, [g, pk, c0, dec, proof.A, proof.B], transcript);
   const lhs1 = scalarMul(F, a, d, g,  proof.z);
   const rhs1 = projectiveAdd(F, a, d, proof.A, scalarMul(F, a, d, pk, c));
 
@@ -107,6 +153,25 @@ export function cpVerify(
   return eqProj(F, lhs1, rhs1) && eqProj(F, lhs2, rhs2);
 }
 
+// Schnorr proof of knowledge of sk for pk = sk·g, bound to the player and the lobby
+// (the deployer address when the proof is checked on lobby init).
+export function skProve(
+  F: any, a: bigint, d: bigint,
+  g: { X: bigint, Y: bigint, Z: bigint },
+  pk: { X: bigint, Y: bigint, Z: bigint },
+  sk: bigint,
+  playerId: Uint8Array,
+  lobbyId: Uint8Array
+): { A: { X: bigint, Y: bigint, Z: bigint }; z: bigint } {
+  const r = generateRandomScalar(256) % scalarField;
+
+  const A = scalarMul(F, a, d, g, r);   // r·g
+
+  const c = hashToFr(F, [g, pk, A], [SK_POK_DOMAIN, playerId, lobbyId]);
+  const z = (r + c * sk) % scalarField;
+  return { A, z };
+}
+
 function eqProj(F: any, P: { X: bigint, Y: bigint, Z: bigint }, Q: { X: bigint, Y: bigint, Z: bigint }): boolean {
   return F.mul(P.X, Q.Z) === F.mul(Q.X, P.Z)
       && F.mul(P.Y, Q.Z) === F.mul(Q.Y, P.Z);